@@ -0,0 +1,236 @@
+//! Settings that can change while the server is running.
+//!
+//! The env file is re-read on `SIGHUP` or when its modification time changes.
+//! Values from the file replace the ones loaded at start-up, except variables that
+//! were already set in the process environment, which always win like they do
+//! with `dotenv`.
+
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime};
+
+/// How often the env file is checked for changes
+pub const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Default)]
+struct ConfigState {
+    initialized: bool,
+    env_file: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    process_keys: HashSet<String>,
+    file_values: HashMap<String, String>,
+}
+
+lazy_static! {
+    static ref STATE: RwLock<ConfigState> = RwLock::new(ConfigState::default());
+}
+
+/// Loads the env file from `env_file`, or from the default location, and
+/// remembers it for later reloads.
+pub fn init(env_file: Option<String>) {
+    let process_keys = std::env::vars().map(|(k, _)| k).collect();
+
+    let env_file = match env_file {
+        Some(path) => {
+            dotenv::from_path(&path).ok();
+            Some(PathBuf::from(path))
+        }
+        None => dotenv::dotenv().ok(),
+    };
+
+    let mut state = STATE.write().unwrap_or_else(|e| e.into_inner());
+    state.initialized = true;
+    state.process_keys = process_keys;
+    state.env_file = env_file;
+    state.modified = state.env_file.as_ref().and_then(modified);
+    state.file_values = state
+        .env_file
+        .as_ref()
+        .and_then(|p| read_env_file(p).ok())
+        .unwrap_or_default();
+}
+
+/// Current value of `name`. Use this instead of `std::env::var` for anything
+/// that should follow reloads.
+pub fn var(name: &str) -> Option<String> {
+    STATE.read().unwrap_or_else(|e| e.into_inner()).var(name)
+}
+
+/// Re-reads the env file. Returns the names of variables whose value changed.
+pub fn reload() -> anyhow::Result<Vec<String>> {
+    STATE.write().unwrap_or_else(|e| e.into_inner()).reload()
+}
+
+impl ConfigState {
+    fn var(&self, name: &str) -> Option<String> {
+        if !self.initialized || self.process_keys.contains(name) {
+            return std::env::var(name).ok();
+        }
+        self.file_values.get(name).cloned()
+    }
+
+    fn reload(&mut self) -> anyhow::Result<Vec<String>> {
+        let Some(path) = self.env_file.clone() else {
+            return Ok(Vec::new());
+        };
+
+        // Recorded even when reading fails, so a broken file is reported once
+        // per change rather than on every poll
+        self.modified = modified(&path);
+        let values = read_env_file(&path)?;
+        let mut changed: Vec<String> = values
+            .iter()
+            .filter(|(k, v)| self.file_values.get(*k) != Some(*v))
+            .map(|(k, _)| k.clone())
+            .chain(
+                self.file_values
+                    .keys()
+                    .filter(|k| !values.contains_key(*k))
+                    .cloned(),
+            )
+            .filter(|k| !self.process_keys.contains(k))
+            .collect();
+        changed.sort();
+
+        self.file_values = values;
+        Ok(changed)
+    }
+}
+
+/// Resolves on `SIGHUP` or once the env file has been modified, whichever comes first.
+pub async fn wait_for_reload() -> &'static str {
+    let file_changed = async {
+        loop {
+            tokio::time::sleep(RELOAD_POLL_INTERVAL).await;
+            let state = STATE.read().unwrap_or_else(|e| e.into_inner());
+            if let Some(path) = &state.env_file
+                && modified(path) != state.modified
+            {
+                return;
+            }
+        }
+    };
+
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::hangup()) {
+            Ok(mut hangup) => tokio::select! {
+                _ = hangup.recv() => "SIGHUP",
+                _ = file_changed => "env file change",
+            },
+            Err(e) => {
+                tracing::error!(
+                    "Failed to listen for SIGHUP, watching the env file only: {}",
+                    e
+                );
+                file_changed.await;
+                "env file change"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        file_changed.await;
+        "env file change"
+    }
+}
+
+/// Whether `tool` passes `MCP_ALLOW_TOOLS` and `MCP_DENY_TOOLS`.
+pub fn tool_enabled(tool: &str) -> bool {
+    let allow = var("MCP_ALLOW_TOOLS").unwrap_or_default();
+    let deny = var("MCP_DENY_TOOLS").unwrap_or_default();
+    (split_list(&allow).next().is_none() || split_list(&allow).any(|t| t == tool))
+        && !split_list(&deny).any(|t| t == tool)
+}
+
+/// Calls per minute allowed for `tool`, from `MCP_RATE_LIMIT`.
+pub fn rate_limit(tool: &str) -> Option<u32> {
+    parse_rate_limit(&var("MCP_RATE_LIMIT")?, tool)
+}
+
+/// A bare number is the default for every tool and `Tool=N` overrides it,
+/// e.g. `60,PostTweet=5`.
+fn parse_rate_limit(value: &str, tool: &str) -> Option<u32> {
+    let mut default = None;
+    for item in split_list(value) {
+        match item.split_once('=') {
+            Some((name, limit)) if name.trim() == tool => return limit.trim().parse().ok(),
+            Some(_) => {}
+            None => default = item.parse().ok(),
+        }
+    }
+    default
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// The replacement API writes into the process environment, which is unsafe once
+// other threads are running
+#[allow(deprecated)]
+fn read_env_file(path: &PathBuf) -> anyhow::Result<HashMap<String, String>> {
+    dotenv::from_path_iter(path)?
+        .map(|item| item.map_err(anyhow::Error::from))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rate_limit() {
+        assert_eq!(parse_rate_limit("60, PostTweet=5", "PostTweet"), Some(5));
+        assert_eq!(
+            parse_rate_limit("60, PostTweet=5", "SearchTweets"),
+            Some(60)
+        );
+        assert_eq!(parse_rate_limit("PostTweet=5", "SearchTweets"), None);
+    }
+
+    #[test]
+    fn test_reload_reports_changed_keys() {
+        let path = std::env::temp_dir().join(format!("mcp-config-{}.env", std::process::id()));
+        std::fs::write(
+            &path,
+            "MCP_TEST_TOKEN=old\nMCP_TEST_KEPT=same\nMCP_TEST_GONE=1\n",
+        )
+        .unwrap();
+
+        let mut state = ConfigState {
+            initialized: true,
+            process_keys: HashSet::from(["MCP_TEST_PROCESS".to_string()]),
+            file_values: read_env_file(&path).unwrap(),
+            env_file: Some(path.clone()),
+            ..Default::default()
+        };
+        assert_eq!(state.var("MCP_TEST_TOKEN").as_deref(), Some("old"));
+
+        std::fs::write(
+            &path,
+            "MCP_TEST_TOKEN=new\nMCP_TEST_KEPT=same\nMCP_TEST_PROCESS=ignored\n",
+        )
+        .unwrap();
+        let changed = state.reload().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(changed, ["MCP_TEST_GONE", "MCP_TEST_TOKEN"]);
+        assert_eq!(state.var("MCP_TEST_TOKEN").as_deref(), Some("new"));
+        assert_eq!(state.var("MCP_TEST_GONE"), None);
+
+        // A failed reload keeps the old values but is not retried until the
+        // file changes again
+        assert!(state.modified.is_some());
+        assert!(state.reload().is_err());
+        assert_eq!(state.modified, None);
+        assert_eq!(state.var("MCP_TEST_TOKEN").as_deref(), Some("new"));
+    }
+}
@@ -1,12 +1,27 @@
+mod config;
+#[cfg(any(feature = "arxiv", feature = "shopify", feature = "openapi"))]
+mod output;
+#[cfg(any(
+    feature = "twitter",
+    feature = "discord",
+    feature = "shopify",
+    feature = "huggingface",
+    feature = "replicate",
+    feature = "openapi"
+))]
+mod secrets;
+mod server;
 mod servers;
+mod shutdown;
 use std::env;
+use std::time::Duration;
 
 use anyhow::Result;
 use clap::{Parser, ValueEnum};
-use dotenv::dotenv;
 use mcp_core::{
+    protocol::Protocol,
     server::Server,
-    transport::{ServerSseTransport, ServerStdioTransport},
+    transport::{ServerStdioTransport, Transport},
 };
 
 #[derive(Parser)]
@@ -23,6 +38,10 @@ struct Cli {
     /// Optional path to .env file
     #[arg(short, long)]
     env_file: Option<String>,
+
+    /// Seconds to wait for in-flight tool calls after SIGINT/SIGTERM
+    #[arg(long, default_value_t = shutdown::DEFAULT_DRAIN_TIMEOUT_SECS)]
+    drain_timeout: u64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
@@ -39,6 +58,7 @@ enum ServerType {
     Shopify,
     HuggingFace,
     Replicate,
+    OpenApi,
 }
 
 #[tokio::main]
@@ -46,10 +66,18 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     // Load env file from path if provided, otherwise load from default location
-    if let Some(env_path) = cli.env_file {
-        dotenv::from_path(env_path).ok();
-    } else {
-        dotenv().ok();
+    config::init(cli.env_file);
+
+    // Set up logging first so start-up checks are reported
+    match cli.transport {
+        TransportType::Sse => tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .init(),
+        // Prevents the server from logging to stdout
+        TransportType::Stdio => tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(std::io::stderr)
+            .init(),
     }
 
     // Select the server based on the CLI argument
@@ -57,6 +85,7 @@ async fn main() -> Result<()> {
         ServerType::Arxiv => {
             #[cfg(feature = "arxiv")]
             {
+                tokio::spawn(servers::arxiv::watcher::run());
                 servers::arxiv::server::protocol()
             }
             #[cfg(not(feature = "arxiv"))]
@@ -67,6 +96,7 @@ async fn main() -> Result<()> {
         ServerType::Twitter => {
             #[cfg(feature = "twitter")]
             {
+                secrets::validate(servers::twitter::server::SECRETS).await?;
                 servers::twitter::server::protocol()
             }
             #[cfg(not(feature = "twitter"))]
@@ -77,6 +107,7 @@ async fn main() -> Result<()> {
         ServerType::Discord => {
             #[cfg(feature = "discord")]
             {
+                secrets::validate(servers::discord::server::SECRETS).await?;
                 servers::discord::server::protocol()
             }
             #[cfg(not(feature = "discord"))]
@@ -87,6 +118,7 @@ async fn main() -> Result<()> {
         ServerType::Shopify => {
             #[cfg(feature = "shopify")]
             {
+                secrets::validate(servers::shopify::server::SECRETS).await?;
                 servers::shopify::server::protocol()
             }
             #[cfg(not(feature = "shopify"))]
@@ -97,6 +129,7 @@ async fn main() -> Result<()> {
         ServerType::HuggingFace => {
             #[cfg(feature = "huggingface")]
             {
+                secrets::validate(servers::huggingface::server::SECRETS).await?;
                 servers::huggingface::server::protocol()
             }
             #[cfg(not(feature = "huggingface"))]
@@ -107,6 +140,7 @@ async fn main() -> Result<()> {
         ServerType::Replicate => {
             #[cfg(feature = "replicate")]
             {
+                secrets::validate(servers::replicate::server::SECRETS).await?;
                 servers::replicate::server::protocol()
             }
             #[cfg(not(feature = "replicate"))]
@@ -114,44 +148,165 @@ async fn main() -> Result<()> {
                 anyhow::bail!("Replicate feature is not enabled")
             }
         }
+        ServerType::OpenApi => {
+            #[cfg(feature = "openapi")]
+            {
+                servers::openapi::server::protocol().await?
+            }
+            #[cfg(not(feature = "openapi"))]
+            {
+                anyhow::bail!("OpenAPI feature is not enabled")
+            }
+        }
     };
 
-    match cli.transport {
-        TransportType::Sse => {
-            tracing_subscriber::fmt()
-                .with_max_level(tracing::Level::DEBUG)
-                .init();
-
-            let mut port = env::var("SERVER_PORT")
-                .ok()
-                .and_then(|p| p.parse::<u16>().ok())
-                .unwrap_or(3000);
-
-            // Keep trying ports until we find an available one
-            while std::net::TcpListener::bind(format!("0.0.0.0:{}", port)).is_err() {
-                if port == u16::MAX {
-                    anyhow::bail!("No available ports found");
+    tokio::spawn(watch_config(cli.transport, protocol.clone()));
+    tokio::spawn(forward_resource_updates(cli.transport, protocol.clone()));
+
+    let transport = cli.transport;
+    let server = async move {
+        match transport {
+            TransportType::Sse => {
+                let mut port = env::var("SERVER_PORT")
+                    .ok()
+                    .and_then(|p| p.parse::<u16>().ok())
+                    .unwrap_or(3000);
+
+                // Keep trying ports until we find an available one
+                while std::net::TcpListener::bind(format!("0.0.0.0:{}", port)).is_err() {
+                    if port == u16::MAX {
+                        anyhow::bail!("No available ports found");
+                    }
+                    tracing::warn!("Port {} is already in use, trying {}", port, port + 1);
+                    port += 1;
                 }
-                tracing::warn!("Port {} is already in use, trying {}", port, port + 1);
-                port += 1;
+                tracing::info!("Starting server on port {}", port);
+
+                Server::start(server::SseTransport::new(
+                    "0.0.0.0".to_string(),
+                    port,
+                    protocol,
+                ))
+                .await
             }
-            tracing::info!("Starting server on port {}", port);
+            TransportType::Stdio => Server::start(ServerStdioTransport::new(protocol)).await,
+        }
+    };
+
+    // The stdio transport reads stdin synchronously, so it runs on a blocking
+    // thread to keep the runtime free to handle shutdown signals
+    let handle = tokio::runtime::Handle::current();
+    let mut server = tokio::task::spawn_blocking(move || handle.block_on(server));
+
+    tokio::select! {
+        result = &mut server => result?,
+        signal = shutdown::wait_for_signal() => {
+            tracing::info!("Received {}, draining in-flight tool calls", signal);
 
-            Server::start(ServerSseTransport::new(
-                "0.0.0.0".to_string(),
-                port,
-                protocol,
-            ))
-            .await
+            // Non-zero when calls were abandoned, so supervisors can tell it apart
+            // from a clean shutdown
+            let code = tokio::select! {
+                remaining = shutdown::drain(Duration::from_secs(cli.drain_timeout)) => {
+                    if remaining > 0 {
+                        tracing::warn!("Drain timed out with {} tool call(s) still running", remaining);
+                        1
+                    } else {
+                        0
+                    }
+                }
+                _ = shutdown::wait_for_signal() => {
+                    tracing::warn!("Received second signal, exiting without waiting");
+                    1
+                }
+            };
+
+            // The server loop may still be blocked on stdin, so exit explicitly
+            std::process::exit(code)
         }
-        TransportType::Stdio => {
-            // Prevents the server from logging to stdout
-            tracing_subscriber::fmt()
-                .with_max_level(tracing::Level::DEBUG)
-                .with_writer(std::io::stderr)
-                .init();
-
-            Server::start(ServerStdioTransport::new(protocol)).await
+    }
+}
+
+/// Applies config changes on `SIGHUP` or env file edits and tells the client when
+/// the set of exposed tools changed.
+async fn watch_config(transport: TransportType, protocol: Protocol) {
+    let mut exposed = server::exposed_tools();
+
+    loop {
+        let trigger = config::wait_for_reload().await;
+        match config::reload() {
+            Ok(changed) if changed.is_empty() => {
+                tracing::info!("Reloaded config on {}, nothing changed", trigger);
+            }
+            Ok(changed) => {
+                tracing::info!("Reloaded config on {}: {}", trigger, changed.join(", "));
+            }
+            Err(e) => {
+                tracing::error!("Failed to reload config on {}: {}", trigger, e);
+                continue;
+            }
+        }
+
+        #[cfg(any(
+            feature = "twitter",
+            feature = "discord",
+            feature = "shopify",
+            feature = "huggingface",
+            feature = "replicate",
+            feature = "openapi"
+        ))]
+        if let Err(e) = secrets::reload().await {
+            tracing::error!("{}", e);
+        }
+
+        let current = server::exposed_tools();
+        if current == exposed {
+            continue;
+        }
+        exposed = current;
+        tracing::info!("Exposed tools changed: {}", exposed.join(", "));
+
+        let sent = match transport {
+            TransportType::Stdio => {
+                ServerStdioTransport::new(protocol.clone())
+                    .send_notification("notifications/tools/list_changed", None)
+                    .await
+            }
+            TransportType::Sse => {
+                server::notify_sse_sessions("notifications/tools/list_changed", None).await
+            }
+        };
+        if let Err(e) = sent {
+            tracing::error!("Failed to send tools/list_changed: {}", e);
+        }
+    }
+}
+
+/// Sends `notifications/resources/updated` for subscribed resources that changed.
+async fn forward_resource_updates(transport: TransportType, protocol: Protocol) {
+    let mut updates = server::resource_updates();
+    loop {
+        let uri = match updates.recv().await {
+            Ok(uri) => uri,
+            Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                tracing::warn!("Dropped {} resource update(s)", skipped);
+                continue;
+            }
+            Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+        };
+
+        let sent = match transport {
+            TransportType::Stdio => {
+                ServerStdioTransport::new(protocol.clone())
+                    .send_notification(
+                        "notifications/resources/updated",
+                        Some(serde_json::json!({ "uri": uri })),
+                    )
+                    .await
+            }
+            TransportType::Sse => server::notify_sse_subscribers(&uri).await,
+        };
+        if let Err(e) = sent {
+            tracing::error!("Failed to send resources/updated: {}", e);
         }
     }
 }
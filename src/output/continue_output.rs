@@ -0,0 +1,41 @@
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+
+use super::continue_output;
+
+#[tool(
+    name = "ContinueOutput",
+    description = "Fetch the next chunk of a tool response that was truncated for exceeding the output limit.",
+    params(cursor = "The continuation cursor from the truncation notice")
+)]
+pub async fn continue_output_tool(cursor: String) -> Result<ToolResponseContent> {
+    let chunk = continue_output(&cursor)?;
+
+    Ok(tool_text_content!(chunk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::shape_with_limit;
+
+    #[tokio::test]
+    async fn test_continue_output_tool() {
+        let text = "0123456789".repeat(10);
+        let chunk = shape_with_limit("Test", text, 40);
+        let start = chunk.rfind("cursor \"").unwrap() + "cursor \"".len();
+        let end = chunk[start..].find('"').unwrap() + start;
+
+        match continue_output_tool(chunk[start..end].to_string()).await {
+            Ok(content) => {
+                println!("Continued content: {:?}", content);
+                assert!(format!("{:?}", content).contains("0123456789"));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
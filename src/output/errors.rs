@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum McpOutputError {
+    #[error("Invalid cursor: {0}")]
+    InvalidCursor(String),
+    #[error("Cursor has expired or was evicted, re-run the original tool")]
+    ExpiredCursor,
+}
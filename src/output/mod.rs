@@ -0,0 +1,257 @@
+pub mod continue_output;
+pub mod errors;
+
+pub use continue_output::*;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use errors::McpOutputError;
+
+/// Characters returned per tool response when `MCP_MAX_OUTPUT_CHARS` is not set
+pub const DEFAULT_MAX_OUTPUT_CHARS: usize = 20_000;
+
+/// How long a truncated output can still be continued
+const OUTPUT_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Upper bound on truncated outputs kept in memory, oldest are evicted first
+const MAX_STORED_OUTPUTS: usize = 32;
+
+struct StoredOutput {
+    tool: String,
+    text: String,
+    limit: usize,
+    summary: Option<String>,
+    stored_at: Instant,
+}
+
+lazy_static! {
+    /// Keyed by 128 random bits, so an SSE client cannot guess the cursor of
+    /// another client's output
+    static ref OUTPUTS: Mutex<HashMap<String, StoredOutput>> = Mutex::new(HashMap::new());
+}
+
+/// Maximum characters per tool response, read from `MCP_MAX_OUTPUT_CHARS`.
+pub fn max_output_chars() -> usize {
+    crate::config::var("MCP_MAX_OUTPUT_CHARS")
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_MAX_OUTPUT_CHARS)
+}
+
+/// Returns `text` unchanged if it fits the configured limit. Otherwise returns the
+/// first chunk followed by a notice describing what was cut and a cursor for
+/// `ContinueOutput`.
+pub fn shape(tool: &str, text: String) -> String {
+    shape_with_limit(tool, text, max_output_chars())
+}
+
+pub fn shape_with_limit(tool: &str, text: String, limit: usize) -> String {
+    let limit = limit.max(1);
+    if text.chars().count() <= limit {
+        return text;
+    }
+
+    let key = format!("{:032x}", rand::random::<u128>());
+    let stored = StoredOutput {
+        tool: tool.to_string(),
+        summary: summarize_json(&text),
+        text,
+        limit,
+        stored_at: Instant::now(),
+    };
+    let chunk = render_chunk(&key, &stored, 0);
+
+    let mut outputs = OUTPUTS.lock().unwrap_or_else(|e| e.into_inner());
+    evict(&mut outputs);
+    outputs.insert(key, stored);
+
+    chunk
+}
+
+/// Returns the chunk addressed by a cursor previously handed out by [`shape`].
+pub fn continue_output(cursor: &str) -> Result<String, McpOutputError> {
+    let (key, offset) = decode_cursor(cursor)?;
+
+    let mut outputs = OUTPUTS.lock().unwrap_or_else(|e| e.into_inner());
+    evict(&mut outputs);
+    let stored = outputs.get(&key).ok_or(McpOutputError::ExpiredCursor)?;
+
+    if offset >= stored.text.len() || !stored.text.is_char_boundary(offset) {
+        return Err(McpOutputError::InvalidCursor(cursor.to_string()));
+    }
+
+    Ok(render_chunk(&key, stored, offset))
+}
+
+fn render_chunk(key: &str, stored: &StoredOutput, start: usize) -> String {
+    let text = &stored.text;
+    let rest = &text[start..];
+
+    let mut end = rest
+        .char_indices()
+        .nth(stored.limit)
+        .map(|(i, _)| start + i)
+        .unwrap_or(text.len());
+
+    // Prefer to cut on a line break as long as it keeps most of the chunk
+    if end < text.len()
+        && let Some(newline) = text[start..end].rfind('\n')
+        && newline + 1 > (end - start) / 2
+    {
+        end = start + newline + 1;
+    }
+
+    let mut chunk = text[start..end].to_string();
+    if end == text.len() {
+        return chunk;
+    }
+
+    let shown_from = text[..start].chars().count() + 1;
+    let shown_to = shown_from + chunk.chars().count() - 1;
+    let total = shown_to + text[end..].chars().count();
+    let remaining_lines = text[end..].lines().count();
+
+    chunk.push_str(&format!(
+        "\n\n[Output of {} truncated: showing characters {}-{} of {}. {} characters ({} lines) remain.",
+        stored.tool,
+        shown_from,
+        shown_to,
+        total,
+        total - shown_to,
+        remaining_lines
+    ));
+    if let Some(summary) = &stored.summary {
+        chunk.push_str(&format!(" The full response contains {}.", summary));
+    }
+    chunk.push_str(&format!(
+        " Call ContinueOutput with cursor \"{}\" to fetch the next chunk.]",
+        encode_cursor(key, end)
+    ));
+
+    chunk
+}
+
+/// Describes the top-level collections of a JSON document, e.g. `products (250 items)`.
+fn summarize_json(text: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    let parts: Vec<String> = match value {
+        serde_json::Value::Array(items) => vec![format!("{} items", items.len())],
+        serde_json::Value::Object(map) => map
+            .iter()
+            .filter_map(|(k, v)| v.as_array().map(|a| format!("{} ({} items)", k, a.len())))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
+
+fn evict(outputs: &mut HashMap<String, StoredOutput>) {
+    outputs.retain(|_, o| o.stored_at.elapsed() < OUTPUT_TTL);
+
+    while outputs.len() >= MAX_STORED_OUTPUTS {
+        let oldest = outputs
+            .iter()
+            .min_by_key(|(_, o)| o.stored_at)
+            .map(|(k, _)| k.clone());
+        match oldest {
+            Some(key) => outputs.remove(&key),
+            None => break,
+        };
+    }
+}
+
+fn encode_cursor(key: &str, offset: usize) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}:{}", key, offset))
+}
+
+fn decode_cursor(cursor: &str) -> Result<(String, usize), McpOutputError> {
+    let invalid = || McpOutputError::InvalidCursor(cursor.to_string());
+
+    let decoded = URL_SAFE_NO_PAD
+        .decode(cursor.trim())
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(invalid)?;
+    let (key, offset) = decoded.split_once(':').ok_or_else(invalid)?;
+    let offset = offset.parse::<usize>().map_err(|_| invalid())?;
+
+    Ok((key.to_string(), offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract_cursor(chunk: &str) -> Option<String> {
+        let start = chunk.rfind("cursor \"")? + "cursor \"".len();
+        let end = chunk[start..].find('"')? + start;
+        Some(chunk[start..end].to_string())
+    }
+
+    fn strip_notice(chunk: &str) -> &str {
+        chunk
+            .rfind("\n\n[Output of ")
+            .map(|i| &chunk[..i])
+            .unwrap_or(chunk)
+    }
+
+    #[test]
+    fn test_short_output_is_unchanged() {
+        let text = "short output".to_string();
+        assert_eq!(shape_with_limit("Test", text.clone(), 100), text);
+    }
+
+    #[test]
+    fn test_chunks_reassemble_original() {
+        let text: String = (0..200)
+            .map(|i| format!("line {} with ünïcödé\n", i))
+            .collect();
+
+        let mut chunk = shape_with_limit("Test", text.clone(), 300);
+        let mut reassembled = String::new();
+        let mut calls = 0;
+        while let Some(cursor) = extract_cursor(&chunk) {
+            assert!(chunk.contains("remain"));
+            reassembled.push_str(strip_notice(&chunk));
+            chunk = continue_output(&cursor).unwrap();
+            calls += 1;
+        }
+        reassembled.push_str(&chunk);
+
+        assert!(calls > 1);
+        assert_eq!(reassembled, text);
+    }
+
+    #[test]
+    fn test_json_summary() {
+        let items: Vec<serde_json::Value> = (0..50)
+            .map(|i| serde_json::json!({ "id": i, "title": "A product title" }))
+            .collect();
+        let text = serde_json::json!({ "products": items }).to_string();
+
+        let chunk = shape_with_limit("ListProducts", text, 200);
+        assert!(chunk.contains("products (50 items)"));
+    }
+
+    #[test]
+    fn test_invalid_cursor() {
+        assert!(matches!(
+            continue_output("not a cursor"),
+            Err(McpOutputError::InvalidCursor(_))
+        ));
+        assert!(matches!(
+            continue_output(&encode_cursor("missing", 0)),
+            Err(McpOutputError::ExpiredCursor)
+        ));
+    }
+}
@@ -0,0 +1,42 @@
+use thiserror::Error;
+
+/// Errors name the secret and the source it was read from but never include
+/// the value or anything a provider printed.
+#[derive(Debug, Error)]
+pub enum McpSecretError {
+    #[error("Secret {0} is not set")]
+    NotSet(String),
+    #[error("Secret {0} resolved to an empty value")]
+    Empty(String),
+    #[error("Secret {name} could not be read from file {path}: {source}")]
+    File {
+        name: String,
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("Secret {name} command failed: {reason}")]
+    Command { name: String, reason: String },
+    #[error("Secret {name} could not be read from encrypted store {path}: {reason}")]
+    Store {
+        name: String,
+        path: String,
+        reason: String,
+    },
+    #[error("Secret {name} has no entry `{key}` in encrypted store {path}")]
+    MissingEntry {
+        name: String,
+        key: String,
+        path: String,
+    },
+}
+
+impl McpSecretError {
+    /// Maps an unset secret to the server's own "missing" error and keeps every
+    /// other failure so the reason is not lost.
+    pub fn or_missing<E: From<McpSecretError>>(self, missing: E) -> E {
+        match self {
+            McpSecretError::NotSet(_) => missing,
+            other => other.into(),
+        }
+    }
+}
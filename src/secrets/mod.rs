@@ -0,0 +1,430 @@
+//! Credential lookup shared by all servers.
+//!
+//! A secret is read from the environment variable of the same name. The value is
+//! used as-is unless it starts with one of these prefixes:
+//!
+//! - `file:/run/secrets/token` reads the file, re-read whenever its mtime changes
+//! - `cmd:pass show discord` runs the command with `sh -c`, re-run once the result
+//!   is older than `SECRETS_CACHE_TTL` seconds. It is killed after
+//!   `SECRETS_COMMAND_TIMEOUT` seconds
+//! - `enc:DISCORD_TOKEN` looks the key up in the age passphrase-encrypted store at
+//!   `SECRETS_STORE_PATH`, unlocked with `SECRETS_PASSPHRASE`
+//!
+//! Resolved values are only kept in memory and are never logged. Variables are read
+//! through [`crate::config`], so changes to the env file apply after a reload.
+
+pub mod errors;
+
+use age::secrecy::SecretString;
+use lazy_static::lazy_static;
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use std::io::Read;
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::process::Command;
+
+use crate::config;
+
+pub use errors::McpSecretError;
+
+/// Seconds a `cmd:` result is reused when `SECRETS_CACHE_TTL` is not set
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+
+/// Seconds a `cmd:` command may run when `SECRETS_COMMAND_TIMEOUT` is not set
+pub const DEFAULT_COMMAND_TIMEOUT_SECS: u64 = 30;
+
+/// Store path used when `SECRETS_STORE_PATH` is not set
+pub const DEFAULT_STORE_PATH: &str = "secrets.age";
+
+const PASSPHRASE_VAR: &str = "SECRETS_PASSPHRASE";
+
+enum Freshness {
+    Modified(Option<SystemTime>),
+    Fetched(Instant),
+}
+
+struct CachedSecret {
+    spec: String,
+    value: String,
+    freshness: Freshness,
+}
+
+struct Store {
+    path: String,
+    modified: Option<SystemTime>,
+    entries: HashMap<String, String>,
+}
+
+lazy_static! {
+    static ref CACHE: Mutex<HashMap<String, CachedSecret>> = Mutex::new(HashMap::new());
+    /// Held while the store is decrypted, so concurrent lookups wait for one
+    /// decryption instead of each running scrypt
+    static ref STORE: tokio::sync::Mutex<Option<Store>> = tokio::sync::Mutex::new(None);
+    /// Secrets passed to `validate`, checked again on reload
+    static ref REQUIRED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+}
+
+/// Returns the current value of the secret `name`.
+pub async fn get(name: &str) -> Result<String, McpSecretError> {
+    let spec = config::var(name).ok_or_else(|| McpSecretError::NotSet(name.to_string()))?;
+    resolve(name, &spec).await
+}
+
+/// Resolves every secret in `names` so a broken reference stops the server at
+/// start-up rather than on the first tool call. Unset secrets only warn, tools
+/// still report them when called.
+pub async fn validate(names: &[&str]) -> Result<(), McpSecretError> {
+    REQUIRED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .extend(names.iter().map(|n| n.to_string()));
+
+    for name in names {
+        match get(name).await {
+            Ok(_) => {}
+            Err(McpSecretError::NotSet(_)) => tracing::warn!("Secret {} is not set", name),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Drops every cached value so `cmd:` and `enc:` secrets are fetched again, then
+/// re-checks the secrets validated at start-up.
+pub async fn reload() -> Result<(), McpSecretError> {
+    CACHE.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    *STORE.lock().await = None;
+
+    let required: Vec<String> = REQUIRED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .cloned()
+        .collect();
+    validate(&required.iter().map(String::as_str).collect::<Vec<_>>()).await
+}
+
+// Boxed since the store passphrase is itself resolved through here
+fn resolve<'a>(
+    name: &'a str,
+    spec: &'a str,
+) -> Pin<Box<dyn Future<Output = Result<String, McpSecretError>> + Send + 'a>> {
+    Box::pin(async move {
+        let value = if let Some(path) = spec.strip_prefix("file:") {
+            resolve_file(name, spec, path.trim())?
+        } else if let Some(command) = spec.strip_prefix("cmd:") {
+            resolve_command(name, spec, command.trim()).await?
+        } else if let Some(key) = spec.strip_prefix("enc:") {
+            resolve_encrypted(name, key.trim()).await?
+        } else {
+            spec.to_string()
+        };
+
+        if value.is_empty() {
+            return Err(McpSecretError::Empty(name.to_string()));
+        }
+        Ok(value)
+    })
+}
+
+fn cached(name: &str, spec: &str, is_fresh: impl Fn(&Freshness) -> bool) -> Option<String> {
+    let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .get(name)
+        .filter(|c| c.spec == spec && is_fresh(&c.freshness))
+        .map(|c| c.value.clone())
+}
+
+fn remember(name: &str, spec: &str, value: &str, freshness: Freshness) {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.insert(
+        name.to_string(),
+        CachedSecret {
+            spec: spec.to_string(),
+            value: value.to_string(),
+            freshness,
+        },
+    );
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn resolve_file(name: &str, spec: &str, path: &str) -> Result<String, McpSecretError> {
+    let mtime = modified(path);
+    if mtime.is_some()
+        && let Some(value) = cached(
+            name,
+            spec,
+            |f| matches!(f, Freshness::Modified(m) if *m == mtime),
+        )
+    {
+        return Ok(value);
+    }
+
+    let value = std::fs::read_to_string(path)
+        .map_err(|source| McpSecretError::File {
+            name: name.to_string(),
+            path: path.to_string(),
+            source,
+        })?
+        .trim()
+        .to_string();
+
+    tracing::debug!("Read secret {} from file", name);
+    remember(name, spec, &value, Freshness::Modified(mtime));
+    Ok(value)
+}
+
+fn cache_ttl() -> Duration {
+    Duration::from_secs(
+        config::var("SECRETS_CACHE_TTL")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_CACHE_TTL_SECS),
+    )
+}
+
+fn command_timeout() -> Duration {
+    Duration::from_secs(
+        config::var("SECRETS_COMMAND_TIMEOUT")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_COMMAND_TIMEOUT_SECS),
+    )
+}
+
+async fn resolve_command(name: &str, spec: &str, command: &str) -> Result<String, McpSecretError> {
+    let ttl = cache_ttl();
+    if let Some(value) = cached(
+        name,
+        spec,
+        |f| matches!(f, Freshness::Fetched(at) if at.elapsed() < ttl),
+    ) {
+        return Ok(value);
+    }
+
+    let value = run_command(command, command_timeout())
+        .await
+        .map_err(|reason| McpSecretError::Command {
+            name: name.to_string(),
+            reason,
+        })?;
+
+    tracing::debug!("Read secret {} from command", name);
+    remember(name, spec, &value, Freshness::Fetched(Instant::now()));
+    Ok(value)
+}
+
+/// Trimmed stdout of `command`, which is killed once `timeout` passes.
+async fn run_command(command: &str, timeout: Duration) -> Result<String, String> {
+    // stderr is captured and dropped since password managers may echo what they read
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(timeout, output)
+        .await
+        .map_err(|_| format!("timed out after {:?}", timeout))?
+        .map_err(|e| format!("could not start: {}", e))?;
+    if !output.status.success() {
+        return Err(output.status.to_string());
+    }
+
+    String::from_utf8(output.stdout)
+        .map(|value| value.trim().to_string())
+        .map_err(|_| "output is not valid UTF-8".to_string())
+}
+
+async fn resolve_encrypted(name: &str, key: &str) -> Result<String, McpSecretError> {
+    let path = config::var("SECRETS_STORE_PATH").unwrap_or_else(|| DEFAULT_STORE_PATH.to_string());
+    let store_error = |reason: String| McpSecretError::Store {
+        name: name.to_string(),
+        path: path.clone(),
+        reason,
+    };
+
+    let mtime = modified(&path);
+    // Held across the passphrase lookup too, which cannot need the store
+    let mut store = STORE.lock().await;
+    let current = store
+        .as_ref()
+        .filter(|s| s.path == path && mtime.is_some() && s.modified == mtime)
+        .map(|s| s.entries.get(key).cloned());
+
+    let value = match current {
+        Some(value) => value,
+        None => {
+            let passphrase = match config::var(PASSPHRASE_VAR) {
+                Some(spec) if spec.starts_with("enc:") => {
+                    return Err(store_error(format!(
+                        "{} cannot itself be an enc: reference",
+                        PASSPHRASE_VAR
+                    )));
+                }
+                Some(spec) => resolve(PASSPHRASE_VAR, &spec).await?,
+                None => return Err(store_error(format!("{} is not set", PASSPHRASE_VAR))),
+            };
+            // scrypt takes seconds by design, so it runs off the async workers
+            let read_path = path.clone();
+            let entries = tokio::task::spawn_blocking(move || {
+                let encrypted = std::fs::read(&read_path).map_err(|e| e.to_string())?;
+                decrypt_store(&encrypted, passphrase)
+            })
+            .await
+            .map_err(|e| store_error(e.to_string()))?
+            .map_err(store_error)?;
+
+            tracing::debug!(
+                "Loaded {} entries from encrypted store {}",
+                entries.len(),
+                path
+            );
+            let value = entries.get(key).cloned();
+            *store = Some(Store {
+                path: path.clone(),
+                modified: mtime,
+                entries,
+            });
+            value
+        }
+    };
+
+    value.ok_or_else(|| McpSecretError::MissingEntry {
+        name: name.to_string(),
+        key: key.to_string(),
+        path: path.clone(),
+    })
+}
+
+/// Decrypts an age file (binary or armored) and parses its `KEY=VALUE` lines.
+fn decrypt_store(encrypted: &[u8], passphrase: String) -> Result<HashMap<String, String>, String> {
+    let decryptor = age::Decryptor::new_buffered(age::armor::ArmoredReader::new(encrypted))
+        .map_err(|e| e.to_string())?;
+    if !decryptor.is_scrypt() {
+        return Err("store must be encrypted with a passphrase (age -p)".to_string());
+    }
+
+    let identity = age::scrypt::Identity::new(SecretString::from(passphrase));
+    let mut plaintext = String::new();
+    decryptor
+        .decrypt(std::iter::once(&identity as &dyn age::Identity))
+        .map_err(|e| e.to_string())?
+        .read_to_string(&mut plaintext)
+        .map_err(|e| e.to_string())?;
+
+    Ok(parse_entries(&plaintext))
+}
+
+fn parse_entries(plaintext: &str) -> HashMap<String, String> {
+    plaintext
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let unquoted = ['"', '\'']
+                .iter()
+                .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+                .unwrap_or(value);
+            (key.trim().to_string(), unquoted.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[tokio::test]
+    async fn test_file_secret_is_reread_on_rotation() {
+        let path = std::env::temp_dir().join(format!("mcp-secret-{}", std::process::id()));
+        let spec = format!("file:{}", path.display());
+
+        std::fs::write(&path, "first-token\n").unwrap();
+        assert_eq!(
+            resolve("TEST_FILE_SECRET", &spec).await.unwrap(),
+            "first-token"
+        );
+
+        std::fs::write(&path, "second-token\n").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(
+            resolve("TEST_FILE_SECRET", &spec).await.unwrap(),
+            "second-token"
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        let err = resolve("TEST_FILE_SECRET", &spec)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("TEST_FILE_SECRET"));
+    }
+
+    #[tokio::test]
+    async fn test_command_secret_hides_output_on_failure() {
+        assert_eq!(
+            resolve("TEST_CMD_SECRET", "cmd:printf 'cmd-token\\n'")
+                .await
+                .unwrap(),
+            "cmd-token"
+        );
+
+        let err = resolve("TEST_CMD_FAILURE", "cmd:echo leaked-value; exit 3")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("TEST_CMD_FAILURE"));
+        assert!(!err.contains("leaked-value"));
+
+        let err = run_command("sleep 5", Duration::from_millis(100))
+            .await
+            .unwrap_err();
+        assert!(err.contains("timed out"));
+    }
+
+    #[test]
+    fn test_encrypted_store_round_trip() {
+        let mut recipient = age::scrypt::Recipient::new(SecretString::from("hunter2".to_string()));
+        // Keeps the test fast, real stores use age's calibrated work factor
+        recipient.set_work_factor(4);
+
+        let mut encrypted = Vec::new();
+        let mut writer = age::Encryptor::with_recipients(std::iter::once(&recipient as _))
+            .unwrap()
+            .wrap_output(&mut encrypted)
+            .unwrap();
+        writer
+            .write_all(b"# tokens\nDISCORD_TOKEN=abc123\nHF_API_TOKEN = \"hf_456\"\n")
+            .unwrap();
+        writer.finish().unwrap();
+
+        let entries = decrypt_store(&encrypted, "hunter2".to_string()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries["DISCORD_TOKEN"], "abc123");
+        assert_eq!(entries["HF_API_TOKEN"], "hf_456");
+
+        assert!(decrypt_store(&encrypted, "wrong".to_string()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_only_warns_on_unset() {
+        assert!(validate(&["TEST_SECRET_THAT_IS_NEVER_SET"]).await.is_ok());
+        assert!(matches!(
+            resolve("TEST_EMPTY_SECRET", "").await,
+            Err(McpSecretError::Empty(_))
+        ));
+    }
+}
@@ -0,0 +1,886 @@
+//! Drop-in replacement for `mcp_core::server::Server::builder`.
+//!
+//! The builder exposes the same chain as mcp-core but installs its own `tools/*`
+//! handlers, so every tool call goes through the shutdown gate in [`crate::shutdown`]
+//! and the reloadable allow/deny lists and rate limits in [`crate::config`].
+//!
+//! Servers can also register read-only resources. Clients may subscribe to them,
+//! and [`resource_updated`] queues a `notifications/resources/updated` that
+//! `main` delivers over the transport, to the SSE sessions that subscribed.
+//!
+//! [`SseTransport`] replaces mcp-core's SSE transport, which keeps its sessions
+//! private, so notifications can be sent to every connected SSE client.
+
+use actix_web::{App, HttpResponse, HttpServer, web};
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+use lazy_static::lazy_static;
+use mcp_core::protocol::{Protocol, ProtocolBuilder, RequestOptions};
+use mcp_core::tools::ToolHandlerFn;
+use mcp_core::transport::{
+    JsonRpcError, JsonRpcMessage, JsonRpcNotification, JsonRpcResponse, RequestId, Transport,
+};
+use mcp_core::types::{
+    CallToolRequest, CallToolResponse, Implementation, InitializeRequest, InitializeResponse,
+    LATEST_PROTOCOL_VERSION, ListRequest, ReadResourceRequest, Resource, ResourceCapabilities,
+    ResourcesListResponse, ServerCapabilities, Tool, ToolResponseContent, ToolsListResponse,
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc};
+
+use crate::{config, shutdown};
+
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Messages queued per SSE session before senders wait
+const SSE_BUFFER: usize = 100;
+
+/// How often idle SSE streams are pinged
+const SSE_PING_INTERVAL: Duration = Duration::from_secs(15);
+
+lazy_static! {
+    /// Tool names of the last built protocol, in registration order
+    static ref REGISTERED_TOOLS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    /// Tool filter of the last built protocol
+    static ref TOOL_FILTER: Mutex<Option<ToolFilter>> = Mutex::new(None);
+    /// Subscribed clients by resource URI, see [`current_client`]
+    static ref SUBSCRIPTIONS: Mutex<HashMap<String, HashSet<String>>> =
+        Mutex::new(HashMap::new());
+    static ref RESOURCE_UPDATES: broadcast::Sender<String> = broadcast::channel(64).0;
+    /// Open SSE streams by session ID
+    static ref SSE_SESSIONS: Mutex<HashMap<String, mpsc::Sender<JsonRpcMessage>>> =
+        Mutex::new(HashMap::new());
+}
+
+tokio::task_local! {
+    /// SSE session of the request being handled
+    static SSE_SESSION: String;
+}
+
+/// The client of the request being handled: its SSE session ID, or an empty
+/// string for the single stdio client.
+fn current_client() -> String {
+    SSE_SESSION.try_with(Clone::clone).unwrap_or_default()
+}
+
+/// Registered tools that pass the current allow/deny lists and tool filter.
+pub fn exposed_tools() -> Vec<String> {
+    let filter = TOOL_FILTER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    REGISTERED_TOOLS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter(|name| is_exposed(&filter, name))
+        .cloned()
+        .collect()
+}
+
+fn is_exposed(filter: &Option<ToolFilter>, tool: &str) -> bool {
+    config::tool_enabled(tool) && filter.as_ref().is_none_or(|f| f(tool))
+}
+
+/// Start times of recent calls per tool, used for `MCP_RATE_LIMIT`
+type CallLog = Mutex<HashMap<String, VecDeque<Instant>>>;
+
+/// A tool handler that may capture state, unlike mcp-core's `ToolHandlerFn`.
+pub type ToolHandler = Arc<
+    dyn Fn(CallToolRequest) -> Pin<Box<dyn Future<Output = CallToolResponse> + Send>> + Send + Sync,
+>;
+
+/// Decides on every `tools/list` and `tools/call` whether a tool is exposed, on
+/// top of the allow/deny lists. Reads its settings through [`crate::config`] to
+/// follow reloads.
+pub type ToolFilter = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Returns the current text of a resource.
+pub type ResourceReader =
+    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Result<String>> + Send>> + Send + Sync>;
+
+#[derive(Debug, Deserialize)]
+struct SubscribeRequest {
+    uri: String,
+}
+
+/// Tells the client that the resource at `uri` changed, if it subscribed to it.
+#[cfg(feature = "arxiv")]
+pub fn resource_updated(uri: &str) {
+    let subscribed = SUBSCRIPTIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(uri)
+        .is_some_and(|clients| !clients.is_empty());
+    if subscribed {
+        // Fails only when nothing is listening, e.g. in tests
+        let _ = RESOURCE_UPDATES.send(uri.to_string());
+    }
+}
+
+/// URIs of subscribed resources as they change.
+pub fn resource_updates() -> broadcast::Receiver<String> {
+    RESOURCE_UPDATES.subscribe()
+}
+
+pub struct Server;
+
+impl Server {
+    pub fn builder(name: String, version: String) -> ServerProtocolBuilder {
+        ServerProtocolBuilder::new(name, version)
+    }
+}
+
+pub struct ServerProtocolBuilder {
+    server_info: Implementation,
+    capabilities: ServerCapabilities,
+    tools: Vec<(Tool, ToolHandler)>,
+    tool_filter: Option<ToolFilter>,
+    resources: Vec<(Resource, ResourceReader)>,
+}
+
+impl ServerProtocolBuilder {
+    pub fn new(name: String, version: String) -> Self {
+        ServerProtocolBuilder {
+            server_info: Implementation { name, version },
+            capabilities: ServerCapabilities::default(),
+            tools: Vec::new(),
+            tool_filter: None,
+            resources: Vec::new(),
+        }
+    }
+
+    pub fn capabilities(mut self, capabilities: ServerCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    pub fn register_tool(self, tool: Tool, f: ToolHandlerFn) -> Self {
+        self.register_tool_handler(tool, Arc::new(f))
+    }
+
+    /// Registers a tool whose handler is built at runtime, e.g. from a spec file.
+    pub fn register_tool_handler(mut self, tool: Tool, handler: ToolHandler) -> Self {
+        self.tools.retain(|(t, _)| t.name != tool.name);
+        self.tools.push((tool, handler));
+        self
+    }
+
+    /// Hides registered tools for which `filter` returns false.
+    #[cfg(feature = "openapi")]
+    pub fn tool_filter(mut self, filter: ToolFilter) -> Self {
+        self.tool_filter = Some(filter);
+        self
+    }
+
+    /// Registers a read-only resource. Its reader runs on every `resources/read`.
+    #[cfg(feature = "arxiv")]
+    pub fn register_resource(mut self, resource: Resource, reader: ResourceReader) -> Self {
+        self.resources.retain(|(r, _)| r.uri != resource.uri);
+        self.resources.push((resource, reader));
+        self
+    }
+
+    pub fn build(mut self) -> Protocol {
+        // The tool list can change on config reload
+        if let Some(Value::Object(tools)) = &mut self.capabilities.tools {
+            tools.insert("listChanged".to_string(), json!(true));
+        }
+        *REGISTERED_TOOLS.lock().unwrap_or_else(|e| e.into_inner()) =
+            self.tools.iter().map(|(t, _)| t.name.clone()).collect();
+        *TOOL_FILTER.lock().unwrap_or_else(|e| e.into_inner()) = self.tool_filter.clone();
+        if !self.resources.is_empty() && self.capabilities.resources.is_none() {
+            self.capabilities.resources = Some(ResourceCapabilities {
+                subscribe: Some(true),
+                list_changed: Some(false),
+            });
+        }
+
+        let initialized = Arc::new(AtomicBool::new(false));
+        let calls: Arc<CallLog> = Arc::new(Mutex::new(HashMap::new()));
+        let tools: Arc<Vec<Tool>> = Arc::new(self.tools.iter().map(|(t, _)| t.clone()).collect());
+        let list_filter = self.tool_filter.clone();
+        let call_filter = self.tool_filter;
+        let handlers: Arc<HashMap<String, ToolHandler>> =
+            Arc::new(self.tools.into_iter().map(|(t, f)| (t.name, f)).collect());
+
+        let server_info = self.server_info;
+        let capabilities = self.capabilities;
+        let init_flag = initialized.clone();
+        let list_flag = initialized.clone();
+        let call_flag = initialized.clone();
+        let resources_flag = initialized;
+        let resources = self.resources;
+
+        let builder = Protocol::builder()
+            .request_handler("initialize", move |_req: InitializeRequest| {
+                let server_info = server_info.clone();
+                let capabilities = capabilities.clone();
+                Box::pin(async move {
+                    Ok(InitializeResponse {
+                        protocol_version: LATEST_PROTOCOL_VERSION.to_string(),
+                        capabilities,
+                        server_info,
+                    })
+                })
+            })
+            .notification_handler("notifications/initialized", move |_: ()| {
+                init_flag.store(true, Ordering::SeqCst);
+                Box::pin(async move { Ok(()) })
+            })
+            .request_handler("tools/list", move |_req: ListRequest| {
+                let tools = tools.clone();
+                let filter = list_filter.clone();
+                let initialized = list_flag.load(Ordering::SeqCst);
+                Box::pin(async move {
+                    if !initialized {
+                        return Err(anyhow::anyhow!(
+                            "Client must be initialized before using tools/list"
+                        ));
+                    }
+
+                    Ok(ToolsListResponse {
+                        tools: tools
+                            .iter()
+                            .filter(|t| is_exposed(&filter, &t.name))
+                            .cloned()
+                            .collect(),
+                        next_cursor: None,
+                        meta: None,
+                    })
+                })
+            })
+            .request_handler("tools/call", move |req: CallToolRequest| {
+                let handlers = handlers.clone();
+                let calls = calls.clone();
+                let filter = call_filter.clone();
+                let initialized = call_flag.load(Ordering::SeqCst);
+                Box::pin(
+                    async move { call_tool(&handlers, &calls, &filter, initialized, req).await },
+                )
+            });
+
+        if resources.is_empty() {
+            builder.build()
+        } else {
+            with_resources(builder, resources, resources_flag).build()
+        }
+    }
+}
+
+fn with_resources(
+    builder: ProtocolBuilder,
+    resources: Vec<(Resource, ResourceReader)>,
+    initialized: Arc<AtomicBool>,
+) -> ProtocolBuilder {
+    let list: Arc<Vec<Resource>> = Arc::new(resources.iter().map(|(r, _)| r.clone()).collect());
+    let readers: Arc<HashMap<String, (Resource, ResourceReader)>> = Arc::new(
+        resources
+            .into_iter()
+            .map(|(r, f)| (r.uri.to_string(), (r, f)))
+            .collect(),
+    );
+    let read_flag = initialized.clone();
+    let subscribe_readers = readers.clone();
+
+    builder
+        .request_handler("resources/list", move |_req: ListRequest| {
+            let list = list.clone();
+            let initialized = initialized.load(Ordering::SeqCst);
+            Box::pin(async move {
+                if !initialized {
+                    return Err(anyhow::anyhow!(
+                        "Client must be initialized before using resources/list"
+                    ));
+                }
+                Ok(ResourcesListResponse {
+                    resources: list.to_vec(),
+                    next_cursor: None,
+                    meta: None,
+                })
+            })
+        })
+        .request_handler("resources/read", move |req: ReadResourceRequest| {
+            let readers = readers.clone();
+            let initialized = read_flag.load(Ordering::SeqCst);
+            Box::pin(async move {
+                if !initialized {
+                    return Err(anyhow::anyhow!(
+                        "Client must be initialized before using resources/read"
+                    ));
+                }
+                let uri = req.uri.to_string();
+                let (resource, reader) = readers
+                    .get(&uri)
+                    .ok_or_else(|| anyhow::anyhow!("Resource not found: {}", uri))?;
+                let text = reader().await?;
+                Ok(json!({
+                    "contents": [{
+                        "uri": uri,
+                        "mimeType": resource.mime_type,
+                        "text": text,
+                    }]
+                }))
+            })
+        })
+        .request_handler("resources/subscribe", move |req: SubscribeRequest| {
+            let known = subscribe_readers.contains_key(&req.uri);
+            let client = current_client();
+            Box::pin(async move {
+                if !known {
+                    return Err(anyhow::anyhow!("Resource not found: {}", req.uri));
+                }
+                SUBSCRIPTIONS
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .entry(req.uri)
+                    .or_default()
+                    .insert(client);
+                Ok(json!({}))
+            })
+        })
+        .request_handler("resources/unsubscribe", move |req: SubscribeRequest| {
+            let client = current_client();
+            Box::pin(async move {
+                let mut subscriptions = SUBSCRIPTIONS.lock().unwrap_or_else(|e| e.into_inner());
+                if let Some(clients) = subscriptions.get_mut(&req.uri) {
+                    clients.remove(&client);
+                    if clients.is_empty() {
+                        subscriptions.remove(&req.uri);
+                    }
+                }
+                Ok(json!({}))
+            })
+        })
+}
+
+async fn call_tool(
+    handlers: &HashMap<String, ToolHandler>,
+    calls: &CallLog,
+    filter: &Option<ToolFilter>,
+    initialized: bool,
+    req: CallToolRequest,
+) -> Result<CallToolResponse> {
+    if !initialized {
+        return Err(anyhow::anyhow!(
+            "Client must be initialized before using tools/call"
+        ));
+    }
+
+    let handler = handlers
+        .get(&req.name)
+        .filter(|_| is_exposed(filter, &req.name))
+        .ok_or_else(|| anyhow::anyhow!("Tool not found: {}", req.name))?;
+
+    if let Some(limit) = config::rate_limit(&req.name)
+        && let Some(retry_in) = check_rate_limit(calls, &req.name, limit)
+    {
+        return Ok(error_response(format!(
+            "Rate limit of {} calls per minute reached for {}, retry in {} seconds",
+            limit,
+            req.name,
+            retry_in.as_secs().max(1)
+        )));
+    }
+
+    // Held until the tool returns so shutdown can wait for it
+    let Some(_call) = shutdown::begin_call() else {
+        return Ok(error_response(
+            "Server is shutting down and no longer accepts tool calls".to_string(),
+        ));
+    };
+
+    Ok(handler(req).await)
+}
+
+/// Records a call to `tool` unless `limit` calls already started within the last
+/// minute, in which case returns how long until the oldest one leaves the window.
+fn check_rate_limit(calls: &CallLog, tool: &str, limit: u32) -> Option<Duration> {
+    let now = Instant::now();
+    let mut calls = calls.lock().unwrap_or_else(|e| e.into_inner());
+    let log = calls.entry(tool.to_string()).or_default();
+
+    while log
+        .front()
+        .is_some_and(|t| now.duration_since(*t) >= RATE_LIMIT_WINDOW)
+    {
+        log.pop_front();
+    }
+    if log.len() >= limit as usize {
+        return log
+            .front()
+            .map(|t| RATE_LIMIT_WINDOW.saturating_sub(now.duration_since(*t)));
+    }
+
+    log.push_back(now);
+    None
+}
+
+fn error_response(text: String) -> CallToolResponse {
+    CallToolResponse {
+        content: vec![ToolResponseContent::Text { text }],
+        is_error: Some(true),
+        meta: None,
+    }
+}
+
+/// Sends a notification to every connected SSE client.
+pub async fn notify_sse_sessions(method: &str, params: Option<Value>) -> Result<()> {
+    notify_sse_sessions_where(|_| true, method, params).await
+}
+
+/// Sends `notifications/resources/updated` to the SSE clients subscribed to `uri`.
+pub async fn notify_sse_subscribers(uri: &str) -> Result<()> {
+    let subscribers = SUBSCRIPTIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(uri)
+        .cloned()
+        .unwrap_or_default();
+    notify_sse_sessions_where(
+        |id| subscribers.contains(id),
+        "notifications/resources/updated",
+        Some(json!({ "uri": uri })),
+    )
+    .await
+}
+
+async fn notify_sse_sessions_where(
+    include: impl Fn(&str) -> bool,
+    method: &str,
+    params: Option<Value>,
+) -> Result<()> {
+    let sessions: Vec<(String, mpsc::Sender<JsonRpcMessage>)> = SSE_SESSIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter(|(id, _)| include(id))
+        .map(|(id, tx)| (id.clone(), tx.clone()))
+        .collect();
+
+    let mut failed = Vec::new();
+    for (id, tx) in sessions {
+        let message = JsonRpcMessage::Notification(JsonRpcNotification {
+            method: method.to_string(),
+            params: params.clone(),
+            jsonrpc: Default::default(),
+        });
+        if tx.send(message).await.is_err() {
+            failed.push(id);
+        }
+    }
+    if !failed.is_empty() {
+        anyhow::bail!("SSE session(s) closed: {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Serves MCP over SSE like mcp-core's `ServerSseTransport`: clients open
+/// `/sse`, receive a `/message?sessionId=` endpoint to post to, and get the
+/// responses and notifications on the stream.
+#[derive(Clone)]
+pub struct SseTransport {
+    host: String,
+    port: u16,
+    protocol: Protocol,
+}
+
+impl SseTransport {
+    pub fn new(host: String, port: u16, protocol: Protocol) -> Self {
+        SseTransport {
+            host,
+            port,
+            protocol,
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for SseTransport {
+    async fn open(&self) -> Result<()> {
+        let transport = self.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(web::Data::new(transport.clone()))
+                .route("/sse", web::get().to(sse_handler))
+                .route("/message", web::post().to(message_handler))
+        })
+        // SIGINT and SIGTERM are handled in `main` so tool calls can drain
+        .disable_signals()
+        .bind((self.host.clone(), self.port))?
+        .run();
+
+        server
+            .await
+            .map_err(|e| anyhow::anyhow!("Server error: {:?}", e))
+    }
+
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn poll_message(&self) -> Result<Option<JsonRpcMessage>> {
+        Ok(None)
+    }
+
+    fn request(
+        &self,
+        _method: &str,
+        _params: Option<Value>,
+        _options: RequestOptions,
+    ) -> Pin<Box<dyn Future<Output = Result<JsonRpcResponse>> + Send + Sync>> {
+        Box::pin(async { anyhow::bail!("Requests to SSE clients are not supported") })
+    }
+
+    async fn send_notification(&self, method: &str, params: Option<Value>) -> Result<()> {
+        notify_sse_sessions(method, params).await
+    }
+
+    async fn send_response(
+        &self,
+        _id: RequestId,
+        _result: Option<Value>,
+        _error: Option<JsonRpcError>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Removes its session and subscriptions once the SSE stream holding it is
+/// dropped.
+struct SseSession(String);
+
+impl Drop for SseSession {
+    fn drop(&mut self) {
+        SSE_SESSIONS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.0);
+        SUBSCRIPTIONS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|_, clients| {
+                clients.remove(&self.0);
+                !clients.is_empty()
+            });
+        tracing::info!("SSE session {} closed", self.0);
+    }
+}
+
+#[derive(Deserialize)]
+struct MessageQuery {
+    #[serde(rename = "sessionId")]
+    session_id: String,
+}
+
+async fn sse_handler() -> HttpResponse {
+    let session_id = format!("{:032x}", rand::random::<u128>());
+    let (tx, rx) = mpsc::channel(SSE_BUFFER);
+    SSE_SESSIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(session_id.clone(), tx.clone());
+    tracing::info!("SSE session {} opened", session_id);
+
+    // Ends once the stream, and with it the receiver, is dropped
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(SSE_PING_INTERVAL).await;
+            let ping = JsonRpcMessage::Notification(JsonRpcNotification {
+                method: "ping".to_string(),
+                ..Default::default()
+            });
+            if tx.send(ping).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let endpoint = format!(
+        "event: endpoint\ndata: /message?sessionId={}\n\n",
+        session_id
+    );
+    let messages = futures::stream::unfold(
+        (rx, SseSession(session_id.clone())),
+        |(mut rx, session)| async move {
+            let message = rx.recv().await?;
+            let data = serde_json::to_string(&message).ok()?;
+            let event = web::Bytes::from(format!("event: message\ndata: {}\n\n", data));
+            Some((Ok::<_, std::convert::Infallible>(event), (rx, session)))
+        },
+    );
+    let stream =
+        futures::stream::once(async move { Ok(web::Bytes::from(endpoint)) }).chain(messages);
+
+    HttpResponse::Ok()
+        .append_header(("X-Session-Id", session_id))
+        .content_type("text/event-stream")
+        .streaming(stream)
+}
+
+async fn message_handler(
+    query: web::Query<MessageQuery>,
+    message: web::Json<JsonRpcMessage>,
+    transport: web::Data<SseTransport>,
+) -> HttpResponse {
+    let session_id = query.into_inner().session_id;
+    let tx = SSE_SESSIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&session_id)
+        .cloned();
+    let Some(tx) = tx else {
+        return HttpResponse::NotFound().body(format!("Session {} not found", session_id));
+    };
+
+    match message.into_inner() {
+        JsonRpcMessage::Request(request) => {
+            // Lets subscriptions made by this request be tied to the session
+            let response = SSE_SESSION
+                .scope(
+                    session_id.clone(),
+                    transport.protocol.handle_request(request),
+                )
+                .await;
+            if tx.send(JsonRpcMessage::Response(response)).await.is_err() {
+                tracing::error!("Failed to send response to SSE session {}", session_id);
+                return HttpResponse::InternalServerError().finish();
+            }
+        }
+        JsonRpcMessage::Response(response) => transport.protocol.handle_response(response).await,
+        JsonRpcMessage::Notification(notification) => {
+            transport.protocol.handle_notification(notification).await
+        }
+    }
+    HttpResponse::Accepted().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::transport::{JsonRpcNotification, JsonRpcRequest};
+
+    fn echo_tool() -> Tool {
+        Tool {
+            name: "Echo".to_string(),
+            description: None,
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+        }
+    }
+
+    fn echo_call() -> ToolHandlerFn {
+        |req: CallToolRequest| {
+            Box::pin(async move {
+                CallToolResponse {
+                    content: vec![ToolResponseContent::Text {
+                        text: serde_json::to_string(&req.arguments).unwrap_or_default(),
+                    }],
+                    is_error: None,
+                    meta: None,
+                }
+            })
+        }
+    }
+
+    fn request(id: u64, method: &str, params: serde_json::Value) -> JsonRpcRequest {
+        JsonRpcRequest {
+            id,
+            method: method.to_string(),
+            params: Some(params),
+            jsonrpc: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_protocol_lists_and_calls_tools() {
+        let protocol = Server::builder("test".to_string(), "0.1.0".to_string())
+            .register_tool(echo_tool(), echo_call())
+            .build();
+
+        let early = protocol
+            .handle_request(request(1, "tools/list", json!({})))
+            .await;
+        assert!(early.error.is_some());
+
+        protocol
+            .handle_notification(JsonRpcNotification {
+                method: "notifications/initialized".to_string(),
+                ..Default::default()
+            })
+            .await;
+
+        let list = protocol
+            .handle_request(request(2, "tools/list", json!({})))
+            .await;
+        assert_eq!(list.result.unwrap()["tools"][0]["name"], "Echo");
+
+        let call = protocol
+            .handle_request(request(
+                3,
+                "tools/call",
+                json!({ "name": "Echo", "arguments": { "text": "hi" } }),
+            ))
+            .await;
+        assert!(
+            call.result.unwrap()["content"][0]["text"]
+                .as_str()
+                .unwrap()
+                .contains("hi")
+        );
+
+        let missing = protocol
+            .handle_request(request(4, "tools/call", json!({ "name": "Missing" })))
+            .await;
+        assert!(missing.error.is_some());
+    }
+
+    #[cfg(feature = "arxiv")]
+    #[tokio::test]
+    async fn test_protocol_reads_and_subscribes_resources() {
+        let uri = "test://resources/greeting";
+        let protocol = Server::builder("test".to_string(), "0.1.0".to_string())
+            .register_resource(
+                Resource {
+                    uri: uri.parse().unwrap(),
+                    name: "Greeting".to_string(),
+                    description: None,
+                    mime_type: Some("text/plain".to_string()),
+                },
+                Arc::new(|| Box::pin(async { Ok("hello".to_string()) })),
+            )
+            .build();
+        protocol
+            .handle_notification(JsonRpcNotification {
+                method: "notifications/initialized".to_string(),
+                ..Default::default()
+            })
+            .await;
+
+        let list = protocol
+            .handle_request(request(1, "resources/list", json!({})))
+            .await;
+        assert_eq!(list.result.unwrap()["resources"][0]["uri"], uri);
+
+        let read = protocol
+            .handle_request(request(2, "resources/read", json!({ "uri": uri })))
+            .await;
+        assert_eq!(read.result.unwrap()["contents"][0]["text"], "hello");
+
+        let mut updates = resource_updates();
+        resource_updated(uri);
+        assert!(updates.try_recv().is_err());
+
+        let subscribe = protocol
+            .handle_request(request(3, "resources/subscribe", json!({ "uri": uri })))
+            .await;
+        assert!(subscribe.error.is_none());
+        resource_updated(uri);
+        assert_eq!(updates.try_recv().unwrap(), uri);
+
+        let missing = protocol
+            .handle_request(request(
+                4,
+                "resources/subscribe",
+                json!({ "uri": "test://missing" }),
+            ))
+            .await;
+        assert!(missing.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_notify_sse_sessions() {
+        let (tx, mut rx) = mpsc::channel(SSE_BUFFER);
+        let session = SseSession("test-notify-session".to_string());
+        SSE_SESSIONS.lock().unwrap().insert(session.0.clone(), tx);
+
+        notify_sse_sessions("notifications/tools/list_changed", None)
+            .await
+            .unwrap();
+        match rx.recv().await {
+            Some(JsonRpcMessage::Notification(n)) => {
+                assert_eq!(n.method, "notifications/tools/list_changed")
+            }
+            other => panic!("Expected a notification, got {:?}", other),
+        }
+
+        // Closing the stream ends the session
+        drop(session);
+        assert!(
+            !SSE_SESSIONS
+                .lock()
+                .unwrap()
+                .contains_key("test-notify-session")
+        );
+    }
+
+    #[cfg(feature = "arxiv")]
+    #[tokio::test]
+    async fn test_notify_sse_subscribers() {
+        let uri = "test://resources/sse";
+        let protocol = Server::builder("test".to_string(), "0.1.0".to_string())
+            .register_resource(
+                Resource {
+                    uri: uri.parse().unwrap(),
+                    name: "Sse".to_string(),
+                    description: None,
+                    mime_type: None,
+                },
+                Arc::new(|| Box::pin(async { Ok(String::new()) })),
+            )
+            .build();
+        let (tx, mut subscribed_rx) = mpsc::channel(SSE_BUFFER);
+        let subscribed = SseSession("test-subscribed-session".to_string());
+        SSE_SESSIONS
+            .lock()
+            .unwrap()
+            .insert(subscribed.0.clone(), tx);
+        let (tx, mut other_rx) = mpsc::channel(SSE_BUFFER);
+        let other = SseSession("test-other-session".to_string());
+        SSE_SESSIONS.lock().unwrap().insert(other.0.clone(), tx);
+
+        let response = SSE_SESSION
+            .scope(
+                subscribed.0.clone(),
+                protocol.handle_request(request(1, "resources/subscribe", json!({ "uri": uri }))),
+            )
+            .await;
+        assert!(response.error.is_none());
+
+        notify_sse_subscribers(uri).await.unwrap();
+        match subscribed_rx.recv().await {
+            Some(JsonRpcMessage::Notification(n)) => {
+                assert_eq!(n.method, "notifications/resources/updated");
+                assert_eq!(n.params.unwrap()["uri"], uri);
+            }
+            other => panic!("Expected a notification, got {:?}", other),
+        }
+        assert!(other_rx.try_recv().is_err());
+
+        // Closing the stream drops its subscriptions
+        drop(subscribed);
+        assert!(!SUBSCRIPTIONS.lock().unwrap().contains_key(uri));
+        drop(other);
+    }
+
+    #[test]
+    fn test_rate_limit_window() {
+        let calls = CallLog::default();
+        assert!(check_rate_limit(&calls, "Echo", 2).is_none());
+        assert!(check_rate_limit(&calls, "Echo", 2).is_none());
+        assert!(check_rate_limit(&calls, "Echo", 2).is_some());
+        assert!(check_rate_limit(&calls, "Other", 2).is_none());
+
+        // Calls older than the window no longer count
+        calls.lock().unwrap().get_mut("Echo").unwrap()[0] -= RATE_LIMIT_WINDOW;
+        assert!(check_rate_limit(&calls, "Echo", 2).is_none());
+    }
+}
@@ -0,0 +1,193 @@
+use super::atom::{self, Feed};
+use super::errors::McpArxivError;
+use super::query::{Clause, QuerySpec};
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::output;
+
+/// The API refuses slices larger than this in one request
+const MAX_RESULTS_LIMIT: u32 = 2000;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AdvancedSearchResult {
+    /// Compiled `search_query`, handy for refining the search by hand
+    query: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_start: Option<u64>,
+    #[serde(flatten)]
+    feed: Feed,
+}
+
+#[tool(
+    name = "AdvancedSearch",
+    description = "Search arXiv with structured fields instead of raw query syntax. Set fields are combined with AND; authors must all match, categories may match any. Category codes are checked against the arXiv taxonomy. Returns JSON with the compiled query, totalResults, nextStart (when more results exist) and the matching papers.",
+    params(
+        title = "Phrase to match in the title",
+        abstract_text = "Phrase to match in the abstract",
+        authors = "Author names, all of which must match (e.g. ['Hinton, Geoffrey', 'LeCun'])",
+        categories = "arXiv category codes, any of which may match (e.g. ['cs.LG', 'stat.ML'])",
+        text = "Phrase to match in any field",
+        submitted_from = "Earliest submission date, YYYY-MM-DD or YYYYMMDDHHMM",
+        submitted_to = "Latest submission date, YYYY-MM-DD or YYYYMMDDHHMM",
+        groups = "Boolean groups ANDed with the other fields. A group is {\"op\": \"AND\"|\"OR\"|\"ANDNOT\", \"clauses\": [...]} where each clause is another group or a term {\"field\": \"title\"|\"abstract\"|\"author\"|\"comment\"|\"journal_ref\"|\"category\"|\"report_number\"|\"text\", \"value\": \"...\"}",
+        start = "Starting index for results (default: 0), use nextStart from a previous call to get the next page",
+        max_results = "Maximum number of results to return (default: 10, max: 2000)",
+        sort_by = "Sort field (relevance, lastUpdatedDate, submittedDate)",
+        sort_order = "Sort order (ascending, descending)"
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn advanced_search_tool(
+    title: Option<String>,
+    abstract_text: Option<String>,
+    authors: Option<Vec<String>>,
+    categories: Option<Vec<String>>,
+    text: Option<String>,
+    submitted_from: Option<String>,
+    submitted_to: Option<String>,
+    groups: Option<Vec<Value>>,
+    start: Option<u32>,
+    max_results: Option<u32>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+) -> Result<ToolResponseContent> {
+    let spec = QuerySpec {
+        title,
+        abstract_text,
+        authors: authors.unwrap_or_default(),
+        categories: categories.unwrap_or_default(),
+        text,
+        submitted_from,
+        submitted_to,
+        groups: groups
+            .unwrap_or_default()
+            .iter()
+            .map(Clause::from_value)
+            .collect::<Result<_, _>>()?,
+    };
+    let query = spec.compile()?;
+
+    let max_results = max_results.unwrap_or(10);
+    if max_results == 0 || max_results > MAX_RESULTS_LIMIT {
+        return Err(McpArxivError::InvalidQuery(format!(
+            "max_results must be between 1 and {}",
+            MAX_RESULTS_LIMIT
+        ))
+        .into());
+    }
+    if let Some(sort) = &sort_by
+        && !matches!(
+            sort.as_str(),
+            "relevance" | "lastUpdatedDate" | "submittedDate"
+        )
+    {
+        return Err(McpArxivError::InvalidQuery(format!("unknown sort_by `{}`", sort)).into());
+    }
+    if let Some(order) = &sort_order
+        && !matches!(order.as_str(), "ascending" | "descending")
+    {
+        return Err(McpArxivError::InvalidQuery(format!("unknown sort_order `{}`", order)).into());
+    }
+
+    let start = start.unwrap_or(0);
+    let mut params = vec![
+        ("search_query", query.clone()),
+        ("start", start.to_string()),
+        ("max_results", max_results.to_string()),
+    ];
+    if let Some(sort) = sort_by {
+        params.push(("sortBy", sort));
+    }
+    if let Some(order) = sort_order {
+        params.push(("sortOrder", order));
+    }
+
+    let feed = atom::query(&params).await?;
+
+    let next = start as u64 + feed.papers.len() as u64;
+    let next_start = (!feed.papers.is_empty() && next < feed.total_results).then_some(next);
+
+    Ok(tool_text_content!(output::shape(
+        "AdvancedSearch",
+        serde_json::to_string(&AdvancedSearchResult {
+            query,
+            next_start,
+            feed,
+        })
+        .map_err(McpArxivError::ResponseSerializeError)?
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_advanced_search_schema() {
+        let tool = AdvancedSearchTool::tool();
+        let properties = &tool.input_schema["properties"];
+        assert_eq!(properties["authors"]["type"], "array");
+        assert!(properties.get("groups").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_advanced_search_rejects_unknown_category() {
+        let result = advanced_search_tool(
+            None,
+            None,
+            None,
+            Some(vec!["cs.XYZ".to_string()]),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
+        assert!(result.unwrap_err().to_string().contains("unknown category"));
+    }
+
+    #[tokio::test]
+    async fn test_advanced_search_tool() {
+        match advanced_search_tool(
+            Some("transformer".to_string()),
+            None,
+            None,
+            Some(vec!["cs.CL".to_string()]),
+            None,
+            Some("2023-01-01".to_string()),
+            Some("2023-12-31".to_string()),
+            Some(vec![json!({
+                "op": "ANDNOT",
+                "clauses": [
+                    { "field": "abstract", "value": "language model" },
+                    { "field": "category", "value": "cs.CV" }
+                ]
+            })]),
+            Some(0),
+            Some(2),
+            Some("submittedDate".to_string()),
+            Some("descending".to_string()),
+        )
+        .await
+        {
+            Ok(content) => {
+                println!("Search result: {:?}", content);
+                assert!(!format!("{:?}", content).is_empty());
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
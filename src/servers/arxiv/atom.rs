@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+
+use super::client;
+use super::errors::McpArxivError;
+use super::xml::Element;
+
+/// Entry IDs of this form carry an API error instead of a paper
+const ERROR_ID_PREFIX: &str = "http://arxiv.org/api/errors";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Author {
+    pub name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub affiliations: Vec<String>,
+}
+
+/// One paper from an arXiv API query.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Paper {
+    /// arXiv ID without version, e.g. `2101.00001` or `hep-th/9901001`
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+    pub title: String,
+    pub authors: Vec<Author>,
+    #[serde(rename = "abstract")]
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_category: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub secondary_categories: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdf_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abs_url: Option<String>,
+    pub published: String,
+    pub updated: String,
+}
+
+/// A page of query results with its OpenSearch counters.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Feed {
+    pub total_results: u64,
+    pub start_index: u64,
+    pub items_per_page: u64,
+    pub papers: Vec<Paper>,
+}
+
+/// Runs a query against `export.arxiv.org/api/query` and parses the feed.
+pub async fn query(params: &[(&str, String)]) -> Result<Feed, McpArxivError> {
+    let res = client::get(&client::api_url("/api/query"), params).await?;
+
+    if !res.status().is_success() {
+        let error_text = res.text().await.map_err(McpArxivError::HttpError)?;
+        return Err(McpArxivError::ApiError(format!(
+            "ArXiv API error: {}",
+            error_text
+        )));
+    }
+
+    parse_feed(&res.text().await.map_err(McpArxivError::HttpError)?)
+}
+
+/// Fetches papers by arXiv ID, in the order given. Fails if any is missing.
+pub async fn fetch_papers(ids: &[String]) -> Result<Vec<Paper>, McpArxivError> {
+    let feed = query(&[
+        ("id_list", ids.join(",")),
+        ("max_results", ids.len().to_string()),
+    ])
+    .await?;
+
+    ids.iter()
+        .map(|id| {
+            let (base, version) = split_version(id.trim());
+            feed.papers
+                .iter()
+                .find(|p| p.id == base && (version.is_none() || p.version == version))
+                .cloned()
+                .ok_or_else(|| McpArxivError::PaperNotFound(id.clone()))
+        })
+        .collect()
+}
+
+/// Parses an Atom response from `export.arxiv.org/api/query`.
+pub fn parse_feed(xml: &str) -> Result<Feed, McpArxivError> {
+    let root = Element::parse(xml)?;
+    if root.name != "feed" {
+        return Err(McpArxivError::ParseError(format!(
+            "expected an Atom feed, found <{}>",
+            root.name
+        )));
+    }
+
+    let counter = |name: &str| {
+        root.child_text(name)
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or_default()
+    };
+
+    let mut papers = Vec::new();
+    for entry in root.children_named("entry") {
+        let id = entry.child_text("id").unwrap_or_default();
+        if id.starts_with(ERROR_ID_PREFIX) {
+            return Err(McpArxivError::ApiError(
+                entry.child_text("summary").unwrap_or(id),
+            ));
+        }
+        papers.push(parse_entry(entry, &id));
+    }
+
+    Ok(Feed {
+        total_results: counter("totalResults"),
+        start_index: counter("startIndex"),
+        items_per_page: counter("itemsPerPage"),
+        papers,
+    })
+}
+
+fn parse_entry(entry: &Element, id_url: &str) -> Paper {
+    let abs_id = id_url.rsplit_once("/abs/").map_or(id_url, |(_, id)| id);
+    let (id, version) = split_version(abs_id);
+
+    let authors = entry
+        .children_named("author")
+        .map(|author| Author {
+            name: author.child_text("name").unwrap_or_default(),
+            affiliations: author
+                .children_named("affiliation")
+                .map(Element::collapsed_text)
+                .filter(|a| !a.is_empty())
+                .collect(),
+        })
+        .collect();
+
+    let primary_category = entry
+        .child("primary_category")
+        .and_then(|c| c.attr("term"))
+        .map(str::to_string);
+    let secondary_categories = entry
+        .children_named("category")
+        .filter_map(|c| c.attr("term"))
+        .filter(|term| Some(*term) != primary_category.as_deref())
+        .map(str::to_string)
+        .collect();
+
+    let link = |matches: &dyn Fn(&Element) -> bool| {
+        entry
+            .children_named("link")
+            .find(|l| matches(l))
+            .and_then(|l| l.attr("href"))
+            .map(str::to_string)
+    };
+
+    Paper {
+        id,
+        version,
+        title: entry.child_text("title").unwrap_or_default(),
+        authors,
+        summary: entry.child_text("summary").unwrap_or_default(),
+        primary_category,
+        secondary_categories,
+        doi: entry.child_text("doi"),
+        journal_ref: entry.child_text("journal_ref"),
+        comments: entry.child_text("comment"),
+        pdf_url: link(&|l| {
+            l.attr("title") == Some("pdf") || l.attr("type") == Some("application/pdf")
+        }),
+        abs_url: link(&|l| l.attr("rel") == Some("alternate")),
+        published: entry.child_text("published").unwrap_or_default(),
+        updated: entry.child_text("updated").unwrap_or_default(),
+    }
+}
+
+/// Splits `2101.00001v2` into `("2101.00001", Some(2))`.
+pub fn split_version(id: &str) -> (String, Option<u32>) {
+    if let Some((base, version)) = id.rsplit_once('v')
+        && !base.is_empty()
+        && base.ends_with(|c: char| c.is_ascii_digit())
+        && let Ok(version) = version.parse::<u32>()
+    {
+        return (base.to_string(), Some(version));
+    }
+    (id.to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:opensearch="http://a9.com/-/spec/opensearch/1.1/" xmlns:arxiv="http://arxiv.org/schemas/atom">
+  <title type="html">ArXiv Query: search_query=all:electron</title>
+  <opensearch:totalResults>1234</opensearch:totalResults>
+  <opensearch:startIndex>10</opensearch:startIndex>
+  <opensearch:itemsPerPage>1</opensearch:itemsPerPage>
+  <entry>
+    <id>http://arxiv.org/abs/hep-th/9901001v3</id>
+    <updated>1999-01-05T12:00:00Z</updated>
+    <published>1999-01-01T12:00:00Z</published>
+    <title>Electron   Dynamics
+      in &amp; Strong Fields</title>
+    <summary>  We study electrons.
+  Twice.
+</summary>
+    <author>
+      <name>Ada Lovelace</name>
+      <arxiv:affiliation>Analytical Engine Society</arxiv:affiliation>
+    </author>
+    <author>
+      <name>Alan Turing</name>
+    </author>
+    <arxiv:doi>10.1000/xyz123</arxiv:doi>
+    <link title="doi" href="http://dx.doi.org/10.1000/xyz123" rel="related"/>
+    <arxiv:comment>12 pages, 3 figures</arxiv:comment>
+    <arxiv:journal_ref>Phys. Rev. D 1 (1999) 1</arxiv:journal_ref>
+    <link href="http://arxiv.org/abs/hep-th/9901001v3" rel="alternate" type="text/html"/>
+    <link title="pdf" href="http://arxiv.org/pdf/hep-th/9901001v3" rel="related" type="application/pdf"/>
+    <arxiv:primary_category term="hep-th" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="hep-th" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="quant-ph" scheme="http://arxiv.org/schemas/atom"/>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_parse_feed() {
+        let feed = parse_feed(FEED).unwrap();
+        assert_eq!(feed.total_results, 1234);
+        assert_eq!(feed.start_index, 10);
+        assert_eq!(feed.papers.len(), 1);
+
+        let paper = &feed.papers[0];
+        assert_eq!(paper.id, "hep-th/9901001");
+        assert_eq!(paper.version, Some(3));
+        assert_eq!(paper.title, "Electron Dynamics in & Strong Fields");
+        assert_eq!(paper.summary, "We study electrons. Twice.");
+        assert_eq!(paper.authors[0].affiliations, ["Analytical Engine Society"]);
+        assert!(paper.authors[1].affiliations.is_empty());
+        assert_eq!(paper.primary_category.as_deref(), Some("hep-th"));
+        assert_eq!(paper.secondary_categories, ["quant-ph"]);
+        assert_eq!(paper.doi.as_deref(), Some("10.1000/xyz123"));
+        assert_eq!(paper.comments.as_deref(), Some("12 pages, 3 figures"));
+        assert_eq!(
+            paper.pdf_url.as_deref(),
+            Some("http://arxiv.org/pdf/hep-th/9901001v3")
+        );
+
+        let json = serde_json::to_value(&feed).unwrap();
+        assert_eq!(json["totalResults"], 1234);
+        assert_eq!(json["papers"][0]["abstract"], "We study electrons. Twice.");
+        assert_eq!(json["papers"][0]["journalRef"], "Phys. Rev. D 1 (1999) 1");
+    }
+
+    #[test]
+    fn test_parse_error_feed() {
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+          <entry>
+            <id>http://arxiv.org/api/errors#incorrect_id_format_for_1234</id>
+            <title>Error</title>
+            <summary>incorrect id format for 1234</summary>
+          </entry>
+        </feed>"#;
+        match parse_feed(xml) {
+            Err(McpArxivError::ApiError(message)) => {
+                assert_eq!(message, "incorrect id format for 1234")
+            }
+            other => panic!("expected an API error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_split_version() {
+        assert_eq!(
+            split_version("2101.00001v12"),
+            ("2101.00001".to_string(), Some(12))
+        );
+        assert_eq!(
+            split_version("2101.00001"),
+            ("2101.00001".to_string(), None)
+        );
+        assert_eq!(
+            split_version("solv-int/9901001"),
+            ("solv-int/9901001".to_string(), None)
+        );
+    }
+}
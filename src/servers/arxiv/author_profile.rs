@@ -0,0 +1,567 @@
+use super::atom::{Author, Paper, query};
+use super::errors::McpArxivError;
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::output;
+
+const PAGE_SIZE: usize = 100;
+const MAX_PAPERS: u32 = 1000;
+/// Co-authors, categories and name variants listed per cluster
+const CLUSTER_TOP: usize = 3;
+/// Clusters at least this large suggest different people sharing the name
+const HOMONYM_CLUSTER_SIZE: usize = 2;
+/// Lowercase words that belong to the surname, as in `Ludwig van Beethoven`
+const SURNAME_PARTICLES: [&str; 14] = [
+    "da", "das", "de", "del", "della", "der", "di", "dos", "du", "la", "le", "van", "von", "ten",
+];
+
+/// A name split into given names and surname.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersonName {
+    pub given: Vec<String>,
+    pub surname: String,
+}
+
+impl PersonName {
+    /// Parses `First Middle Last` or `Last, First Middle`.
+    pub fn parse(name: &str) -> Option<PersonName> {
+        let words = |s: &str| -> Vec<String> {
+            s.split(|c: char| c.is_whitespace() || c == '.')
+                .filter(|w| !w.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let (given, surname) = match name.split_once(',') {
+            Some((surname, given)) => (words(given), words(surname)),
+            None => {
+                let mut given = words(name);
+                let mut start = given.len().checked_sub(1)?;
+                while start > 1 && SURNAME_PARTICLES.contains(&given[start - 1].as_str()) {
+                    start -= 1;
+                }
+                let surname = given.split_off(start);
+                (given, surname)
+            }
+        };
+        (!surname.is_empty()).then(|| PersonName {
+            given,
+            surname: surname.join(" "),
+        })
+    }
+
+    /// Query strings for the common ways arXiv lists this name, e.g.
+    /// `John Smith`, `Smith, John`, `J Smith` and `Smith, J`.
+    pub fn variants(&self) -> Vec<String> {
+        let initials: Vec<String> = self
+            .given
+            .iter()
+            .filter_map(|g| g.chars().next())
+            .map(String::from)
+            .collect();
+        let mut given_forms = vec![self.given.join(" ")];
+        if let Some(first) = initials.first() {
+            given_forms.push(first.clone());
+            given_forms.push(initials.join(" "));
+            if self.given.len() > 1 {
+                given_forms.push(format!("{} {}", self.given[0], initials[1..].join(" ")));
+            }
+        }
+
+        let mut variants: Vec<String> = Vec::new();
+        for given in given_forms {
+            for variant in [
+                format!("{} {}", given, self.surname),
+                format!("{}, {}", self.surname, given),
+            ] {
+                let variant = variant.trim().trim_end_matches(',').to_string();
+                if !variants.iter().any(|v| v.eq_ignore_ascii_case(&variant)) {
+                    variants.push(variant);
+                }
+            }
+        }
+        variants
+    }
+
+    /// Whether `other` could be the same person, allowing initials for given
+    /// names, accents and missing middle names.
+    pub fn matches(&self, other: &PersonName) -> bool {
+        let compatible = |a: &String, b: &String| {
+            let (a, b) = (fold(a), fold(b));
+            a == b
+                || ((a.chars().count() == 1 || b.chars().count() == 1)
+                    && a.chars().next() == b.chars().next())
+        };
+        fold(&self.surname) == fold(&other.surname)
+            && self
+                .given
+                .iter()
+                .zip(&other.given)
+                .take(2)
+                .all(|(a, b)| compatible(a, b))
+    }
+
+    /// First initial and surname, which tells co-authors apart well enough.
+    fn key(&self) -> String {
+        let initial = self.given.first().and_then(|g| fold(g).chars().next());
+        match initial {
+            Some(initial) => format!("{} {}", initial, fold(&self.surname)),
+            None => fold(&self.surname),
+        }
+    }
+}
+
+/// Lowercase letters and digits, with accents removed.
+fn fold(s: &str) -> String {
+    s.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Count {
+    pub name: String,
+    pub papers: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilePaper {
+    pub id: String,
+    pub title: String,
+    pub published: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_category: Option<String>,
+    /// The name as listed on the paper
+    pub listed_as: String,
+    pub cluster: usize,
+}
+
+/// Papers connected by shared co-authors or a common main category.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cluster {
+    pub cluster: usize,
+    pub papers: usize,
+    pub first_year: String,
+    pub last_year: String,
+    pub categories: Vec<Count>,
+    pub co_authors: Vec<Count>,
+    pub listed_as: Vec<Count>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub total_papers: usize,
+    /// Search results without a matching author, from arXiv's loose matching
+    pub unmatched_results: usize,
+    /// Whether the papers split into groups that share neither co-authors
+    /// nor a main category
+    pub likely_homonyms: bool,
+    pub listed_as: Vec<Count>,
+    pub co_authors: Vec<Count>,
+    pub categories: Vec<Count>,
+    /// Papers per year, oldest first
+    pub activity: BTreeMap<String, usize>,
+    pub clusters: Vec<Cluster>,
+    pub papers: Vec<ProfilePaper>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthorProfile {
+    name: String,
+    queried_variants: Vec<String>,
+    #[serde(flatten)]
+    profile: Profile,
+}
+
+#[tool(
+    name = "AuthorProfile",
+    description = "Build a profile of an arXiv author. Searches common variants of the name (e.g. 'John Smith', 'Smith, J'), pages through all results and keeps papers with a matching author. Returns JSON with the papers, co-authors ranked by shared papers, category distribution, papers per year, and clusters of papers with likelyHomonyms set when the name seems to belong to several people.",
+    params(
+        name = "Author name (e.g. 'John Smith' or 'Smith, John')",
+        variants = "Additional spellings to search for (e.g. ['Jon Smith'])",
+        max_papers = "Maximum number of search results to read (default: 300, at most 1000)",
+        co_authors = "Co-authors to list (default: 20)"
+    )
+)]
+pub async fn author_profile_tool(
+    name: String,
+    variants: Option<Vec<String>>,
+    max_papers: Option<u32>,
+    co_authors: Option<u32>,
+) -> Result<ToolResponseContent> {
+    let person = PersonName::parse(&name)
+        .ok_or_else(|| McpArxivError::InvalidArgument(format!("`{}` is not a name", name)))?;
+    let mut queried = person.variants();
+    for variant in variants.unwrap_or_default() {
+        let variant = variant.trim().to_string();
+        if !variant.is_empty() && !queried.contains(&variant) {
+            queried.push(variant);
+        }
+    }
+    let search_query = queried
+        .iter()
+        .map(|v| format!("au:\"{}\"", v.replace('"', "")))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+
+    let max_papers = max_papers.unwrap_or(300).clamp(1, MAX_PAPERS) as usize;
+    let mut papers = Vec::new();
+    while papers.len() < max_papers {
+        let feed = query(&[
+            ("search_query", search_query.clone()),
+            ("sortBy", "submittedDate".to_string()),
+            ("sortOrder", "descending".to_string()),
+            ("start", papers.len().to_string()),
+            (
+                "max_results",
+                PAGE_SIZE.min(max_papers - papers.len()).to_string(),
+            ),
+        ])
+        .await?;
+        let done = feed.papers.is_empty()
+            || papers.len() + feed.papers.len() >= feed.total_results as usize;
+        papers.extend(feed.papers);
+        if done {
+            break;
+        }
+    }
+
+    let profile = build_profile(&person, &papers, co_authors.unwrap_or(20) as usize);
+    Ok(tool_text_content!(output::shape(
+        "AuthorProfile",
+        serde_json::to_string(&AuthorProfile {
+            name: name.trim().to_string(),
+            queried_variants: queried,
+            profile,
+        })?
+    )))
+}
+
+/// Aggregates the papers of `person` among `papers`, listing the top
+/// `co_authors`.
+pub fn build_profile(person: &PersonName, papers: &[Paper], co_authors: usize) -> Profile {
+    // Each matching paper with the position of the person in its authors
+    let matched: Vec<(&Paper, usize)> = papers
+        .iter()
+        .filter_map(|paper| {
+            let position = paper.authors.iter().position(|a| {
+                PersonName::parse(&a.name).is_some_and(|name| person.matches(&name))
+            })?;
+            Some((paper, position))
+        })
+        .collect();
+    let clusters = cluster(&matched);
+    let cluster_count = clusters.iter().max().map_or(0, |c| c + 1);
+    let summaries: Vec<Cluster> = (0..cluster_count)
+        .map(|c| {
+            let members: Vec<&(&Paper, usize)> = matched
+                .iter()
+                .zip(&clusters)
+                .filter(|(_, cluster)| **cluster == c)
+                .map(|(m, _)| m)
+                .collect();
+            let years: Vec<&str> = members.iter().map(|(p, _)| year(p)).collect();
+            Cluster {
+                cluster: c + 1,
+                papers: members.len(),
+                first_year: years.iter().min().unwrap_or(&"").to_string(),
+                last_year: years.iter().max().unwrap_or(&"").to_string(),
+                categories: top(members.iter().flat_map(|(p, _)| categories(p)), CLUSTER_TOP),
+                co_authors: top_authors(
+                    members.iter().flat_map(|(p, i)| others(p, *i)),
+                    CLUSTER_TOP,
+                ),
+                listed_as: top(
+                    members.iter().map(|(p, i)| p.authors[*i].name.clone()),
+                    CLUSTER_TOP,
+                ),
+            }
+        })
+        .collect();
+
+    let mut activity = BTreeMap::new();
+    for (paper, _) in &matched {
+        *activity.entry(year(paper).to_string()).or_insert(0) += 1;
+    }
+
+    Profile {
+        total_papers: matched.len(),
+        unmatched_results: papers.len() - matched.len(),
+        likely_homonyms: summaries
+            .iter()
+            .filter(|c| c.papers >= HOMONYM_CLUSTER_SIZE)
+            .count()
+            > 1,
+        listed_as: top(
+            matched.iter().map(|(p, i)| p.authors[*i].name.clone()),
+            usize::MAX,
+        ),
+        co_authors: top_authors(matched.iter().flat_map(|(p, i)| others(p, *i)), co_authors),
+        categories: top(matched.iter().flat_map(|(p, _)| categories(p)), usize::MAX),
+        activity,
+        clusters: summaries,
+        papers: matched
+            .iter()
+            .zip(&clusters)
+            .map(|((paper, position), cluster)| ProfilePaper {
+                id: paper.id.clone(),
+                title: paper.title.split_whitespace().collect::<Vec<_>>().join(" "),
+                published: paper.published.clone(),
+                primary_category: paper.primary_category.clone(),
+                listed_as: paper.authors[*position].name.clone(),
+                cluster: cluster + 1,
+            })
+            .collect(),
+    }
+}
+
+/// Groups papers that share a co-author, then groups with the same most
+/// common primary category. Returns the cluster of each paper, numbered from
+/// the largest.
+fn cluster(papers: &[(&Paper, usize)]) -> Vec<usize> {
+    let mut parent: Vec<usize> = (0..papers.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    fn union(parent: &mut [usize], a: usize, b: usize) {
+        let (a, b) = (root(parent, a), root(parent, b));
+        parent[a.max(b)] = a.min(b);
+    }
+
+    let mut first_paper: HashMap<String, usize> = HashMap::new();
+    for (i, (paper, position)) in papers.iter().enumerate() {
+        for author in others(paper, *position) {
+            if let Some(key) = PersonName::parse(&author.name).map(|n| n.key()) {
+                let first = *first_paper.entry(key).or_insert(i);
+                union(&mut parent, first, i);
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..papers.len() {
+        groups.entry(root(&mut parent, i)).or_default().push(i);
+    }
+    let mut by_category: HashMap<String, usize> = HashMap::new();
+    for (group, members) in &groups {
+        let main = top(
+            members
+                .iter()
+                .filter_map(|&i| papers[i].0.primary_category.clone()),
+            1,
+        );
+        if let Some(category) = main.into_iter().next() {
+            let first = *by_category.entry(category.name).or_insert(*group);
+            union(&mut parent, first, *group);
+        }
+    }
+
+    let roots: Vec<usize> = (0..papers.len()).map(|i| root(&mut parent, i)).collect();
+    let mut sizes: BTreeMap<usize, usize> = BTreeMap::new();
+    for root in &roots {
+        *sizes.entry(*root).or_insert(0) += 1;
+    }
+    let mut order: Vec<(usize, usize)> = sizes.into_iter().collect();
+    order.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let number: HashMap<usize, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(n, (root, _))| (*root, n))
+        .collect();
+    roots.iter().map(|root| number[root]).collect()
+}
+
+/// The authors of `paper` other than the one at `position`.
+fn others(paper: &Paper, position: usize) -> impl Iterator<Item = &Author> {
+    paper
+        .authors
+        .iter()
+        .enumerate()
+        .filter(move |(i, _)| *i != position)
+        .map(|(_, author)| author)
+}
+
+fn year(paper: &Paper) -> &str {
+    paper.published.get(..4).unwrap_or("")
+}
+
+fn categories(paper: &Paper) -> impl Iterator<Item = String> + '_ {
+    paper
+        .primary_category
+        .iter()
+        .chain(&paper.secondary_categories)
+        .cloned()
+}
+
+/// The `limit` most frequent names, ties broken alphabetically.
+fn top(names: impl Iterator<Item = String>, limit: usize) -> Vec<Count> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for name in names {
+        *counts.entry(name).or_insert(0) += 1;
+    }
+    let mut counts: Vec<Count> = counts
+        .into_iter()
+        .map(|(name, papers)| Count { name, papers })
+        .collect();
+    counts.sort_by(|a, b| b.papers.cmp(&a.papers).then_with(|| a.name.cmp(&b.name)));
+    counts.truncate(limit);
+    counts
+}
+
+/// Like [`top`], counting spellings of the same author together under the
+/// most common one.
+fn top_authors<'a>(authors: impl Iterator<Item = &'a Author>, limit: usize) -> Vec<Count> {
+    let mut spellings: HashMap<String, Vec<String>> = HashMap::new();
+    for author in authors {
+        let key = PersonName::parse(&author.name).map_or_else(|| fold(&author.name), |n| n.key());
+        spellings.entry(key).or_default().push(author.name.clone());
+    }
+    let mut counts: Vec<Count> = spellings
+        .into_values()
+        .map(|names| Count {
+            papers: names.len(),
+            name: top(names.into_iter(), 1).remove(0).name,
+        })
+        .collect();
+    counts.sort_by(|a, b| b.papers.cmp(&a.papers).then_with(|| a.name.cmp(&b.name)));
+    counts.truncate(limit);
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paper(id: &str, published: &str, category: &str, authors: &[&str]) -> Paper {
+        Paper {
+            id: id.to_string(),
+            title: format!("Paper {}", id),
+            published: published.to_string(),
+            primary_category: Some(category.to_string()),
+            authors: authors
+                .iter()
+                .map(|name| Author {
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_person_name() {
+        let name = PersonName::parse("Ludwig van Beethoven").unwrap();
+        assert_eq!(name.given, ["Ludwig"]);
+        assert_eq!(name.surname, "van Beethoven");
+        assert_eq!(
+            PersonName::parse("Smith, John A.").unwrap().variants(),
+            [
+                "John A Smith",
+                "Smith, John A",
+                "J Smith",
+                "Smith, J",
+                "J A Smith",
+                "Smith, J A",
+            ]
+        );
+
+        let john = PersonName::parse("John Smith").unwrap();
+        assert!(john.matches(&PersonName::parse("J. Smith").unwrap()));
+        assert!(john.matches(&PersonName::parse("Smith, John A.").unwrap()));
+        assert!(!john.matches(&PersonName::parse("Jane Smith").unwrap()));
+        assert!(!john.matches(&PersonName::parse("John Smithson").unwrap()));
+        assert!(
+            PersonName::parse("Jose Muller")
+                .unwrap()
+                .matches(&PersonName::parse("José Müller").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_build_profile() {
+        let papers = [
+            paper(
+                "1",
+                "2019-01-01T00:00:00Z",
+                "hep-th",
+                &["J. Smith", "A. Jones"],
+            ),
+            paper(
+                "2",
+                "2020-01-01T00:00:00Z",
+                "hep-th",
+                &["Alice Jones", "John Smith"],
+            ),
+            paper("3", "2021-01-01T00:00:00Z", "hep-th", &["John Smith"]),
+            paper(
+                "4",
+                "2021-06-01T00:00:00Z",
+                "q-bio.NC",
+                &["J. Smith", "B. Lee"],
+            ),
+            paper(
+                "5",
+                "2022-01-01T00:00:00Z",
+                "q-bio.NC",
+                &["Bo Lee", "Smith, J."],
+            ),
+            paper("6", "2022-01-01T00:00:00Z", "cs.LG", &["Jane Smith"]),
+        ];
+        let profile = build_profile(&PersonName::parse("John Smith").unwrap(), &papers, 5);
+
+        assert_eq!(profile.total_papers, 5);
+        assert_eq!(profile.unmatched_results, 1);
+        assert_eq!(
+            profile.activity,
+            BTreeMap::from([
+                ("2019".to_string(), 1),
+                ("2020".to_string(), 1),
+                ("2021".to_string(), 2),
+                ("2022".to_string(), 1),
+            ])
+        );
+        let co_authors: Vec<(&str, usize)> = profile
+            .co_authors
+            .iter()
+            .map(|c| (c.name.as_str(), c.papers))
+            .collect();
+        assert_eq!(co_authors, [("A. Jones", 2), ("B. Lee", 2)]);
+
+        // Papers 1-3 share a co-author or category, 4-5 another
+        let clusters: Vec<usize> = profile.papers.iter().map(|p| p.cluster).collect();
+        assert_eq!(clusters, [1, 1, 1, 2, 2]);
+        assert!(profile.likely_homonyms);
+        assert_eq!(profile.clusters[1].categories[0].name, "q-bio.NC");
+    }
+
+    #[tokio::test]
+    async fn test_author_profile_tool() {
+        match author_profile_tool("Ashish Vaswani".to_string(), None, Some(50), Some(5)).await {
+            Ok(content) => {
+                println!("Profile: {:?}", content);
+                assert!(format!("{:?}", content).contains("coAuthors"));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,344 @@
+use serde::Serialize;
+
+use super::errors::McpArxivError;
+use super::structure::{PaperText, Section, SectionKind};
+
+/// Rough size of a token in English text, used when sizes are given in tokens
+const CHARS_PER_TOKEN: usize = 4;
+/// Chunks smaller than this are mostly metadata, not text
+const MIN_CHUNK_CHARS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeUnit {
+    Characters,
+    Tokens,
+}
+
+impl SizeUnit {
+    pub fn parse(unit: &str) -> Result<SizeUnit, McpArxivError> {
+        match unit.trim().to_ascii_lowercase().as_str() {
+            "characters" | "chars" | "char" => Ok(SizeUnit::Characters),
+            "tokens" | "token" => Ok(SizeUnit::Tokens),
+            _ => Err(McpArxivError::InvalidArgument(format!(
+                "unknown size unit `{}`, expected characters or tokens",
+                unit
+            ))),
+        }
+    }
+
+    pub fn to_chars(self, size: usize) -> usize {
+        match self {
+            SizeUnit::Characters => size,
+            SizeUnit::Tokens => size * CHARS_PER_TOKEN,
+        }
+    }
+}
+
+/// One piece of a paper, ready to be written as a JSONL line.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chunk {
+    /// `<paperId>#<chunkIndex>`
+    pub id: String,
+    pub paper_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub chunk_index: usize,
+    pub section: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_number: Option<String>,
+    pub section_kind: SectionKind,
+    pub page_start: u32,
+    pub page_end: u32,
+    /// Character offsets into the section text, end exclusive
+    pub char_start: usize,
+    pub char_end: usize,
+    pub text: String,
+}
+
+/// A sentence or, for very long sentences, a run of words. Byte offsets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    start: usize,
+    end: usize,
+    /// Last segment of its paragraph
+    paragraph_end: bool,
+}
+
+/// Splits every section of `paper` into chunks of at most `size` characters,
+/// consecutive chunks of a section sharing up to `overlap` characters.
+/// Chunks never cross sections, and end at a paragraph break when that keeps
+/// them at least half full, otherwise at the end of a sentence.
+pub fn chunk_paper(
+    paper: &PaperText,
+    paper_id: &str,
+    size: usize,
+    overlap: usize,
+) -> Result<Vec<Chunk>, McpArxivError> {
+    if size < MIN_CHUNK_CHARS {
+        return Err(McpArxivError::InvalidArgument(format!(
+            "chunk size must be at least {} characters",
+            MIN_CHUNK_CHARS
+        )));
+    }
+    if overlap >= size {
+        return Err(McpArxivError::InvalidArgument(
+            "overlap must be smaller than the chunk size".to_string(),
+        ));
+    }
+
+    let mut chunks = Vec::new();
+    for section in &paper.sections {
+        for (start, end) in chunk_ranges(&section.text, size, overlap) {
+            chunks.push(chunk(paper, section, paper_id, chunks.len(), start, end));
+        }
+    }
+    Ok(chunks)
+}
+
+fn chunk(
+    paper: &PaperText,
+    section: &Section,
+    paper_id: &str,
+    index: usize,
+    start: usize,
+    end: usize,
+) -> Chunk {
+    let text = &section.text;
+    let char_start = text[..start].chars().count();
+    Chunk {
+        id: format!("{}#{}", paper_id, index),
+        paper_id: paper_id.to_string(),
+        title: paper.title.clone(),
+        chunk_index: index,
+        section: section.heading.clone(),
+        section_number: section.number.clone(),
+        section_kind: section.kind,
+        page_start: section.page_at(start),
+        page_end: section.page_at(end.saturating_sub(1)),
+        char_start,
+        char_end: char_start + text[start..end].chars().count(),
+        text: text[start..end].to_string(),
+    }
+}
+
+/// Byte ranges of the chunks of `text`.
+fn chunk_ranges(text: &str, size: usize, overlap: usize) -> Vec<(usize, usize)> {
+    let segments = segments(text, size);
+    let chars =
+        |from: usize, to: usize| text[segments[from].start..segments[to].end].chars().count();
+
+    let mut ranges = Vec::new();
+    let mut first = 0;
+    while first < segments.len() {
+        // `last` is exclusive
+        let mut last = first + 1;
+        let mut paragraph_end = segments[first].paragraph_end.then_some(last);
+        while last < segments.len() && chars(first, last) <= size {
+            last += 1;
+            if segments[last - 1].paragraph_end {
+                paragraph_end = Some(last);
+            }
+        }
+        if last < segments.len()
+            && let Some(end) = paragraph_end
+            && chars(first, end - 1) >= size / 2
+        {
+            last = end;
+        }
+        ranges.push((segments[first].start, segments[last - 1].end));
+        if last == segments.len() {
+            break;
+        }
+
+        // The next chunk repeats the tail of this one, but must still have
+        // room for new text
+        first = (first + 1..last)
+            .find(|from| chars(*from, last - 1) <= overlap && chars(*from, last) <= size)
+            .unwrap_or(last);
+    }
+    ranges
+}
+
+/// Splits `text` into sentences, and sentences longer than `size` characters
+/// into runs of words.
+fn segments(text: &str, size: usize) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut offset = 0;
+    for paragraph in text.split("\n\n") {
+        let first = segments.len();
+        for (start, end) in sentences(paragraph) {
+            split_long(text, offset + start, offset + end, size, &mut segments);
+        }
+        if segments.len() > first
+            && let Some(last) = segments.last_mut()
+        {
+            last.paragraph_end = true;
+        }
+        offset += paragraph.len() + 2;
+    }
+    segments
+}
+
+/// Byte ranges of the sentences of `paragraph`, trimmed. A sentence ends at
+/// `.`, `?` or `!` followed by whitespace, unless the period ends an initial
+/// such as `A.` or a number such as `Fig. 3`.
+pub fn sentences(paragraph: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut chars = paragraph.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let followed_by_space = chars.peek().is_some_and(|(_, next)| next.is_whitespace());
+        if !matches!(c, '.' | '?' | '!') || !followed_by_space {
+            continue;
+        }
+        let word = paragraph[start..i].rsplit(char::is_whitespace).next();
+        let abbreviation = c == '.'
+            && word.is_some_and(|w| {
+                w.chars().count() <= 1
+                    || matches!(w, "Fig" | "Eq" | "Sec" | "al" | "e.g" | "i.e" | "vs")
+            });
+        if !abbreviation {
+            ranges.push((start, i + 1));
+            start = i + 1;
+        }
+    }
+    ranges.push((start, paragraph.len()));
+
+    ranges
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let sentence = &paragraph[start..end];
+            let trimmed_start = start + (sentence.len() - sentence.trim_start().len());
+            let trimmed_end = start + sentence.trim_end().len();
+            (trimmed_start < trimmed_end).then_some((trimmed_start, trimmed_end))
+        })
+        .collect()
+}
+
+/// Pushes `text[start..end]` as segments of at most `size` characters,
+/// breaking between words, or inside a word longer than `size`.
+fn split_long(text: &str, start: usize, end: usize, size: usize, segments: &mut Vec<Segment>) {
+    let mut from = start;
+    while from < end {
+        let rest = &text[from..end];
+        let fits = rest.char_indices().nth(size).map_or(rest.len(), |(i, _)| i);
+        let mut to = if fits == rest.len() {
+            end
+        } else {
+            match rest[..fits].rfind(char::is_whitespace) {
+                Some(space) if space > 0 => from + space,
+                _ => from + fits,
+            }
+        };
+        // Whitespace between runs belongs to neither
+        to = from + text[from..to].trim_end().len();
+        segments.push(Segment {
+            start: from,
+            end: to,
+            paragraph_end: false,
+        });
+        from = to + (text[to..end].len() - text[to..end].trim_start().len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(text: &str) -> Section {
+        Section {
+            number: Some("1".to_string()),
+            heading: "Introduction".to_string(),
+            kind: SectionKind::Body,
+            page_start: 1,
+            page_end: 1,
+            text: text.to_string(),
+            page_breaks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_chunks_respect_boundaries() {
+        let text = "First sentence here. Second sentence is longer than the first one.\n\n\
+                    A new paragraph starts. It has two sentences.";
+        let ranges = chunk_ranges(text, 80, 0);
+        let chunks: Vec<&str> = ranges.iter().map(|(s, e)| &text[*s..*e]).collect();
+        assert_eq!(
+            chunks,
+            [
+                "First sentence here. Second sentence is longer than the first one.",
+                "A new paragraph starts. It has two sentences.",
+            ]
+        );
+
+        // Overlap repeats trailing sentences of the previous chunk
+        let ranges = chunk_ranges(text, 70, 30);
+        let chunks: Vec<&str> = ranges.iter().map(|(s, e)| &text[*s..*e]).collect();
+        assert_eq!(
+            chunks,
+            [
+                "First sentence here. Second sentence is longer than the first one.",
+                "A new paragraph starts. It has two sentences.",
+            ]
+        );
+        let text = "One. Two two. Three three three. Four.";
+        let ranges = chunk_ranges(text, 30, 12);
+        let chunks: Vec<&str> = ranges.iter().map(|(s, e)| &text[*s..*e]).collect();
+        assert_eq!(
+            chunks,
+            ["One. Two two.", "Two two. Three three three.", "Four."]
+        );
+    }
+
+    #[test]
+    fn test_long_sentences_split_between_words() {
+        let text = "word ".repeat(30);
+        for (start, end) in chunk_ranges(text.trim(), 50, 0) {
+            let chunk = &text[start..end];
+            assert!(chunk.len() <= 50);
+            assert!(chunk.starts_with("word") && chunk.ends_with("word"));
+        }
+        assert_eq!(chunk_ranges(&"x".repeat(120), 50, 0).len(), 3);
+    }
+
+    #[test]
+    fn test_chunk_paper_metadata() {
+        let mut intro =
+            section("Ünïcode first page text goes here.\n\nSecond page text goes here.");
+        intro.page_breaks = vec![(intro.text.find("Second").unwrap(), 2)];
+        intro.page_end = 2;
+        let paper = PaperText {
+            title: Some("A Paper".to_string()),
+            sections: vec![intro, section("Short.")],
+            ..Default::default()
+        };
+
+        let chunks = chunk_paper(&paper, "2101.00001", 50, 0).unwrap();
+        let summary: Vec<(&str, usize, u32, u32, usize, usize)> = chunks
+            .iter()
+            .map(|c| {
+                (
+                    c.id.as_str(),
+                    c.chunk_index,
+                    c.page_start,
+                    c.page_end,
+                    c.char_start,
+                    c.char_end,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("2101.00001#0", 0, 1, 1, 0, 34),
+                ("2101.00001#1", 1, 2, 2, 36, 63),
+                ("2101.00001#2", 2, 1, 1, 0, 6),
+            ]
+        );
+        assert_eq!(chunks[1].text, "Second page text goes here.");
+
+        assert!(chunk_paper(&paper, "x", 10, 0).is_err());
+        assert!(chunk_paper(&paper, "x", 100, 100).is_err());
+    }
+}
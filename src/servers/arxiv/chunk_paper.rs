@@ -0,0 +1,89 @@
+use super::chunk::{SizeUnit, chunk_paper};
+use super::errors::McpArxivError;
+use super::extract_paper_text::{arxiv_id, load_paper};
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+
+use crate::output;
+
+#[tool(
+    name = "ChunkPaper",
+    description = "Split the text of a paper PDF into overlapping chunks for embedding. Chunks stay within one section and end at paragraph or sentence boundaries. Returns JSONL, one chunk per line with id, paperId, title, chunkIndex, section, sectionNumber, sectionKind, pageStart, pageEnd, charStart and charEnd (offsets into the section text) and text.",
+    params(
+        paper_url = "An arXiv ID or URL, any PDF URL, or a local PDF path",
+        size = "Maximum chunk size (default: 2000 characters or 500 tokens)",
+        overlap = "Size of the text repeated from the end of the previous chunk of a section (default: a tenth of size)",
+        unit = "Unit of size and overlap: characters or tokens, estimated at 4 characters each (default: characters)",
+        sections = "Sections to chunk, matched by heading text, number or kind (e.g. ['Methods', '3', 'abstract', 'appendix'])",
+        page_range = "Pages to chunk, 1-based and inclusive (e.g. '3-5', '3', '10-')"
+    )
+)]
+pub async fn chunk_paper_tool(
+    paper_url: String,
+    size: Option<u32>,
+    overlap: Option<u32>,
+    unit: Option<String>,
+    sections: Option<Vec<String>>,
+    page_range: Option<String>,
+) -> Result<ToolResponseContent> {
+    let unit = match unit {
+        Some(unit) => SizeUnit::parse(&unit)?,
+        None => SizeUnit::Characters,
+    };
+    let size = match size {
+        Some(size) => unit.to_chars(size as usize),
+        None => 2000,
+    };
+    let overlap = match overlap {
+        Some(overlap) => unit.to_chars(overlap as usize),
+        None => size / 10,
+    };
+
+    let mut paper = load_paper(&paper_url, page_range.as_deref()).await?;
+    if let Some(sections) = sections {
+        paper.select(&sections)?;
+    }
+    let paper_id = arxiv_id(&paper_url).unwrap_or_else(|| paper_url.trim().to_string());
+    let chunks = chunk_paper(&paper, &paper_id, size, overlap)?;
+    if chunks.is_empty() {
+        return Err(McpArxivError::PdfError(format!("no text found in {}", paper_url)).into());
+    }
+
+    let lines = chunks
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tool_text_content!(output::shape(
+        "ChunkPaper",
+        lines.join("\n")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_chunk_paper_tool() {
+        match chunk_paper_tool(
+            "1706.03762".to_string(),
+            Some(300),
+            Some(30),
+            Some("tokens".to_string()),
+            None,
+            Some("1-2".to_string()),
+        )
+        .await
+        {
+            Ok(content) => {
+                println!("Chunks: {:?}", content);
+                assert!(format!("{:?}", content).contains("1706.03762#0"));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use serde_json::{Value, json};
+
+use super::atom::Paper;
+use super::errors::McpArxivError;
+
+/// Title words skipped when picking the word for a citation key
+const STOPWORDS: &[&str] = &[
+    "a", "an", "the", "on", "of", "in", "for", "to", "and", "with", "from", "via", "towards",
+    "toward", "is", "are",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CitationFormat {
+    Bibtex,
+    Ris,
+    CslJson,
+}
+
+impl CitationFormat {
+    pub fn parse(format: &str) -> Result<CitationFormat, McpArxivError> {
+        match format
+            .trim()
+            .to_ascii_lowercase()
+            .replace(['-', '_'], "")
+            .as_str()
+        {
+            "bibtex" | "bib" => Ok(CitationFormat::Bibtex),
+            "ris" => Ok(CitationFormat::Ris),
+            "csljson" | "csl" => Ok(CitationFormat::CslJson),
+            _ => Err(McpArxivError::InvalidArgument(format!(
+                "unknown citation format `{}`, expected bibtex, ris or csl-json",
+                format
+            ))),
+        }
+    }
+}
+
+/// Formats `papers` as one document in `format`.
+pub fn export(papers: &[Paper], format: CitationFormat) -> Result<String, McpArxivError> {
+    let keys = cite_keys(papers);
+    Ok(match format {
+        CitationFormat::Bibtex => papers
+            .iter()
+            .zip(&keys)
+            .map(|(paper, key)| bibtex(paper, key))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        CitationFormat::Ris => papers.iter().map(ris).collect::<Vec<_>>().join("\n"),
+        CitationFormat::CslJson => serde_json::to_string_pretty(
+            &papers
+                .iter()
+                .zip(&keys)
+                .map(|(paper, key)| csl_json(paper, key))
+                .collect::<Vec<_>>(),
+        )?,
+    })
+}
+
+pub fn bibtex(paper: &Paper, key: &str) -> String {
+    let mut fields = vec![
+        ("title", escape_bibtex(&paper.title)),
+        (
+            "author",
+            paper
+                .authors
+                .iter()
+                .map(|a| match split_name(&a.name) {
+                    (given, family) if given.is_empty() => family,
+                    (given, family) => format!("{}, {}", family, given),
+                })
+                .map(|name| escape_bibtex(&name))
+                .collect::<Vec<_>>()
+                .join(" and "),
+        ),
+        ("year", year(paper).to_string()),
+        ("eprint", paper.id.clone()),
+        ("archivePrefix", "arXiv".to_string()),
+    ];
+    if let Some(category) = &paper.primary_category {
+        fields.push(("primaryClass", category.clone()));
+    }
+    if let Some(doi) = &paper.doi {
+        fields.push(("doi", doi.clone()));
+    }
+    if let Some(journal_ref) = &paper.journal_ref {
+        fields.push(("note", escape_bibtex(journal_ref)));
+    }
+    fields.push(("url", abs_url(paper)));
+
+    let body = fields
+        .iter()
+        .map(|(name, value)| format!("  {} = {{{}}}", name, value))
+        .collect::<Vec<_>>()
+        .join(",\n");
+    format!("@misc{{{},\n{}\n}}\n", key, body)
+}
+
+pub fn ris(paper: &Paper) -> String {
+    let kind = if paper.journal_ref.is_some() {
+        "JOUR"
+    } else {
+        "PREP"
+    };
+    let mut lines = vec![("TY", kind.to_string())];
+    for author in &paper.authors {
+        lines.push((
+            "AU",
+            match split_name(&author.name) {
+                (given, family) if given.is_empty() => family,
+                (given, family) => format!("{}, {}", family, given),
+            },
+        ));
+    }
+    lines.push(("TI", paper.title.clone()));
+    lines.push(("PY", year(paper).to_string()));
+    if let Some(date) = paper.published.get(..10) {
+        lines.push(("DA", date.replace('-', "/")));
+    }
+    if let Some(journal_ref) = &paper.journal_ref {
+        lines.push(("JO", journal_ref.clone()));
+    }
+    if let Some(doi) = &paper.doi {
+        lines.push(("DO", doi.clone()));
+    }
+    lines.push(("UR", abs_url(paper)));
+    lines.push(("AB", paper.summary.clone()));
+    for category in paper
+        .primary_category
+        .iter()
+        .chain(&paper.secondary_categories)
+    {
+        lines.push(("KW", category.clone()));
+    }
+    lines.push(("PB", "arXiv".to_string()));
+    lines.push(("M1", format!("arXiv:{}", paper.id)));
+    lines.push(("ER", String::new()));
+
+    lines
+        .iter()
+        .map(|(tag, value)| {
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            format!("{}  - {}", tag, value)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+pub fn csl_json(paper: &Paper, key: &str) -> Value {
+    let authors: Vec<Value> = paper
+        .authors
+        .iter()
+        .map(|a| match split_name(&a.name) {
+            (given, family) if given.is_empty() => json!({ "literal": family }),
+            (given, family) => json!({ "family": family, "given": given }),
+        })
+        .collect();
+    let date_parts: Vec<u32> = paper
+        .published
+        .get(..10)
+        .unwrap_or_default()
+        .split('-')
+        .filter_map(|p| p.parse().ok())
+        .collect();
+
+    let mut item = json!({
+        "id": key,
+        "type": "article",
+        "title": paper.title,
+        "author": authors,
+        "issued": { "date-parts": [date_parts] },
+        "abstract": paper.summary,
+        "publisher": "arXiv",
+        "number": paper.id,
+        "URL": abs_url(paper),
+    });
+    if let Some(doi) = &paper.doi {
+        item["DOI"] = json!(doi);
+    }
+    if let Some(journal_ref) = &paper.journal_ref {
+        item["container-title"] = json!(journal_ref);
+    }
+    item
+}
+
+/// Keys like `lovelace2021sparse`, with a letter suffix when two papers in
+/// the same export would share a key.
+pub fn cite_keys(papers: &[Paper]) -> Vec<String> {
+    let keys: Vec<String> = papers.iter().map(cite_key).collect();
+    keys.iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let same: Vec<usize> = (0..keys.len()).filter(|j| keys[*j] == *key).collect();
+            if same.len() == 1 {
+                return key.clone();
+            }
+            let position = same.iter().position(|j| *j == i).unwrap_or_default();
+            format!("{}{}", key, (b'a' + (position % 26) as u8) as char)
+        })
+        .collect()
+}
+
+fn cite_key(paper: &Paper) -> String {
+    let ascii = |s: &str| {
+        s.chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase()
+    };
+    let family = paper
+        .authors
+        .first()
+        .map(|a| ascii(&split_name(&a.name).1))
+        .filter(|f| !f.is_empty())
+        .unwrap_or_else(|| "arxiv".to_string());
+    let word = paper
+        .title
+        .split_whitespace()
+        .map(ascii)
+        .find(|w| !w.is_empty() && !STOPWORDS.contains(&w.as_str()))
+        .unwrap_or_default();
+    format!("{}{}{}", family, year(paper), word)
+}
+
+/// Splits `Ludwig van Beethoven` into `("Ludwig", "van Beethoven")`. Lowercase
+/// particles start the family name, otherwise it is the last word.
+pub fn split_name(name: &str) -> (String, String) {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    if words.len() < 2 {
+        return (String::new(), words.join(" "));
+    }
+    let family_start = words[1..words.len() - 1]
+        .iter()
+        .position(|w| w.starts_with(char::is_lowercase))
+        .map_or(words.len() - 1, |i| i + 1);
+    (
+        words[..family_start].join(" "),
+        words[family_start..].join(" "),
+    )
+}
+
+fn year(paper: &Paper) -> &str {
+    paper.published.get(..4).unwrap_or_default()
+}
+
+fn abs_url(paper: &Paper) -> String {
+    format!("https://arxiv.org/abs/{}", paper.id)
+}
+
+/// Escapes characters BibTeX treats specially outside math mode. Titles often
+/// contain LaTeX already, so `$`, `\` and braces are left alone, and so is
+/// everything between `$` or `$$` delimiters.
+fn escape_bibtex(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut previous = ' ';
+    let mut math = false;
+    for c in text.chars() {
+        if previous != '\\' {
+            match c {
+                // The second `$` of `$$` does not toggle again
+                '$' if previous != '$' => math = !math,
+                '&' | '%' | '#' | '_' if !math => out.push('\\'),
+                _ => {}
+            }
+        }
+        out.push(c);
+        // An escaped backslash does not escape what follows
+        previous = if previous == '\\' && c == '\\' {
+            ' '
+        } else {
+            c
+        };
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::servers::arxiv::atom::Author;
+
+    fn paper(id: &str, title: &str) -> Paper {
+        Paper {
+            id: id.to_string(),
+            version: Some(2),
+            title: title.to_string(),
+            authors: vec![
+                Author {
+                    name: "Ludwig van Beethoven".to_string(),
+                    affiliations: vec![],
+                },
+                Author {
+                    name: "Ada Lovelace".to_string(),
+                    affiliations: vec![],
+                },
+            ],
+            summary: "We compose.".to_string(),
+            primary_category: Some("cs.SD".to_string()),
+            doi: Some("10.1000/xyz".to_string()),
+            published: "2021-01-05T00:00:00Z".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bibtex() {
+        let papers = [
+            paper("2101.00001", "The Symphony & Its Tokens"),
+            paper("2101.00002", "Symphony Models"),
+        ];
+        let bib = export(&papers, CitationFormat::Bibtex).unwrap();
+        assert!(bib.starts_with("@misc{vanbeethoven2021symphonya,\n"));
+        assert!(bib.contains("@misc{vanbeethoven2021symphonyb,"));
+        assert!(bib.contains("  title = {The Symphony \\& Its Tokens},"));
+        assert!(bib.contains("  author = {van Beethoven, Ludwig and Lovelace, Ada},"));
+        assert!(bib.contains("  eprint = {2101.00001},"));
+        assert!(bib.contains("  primaryClass = {cs.SD},"));
+        assert!(bib.contains("  url = {https://arxiv.org/abs/2101.00001}\n}"));
+
+        assert_eq!(
+            escape_bibtex("Bounds on $x_i$ & $$\\sum_j y_j$$ for 50% of n_k, \\$5_a"),
+            "Bounds on $x_i$ \\& $$\\sum_j y_j$$ for 50\\% of n\\_k, \\$5\\_a"
+        );
+    }
+
+    #[test]
+    fn test_ris_and_csl_json() {
+        let paper = paper("2101.00001", "Symphony");
+        let ris = ris(&paper);
+        assert!(ris.starts_with("TY  - PREP\nAU  - van Beethoven, Ludwig\nAU  - Lovelace, Ada\n"));
+        assert!(ris.contains("DA  - 2021/01/05\n"));
+        assert!(ris.contains("DO  - 10.1000/xyz\n"));
+        assert!(ris.ends_with("ER  - \n"));
+
+        let csl = csl_json(&paper, "key");
+        assert_eq!(csl["author"][0]["family"], "van Beethoven");
+        assert_eq!(csl["issued"]["date-parts"][0], json!([2021, 1, 5]));
+        assert_eq!(csl["DOI"], "10.1000/xyz");
+
+        assert_eq!(
+            CitationFormat::parse("CSL-JSON").unwrap(),
+            CitationFormat::CslJson
+        );
+        assert!(CitationFormat::parse("endnote").is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//! The HTTP client for all arXiv requests.
+//!
+//! arXiv's API terms ask for at most one request every three seconds, over a
+//! single connection, from a client that says who runs it. Requests to arXiv
+//! hosts wait their turn in a process-wide queue, so concurrent tool calls are
+//! delayed rather than failed, and carry a `User-Agent` with a contact
+//! address. Requests to other hosts, such as PDFs hosted elsewhere, are sent
+//! straight away. Every request times out, so a stalled connection cannot
+//! hold up the queue.
+
+use lazy_static::lazy_static;
+use reqwest::header::USER_AGENT;
+use reqwest::{Client, Response};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use url::Url;
+
+use super::errors::McpArxivError;
+
+/// Default for `ARXIV_API_URL`, which serves both the query and OAI-PMH APIs
+const DEFAULT_API_URL: &str = "https://export.arxiv.org";
+/// The shortest interval arXiv allows, also the default
+const MIN_INTERVAL_SECS: u64 = 3;
+const REPOSITORY_URL: &str = "https://github.com/fabelis/mcp-servers";
+/// Longest wait for a connection, and for the response headers once connected.
+/// Requests to arXiv hold the pacer until then, so a stalled one would block
+/// every other arXiv request.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Longest pause while reading a response body, large PDFs may take minutes
+/// in total
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    static ref CLIENT: Client = Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .build()
+        .expect("valid HTTP client configuration");
+    static ref PACER: Pacer = Pacer::default();
+}
+
+/// Spaces out requests, in the order they asked for a turn.
+#[derive(Default)]
+pub struct Pacer {
+    /// When the previous request finished sending, `None` before the first.
+    /// Tokio's mutex is fair, so waiting requests are served first come,
+    /// first served.
+    last: Mutex<Option<Instant>>,
+}
+
+impl Pacer {
+    /// Runs `request` once `interval` has passed since the previous request
+    /// finished sending.
+    pub async fn run<T>(&self, interval: Duration, request: impl Future<Output = T>) -> T {
+        let mut last = self.last.lock().await;
+        if let Some(last) = *last {
+            tokio::time::sleep_until(last + interval).await;
+        }
+        let result = request.await;
+        *last = Some(Instant::now());
+        result
+    }
+}
+
+/// `path` on the arXiv API host, `ARXIV_API_URL` or `https://export.arxiv.org`.
+pub fn api_url(path: &str) -> String {
+    let base = crate::config::var("ARXIV_API_URL")
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| DEFAULT_API_URL.to_string());
+    format!("{}{}", base, path)
+}
+
+/// `ARXIV_USER_AGENT`, or one naming this server and `ARXIV_CONTACT_EMAIL`.
+pub fn user_agent() -> String {
+    if let Some(agent) = crate::config::var("ARXIV_USER_AGENT").filter(|a| !a.trim().is_empty()) {
+        return agent.trim().to_string();
+    }
+    let contact = crate::config::var("ARXIV_CONTACT_EMAIL")
+        .map(|email| email.trim().to_string())
+        .filter(|email| !email.is_empty());
+    match contact {
+        Some(email) => format!(
+            "mcp-servers/{} (+{}; mailto:{})",
+            env!("CARGO_PKG_VERSION"),
+            REPOSITORY_URL,
+            email
+        ),
+        None => format!(
+            "mcp-servers/{} (+{})",
+            env!("CARGO_PKG_VERSION"),
+            REPOSITORY_URL
+        ),
+    }
+}
+
+/// `ARXIV_REQUEST_INTERVAL_SECS`, at least three seconds.
+fn interval() -> Duration {
+    Duration::from_secs(
+        crate::config::var("ARXIV_REQUEST_INTERVAL_SECS")
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(MIN_INTERVAL_SECS)
+            .max(MIN_INTERVAL_SECS),
+    )
+}
+
+fn is_arxiv(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| {
+        url.host_str()
+            .is_some_and(|host| host == "arxiv.org" || host.ends_with(".arxiv.org"))
+    })
+}
+
+/// Sends a GET request, paced when it goes to arXiv. The response body is
+/// read by the caller after the next request may have started.
+pub async fn get(url: &str, query: &[(&str, String)]) -> Result<Response, McpArxivError> {
+    let request = tokio::time::timeout(
+        RESPONSE_TIMEOUT,
+        CLIENT
+            .get(url)
+            .query(query)
+            .header(USER_AGENT, user_agent())
+            .send(),
+    );
+    let response = if is_arxiv(url) {
+        PACER.run(interval(), request).await
+    } else {
+        request.await
+    };
+    response
+        .map_err(|_| {
+            McpArxivError::ApiError(format!(
+                "{} did not respond within {} seconds",
+                url,
+                RESPONSE_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(McpArxivError::HttpError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_is_arxiv() {
+        assert!(is_arxiv("https://export.arxiv.org/api/query"));
+        assert!(is_arxiv("https://arxiv.org/pdf/2101.00001"));
+        assert!(!is_arxiv("https://notarxiv.org/pdf/2101.00001"));
+        assert!(!is_arxiv("http://127.0.0.1:8080/oai2"));
+    }
+
+    #[tokio::test]
+    async fn test_pacer_queues_requests() {
+        let pacer = Arc::new(Pacer::default());
+        let interval = Duration::from_millis(100);
+        let start = Instant::now();
+
+        let tasks: Vec<_> = (0..3)
+            .map(|i| {
+                let pacer = pacer.clone();
+                tokio::spawn(async move {
+                    // Stagger the calls so they queue in order
+                    tokio::time::sleep(Duration::from_millis(i * 10)).await;
+                    pacer.run(interval, async { Instant::now() }).await
+                })
+            })
+            .collect();
+        let mut started = Vec::new();
+        for task in tasks {
+            started.push(task.await.unwrap() - start);
+        }
+
+        assert!(started[0] < interval);
+        assert!(started[1] >= started[0] + interval);
+        assert!(started[2] >= started[1] + interval);
+    }
+}
@@ -0,0 +1,180 @@
+//! Line diffs in unified format.
+
+/// Inputs whose changed middle part exceeds this many line pairs are reported
+/// as replaced wholesale instead of being compared line by line
+const MAX_CELLS: usize = 4_000_000;
+
+/// One step of an edit script, with indices into the old and new sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Edit script turning `old` into `new`, based on their longest common
+/// subsequence. Deletions come before insertions within a change.
+pub fn diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Op> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<Op> = (0..prefix).map(|i| Op::Equal(i, i)).collect();
+    let (n, m) = (old_mid.len(), new_mid.len());
+    if n.saturating_mul(m) > MAX_CELLS {
+        ops.extend((0..n).map(|i| Op::Delete(prefix + i)));
+        ops.extend((0..m).map(|j| Op::Insert(prefix + j)));
+    } else {
+        // lcs[i][j] is the LCS length of old_mid[i..] and new_mid[j..]
+        let width = m + 1;
+        let mut lcs = vec![0u32; (n + 1) * width];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i * width + j] = if old_mid[i] == new_mid[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old_mid[i] == new_mid[j] {
+                ops.push(Op::Equal(prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if j == m || (i < n && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1]) {
+                ops.push(Op::Delete(prefix + i));
+                i += 1;
+            } else {
+                ops.push(Op::Insert(prefix + j));
+                j += 1;
+            }
+        }
+    }
+
+    ops.extend((0..suffix).map(|k| Op::Equal(old.len() - suffix + k, new.len() - suffix + k)));
+    ops
+}
+
+/// Hunks of a unified diff between `old` and `new`, with `context` unchanged
+/// lines around each change. Empty when the inputs are equal.
+pub fn unified(old: &[String], new: &[String], context: usize) -> String {
+    let ops = diff(old, new);
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, Op::Equal(..)))
+        .map(|(i, _)| i)
+        .collect();
+
+    // Lines of each side consumed before each op
+    let mut before = Vec::with_capacity(ops.len() + 1);
+    let (mut old_line, mut new_line) = (0, 0);
+    for op in &ops {
+        before.push((old_line, new_line));
+        match op {
+            Op::Equal(..) => {
+                old_line += 1;
+                new_line += 1;
+            }
+            Op::Delete(_) => old_line += 1,
+            Op::Insert(_) => new_line += 1,
+        }
+    }
+    before.push((old_line, new_line));
+
+    let mut out = String::new();
+    let mut k = 0;
+    while k < changes.len() {
+        let start = changes[k].saturating_sub(context);
+        let mut last = changes[k];
+        while k + 1 < changes.len() && changes[k + 1] <= last + 2 * context + 1 {
+            k += 1;
+            last = changes[k];
+        }
+        k += 1;
+        let end = (last + 1 + context).min(ops.len());
+
+        let (old_start, new_start) = before[start];
+        let (old_end, new_end) = before[end];
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_end - old_start),
+            hunk_range(new_start, new_end - new_start)
+        ));
+        for op in &ops[start..end] {
+            let (prefix, line) = match op {
+                Op::Equal(i, _) => (' ', &old[*i]),
+                Op::Delete(i) => ('-', &old[*i]),
+                Op::Insert(j) => ('+', &new[*j]),
+            };
+            out.push(prefix);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// `start,count` with a 1-based start, or the line before an empty range.
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let ops = diff(&lines("a b c d"), &lines("a c d e"));
+        assert_eq!(
+            ops,
+            [
+                Op::Equal(0, 0),
+                Op::Delete(1),
+                Op::Equal(2, 1),
+                Op::Equal(3, 2),
+                Op::Insert(3),
+            ]
+        );
+        assert!(
+            diff(&lines("x"), &lines("x"))
+                .iter()
+                .all(|op| matches!(op, Op::Equal(..)))
+        );
+    }
+
+    #[test]
+    fn test_unified() {
+        let old = lines("1 2 3 4 5 6 7 8 9 10 11 12");
+        let mut new = old.clone();
+        new[1] = "two".to_string();
+        new.insert(10, "10.5".to_string());
+
+        assert_eq!(
+            unified(&old, &new, 1),
+            "@@ -1,3 +1,3 @@\n 1\n-2\n+two\n 3\n@@ -10,2 +10,3 @@\n 10\n+10.5\n 11\n"
+        );
+        // Close changes share a hunk
+        assert!(unified(&old, &new, 4).starts_with("@@ -1,12 +1,13 @@\n"));
+        assert_eq!(unified(&old, &old, 3), "");
+        assert_eq!(unified(&[], &lines("a"), 3), "@@ -0,0 +1 @@\n+a\n");
+    }
+}
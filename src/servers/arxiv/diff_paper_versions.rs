@@ -0,0 +1,260 @@
+use super::atom::{fetch_papers, split_version};
+use super::chunk::sentences;
+use super::diff::{Op, diff, unified};
+use super::errors::McpArxivError;
+use super::extract_paper_text::load_paper;
+use super::structure::{PaperText, Section};
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+use serde::Serialize;
+
+use crate::output;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SectionStatus {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionChange {
+    pub section: String,
+    pub status: SectionStatus,
+    /// Sentences added and removed
+    pub added: usize,
+    pub removed: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionDiff {
+    id: String,
+    from: String,
+    to: String,
+    sections: Vec<SectionChange>,
+    diff: String,
+}
+
+#[tool(
+    name = "DiffPaperVersions",
+    description = "Compare the text of two versions of an arXiv paper. Sections are matched by heading and compared sentence by sentence. Returns JSON with the status of each section (added, removed, changed, unchanged) and a unified diff with one file header per section.",
+    params(
+        id = "The arXiv ID (e.g. '2101.00001')",
+        from_version = "The older version (e.g. 'v1' or 1; default: the version before to_version)",
+        to_version = "The newer version (default: the latest version)",
+        sections = "Sections to compare, matched by heading text, number or kind (e.g. ['Methods', '3', 'abstract'])",
+        context = "Unchanged sentences shown around each change (default: 3)"
+    )
+)]
+pub async fn diff_paper_versions_tool(
+    id: String,
+    from_version: Option<String>,
+    to_version: Option<String>,
+    sections: Option<Vec<String>>,
+    context: Option<u32>,
+) -> Result<ToolResponseContent> {
+    let (id, id_version) = split_version(id.trim());
+    let to = match (to_version, id_version) {
+        (Some(v), _) => parse_version(&v)?,
+        (None, Some(v)) => v,
+        (None, None) => fetch_papers(std::slice::from_ref(&id))
+            .await?
+            .remove(0)
+            .version
+            .unwrap_or(1),
+    };
+    let from = match from_version.as_deref() {
+        Some(v) => parse_version(v)?,
+        None => to.saturating_sub(1),
+    };
+    if from == 0 || from >= to {
+        return Err(McpArxivError::InvalidArgument(format!(
+            "cannot compare v{} with v{}, from_version must be an earlier version",
+            from, to
+        ))
+        .into());
+    }
+
+    let mut old = load_paper(&format!("https://arxiv.org/abs/{}v{}", id, from), None).await?;
+    let mut new = load_paper(&format!("https://arxiv.org/abs/{}v{}", id, to), None).await?;
+    if let Some(sections) = sections {
+        old.select(&sections)?;
+        new.select(&sections)?;
+    }
+
+    let from = format!("v{}", from);
+    let to = format!("v{}", to);
+    let (sections, diff) = diff_sections(&old, &new, &from, &to, context.unwrap_or(3) as usize);
+    Ok(tool_text_content!(output::shape(
+        "DiffPaperVersions",
+        serde_json::to_string(&VersionDiff {
+            id,
+            from,
+            to,
+            sections,
+            diff,
+        })?
+    )))
+}
+
+fn parse_version(version: &str) -> Result<u32, McpArxivError> {
+    version
+        .trim()
+        .trim_start_matches(['v', 'V'])
+        .parse()
+        .map_err(|_| {
+            McpArxivError::InvalidArgument(format!(
+                "invalid version `{}`, expected e.g. v2 or 2",
+                version
+            ))
+        })
+}
+
+/// Pairs the sections of two versions by heading and diffs each pair, in the
+/// order of the newer version. Returns the change of each section and the
+/// unified diff, labelled `<old_label>/<section>` and `<new_label>/<section>`.
+pub fn diff_sections(
+    old: &PaperText,
+    new: &PaperText,
+    old_label: &str,
+    new_label: &str,
+    context: usize,
+) -> (Vec<SectionChange>, String) {
+    let key = |s: &Section| s.heading.to_lowercase();
+    let old_keys: Vec<String> = old.sections.iter().map(key).collect();
+    let new_keys: Vec<String> = new.sections.iter().map(key).collect();
+
+    let mut changes = Vec::new();
+    let mut out = String::new();
+    for op in diff(&old_keys, &new_keys) {
+        let (old_section, new_section) = match op {
+            Op::Equal(i, j) => (Some(&old.sections[i]), Some(&new.sections[j])),
+            Op::Delete(i) => (Some(&old.sections[i]), None),
+            Op::Insert(j) => (None, Some(&new.sections[j])),
+        };
+        let old_lines = old_section.map(lines).unwrap_or_default();
+        let new_lines = new_section.map(lines).unwrap_or_default();
+        let hunks = unified(&old_lines, &new_lines, context);
+
+        let added = hunks.lines().filter(|l| l.starts_with('+')).count();
+        let removed = hunks.lines().filter(|l| l.starts_with('-')).count();
+        let status = match (old_section, new_section) {
+            (None, _) => SectionStatus::Added,
+            (_, None) => SectionStatus::Removed,
+            _ if hunks.is_empty() => SectionStatus::Unchanged,
+            _ => SectionStatus::Changed,
+        };
+        let Some(title) = new_section.or(old_section).map(title) else {
+            continue;
+        };
+
+        if !hunks.is_empty() {
+            let path = |label: &str, section: Option<&Section>| match section {
+                Some(section) => format!("{}/{}", label, self::title(section)),
+                None => "/dev/null".to_string(),
+            };
+            out.push_str(&format!("--- {}\n", path(old_label, old_section)));
+            out.push_str(&format!("+++ {}\n", path(new_label, new_section)));
+            out.push_str(&hunks);
+        }
+        changes.push(SectionChange {
+            section: title,
+            status,
+            added,
+            removed,
+        });
+    }
+    (changes, out)
+}
+
+fn title(section: &Section) -> String {
+    match &section.number {
+        Some(number) => format!("{} {}", number, section.heading),
+        None => section.heading.clone(),
+    }
+}
+
+/// One sentence per line with paragraphs separated by an empty line, so
+/// changes in how the PDF wraps lines do not show up in the diff.
+fn lines(section: &Section) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in section.text.split("\n\n") {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        for (start, end) in sentences(paragraph) {
+            lines.push(
+                paragraph[start..end]
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paper(text: &str) -> PaperText {
+        PaperText::parse(&[(1, text.to_string())], 1)
+    }
+
+    #[test]
+    fn test_diff_sections() {
+        let old = paper(
+            "Abstract\nWe study a thing.\n1 Introduction\nThe thing is hard.\nWe fix it.\n2 Notes\nGone soon.\n",
+        );
+        let new = paper(
+            "Abstract\nWe study a thing.\n1 Introduction\nThe thing is hard. We fix it\nquickly.\n2 Results\nIt works.\n",
+        );
+
+        let (changes, diff) = diff_sections(&old, &new, "v1", "v2", 3);
+        let summary: Vec<(&str, SectionStatus, usize, usize)> = changes
+            .iter()
+            .map(|c| (c.section.as_str(), c.status, c.added, c.removed))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("Abstract", SectionStatus::Unchanged, 0, 0),
+                ("1 Introduction", SectionStatus::Changed, 1, 1),
+                ("2 Notes", SectionStatus::Removed, 0, 1),
+                ("2 Results", SectionStatus::Added, 1, 0),
+            ]
+        );
+        assert!(diff.starts_with(
+            "--- v1/1 Introduction\n+++ v2/1 Introduction\n@@ -1,2 +1,2 @@\n The thing is hard.\n-We fix it.\n+We fix it quickly.\n"
+        ));
+        assert!(diff.contains("--- v1/2 Notes\n+++ /dev/null\n@@ -1 +0,0 @@\n-Gone soon.\n"));
+    }
+
+    #[tokio::test]
+    async fn test_diff_paper_versions_tool() {
+        match diff_paper_versions_tool(
+            "1706.03762".to_string(),
+            Some("v1".to_string()),
+            Some("v2".to_string()),
+            Some(vec!["abstract".to_string()]),
+            None,
+        )
+        .await
+        {
+            Ok(content) => {
+                println!("Diff: {:?}", content);
+                assert!(format!("{:?}", content).contains("sections"));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
@@ -6,4 +6,26 @@ pub enum McpArxivError {
     HttpError(#[from] reqwest::Error),
     #[error("API error: {0}")]
     ApiError(String),
+    #[error("Failed to parse response: {0}")]
+    ParseError(String),
+    #[error("Failed to serialize response: {0}")]
+    ResponseSerializeError(#[from] serde_json::Error),
+    #[error("OAI-PMH error {code}: {message}")]
+    OaiError { code: String, message: String },
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Failed to read PDF: {0}")]
+    PdfError(String),
+    #[error("Failed to read paper source: {0}")]
+    SourceError(String),
+    #[error("Library error: {0}")]
+    LibraryError(String),
+    #[error("Paper {0} is not in the library")]
+    NotInLibrary(String),
+    #[error("Watcher error: {0}")]
+    WatcherError(String),
+    #[error("No paper found with ID {0}")]
+    PaperNotFound(String),
 }
@@ -0,0 +1,56 @@
+use super::atom::fetch_papers;
+use super::citation::{CitationFormat, export};
+use super::errors::McpArxivError;
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+
+#[tool(
+    name = "ExportCitation",
+    description = "Export citations for one or more arXiv papers as BibTeX, RIS or CSL-JSON, built from the arXiv metadata.",
+    params(
+        ids = "arXiv IDs to cite (e.g. ['2101.00001', 'hep-th/9901001v2'])",
+        format = "Citation format (bibtex, ris, csl-json; default: bibtex)"
+    )
+)]
+pub async fn export_citation_tool(
+    ids: Vec<String>,
+    format: Option<String>,
+) -> Result<ToolResponseContent> {
+    let format = CitationFormat::parse(format.as_deref().unwrap_or("bibtex"))?;
+    let ids: Vec<String> = ids
+        .iter()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect();
+    if ids.is_empty() {
+        return Err(McpArxivError::InvalidArgument("no arXiv IDs given".to_string()).into());
+    }
+
+    let papers = fetch_papers(&ids).await?;
+    Ok(tool_text_content!(export(&papers, format)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_export_citation_tool() {
+        match export_citation_tool(
+            vec!["1706.03762".to_string(), "hep-th/9901001".to_string()],
+            Some("bibtex".to_string()),
+        )
+        .await
+        {
+            Ok(content) => {
+                println!("Citations: {:?}", content);
+                assert!(format!("{:?}", content).contains("@misc{"));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use super::errors::McpArxivError;
+use super::extract_paper_text::{fetch_pdf, parse_pdf};
+use super::figures::{Image, Limits, extract_images};
+use super::pdf;
+use super::structure::Caption;
+use anyhow::Result;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use mcp_core::{tool_image_content, tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+use serde::Serialize;
+
+/// Server-side caps on the requested limits, the images end up in the response
+const MAX_FIGURES: u32 = 50;
+const MAX_IMAGE_KB: u32 = 4096;
+const MAX_TOTAL_KB: u32 = 16384;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Figure {
+    /// Position of the image content after this JSON, from 1
+    index: usize,
+    page: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    caption: Option<String>,
+    width: u32,
+    height: u32,
+    mime_type: &'static str,
+    size_bytes: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SkippedImage {
+    page: u32,
+    reason: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Figures {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    page_count: u32,
+    figures: Vec<Figure>,
+    captions_without_image: Vec<Caption>,
+    skipped: Vec<SkippedImage>,
+    truncated: bool,
+}
+
+#[tool(
+    name = "ExtractFigures",
+    description = "Extract the images embedded in a paper PDF, such as plots and photos, with their captions and page numbers. Returns JSON listing the figures (index, page, label, caption, width, height, mimeType), captions of figures and tables that have no embedded image, and images that were skipped, followed by one image per figure in the same order.",
+    params(
+        paper_url = "An arXiv ID or URL, any PDF URL, or a local PDF path",
+        page_range = "Pages to extract from, 1-based and inclusive (e.g. '3-5', '3', '10-')",
+        max_figures = "Maximum number of images to return (default: 10, at most 50)",
+        max_image_kb = "Maximum size of each image in KB, larger images are scaled down or skipped (default: 1024, at most 4096)",
+        max_total_kb = "Maximum size of all images together in KB (default: 4096, at most 16384)"
+    )
+)]
+pub async fn extract_figures_tool(
+    paper_url: String,
+    page_range: Option<String>,
+    max_figures: Option<u32>,
+    max_image_kb: Option<u32>,
+    max_total_kb: Option<u32>,
+) -> Result<Vec<ToolResponseContent>> {
+    let limits = Limits {
+        max_images: max_figures.unwrap_or(10).min(MAX_FIGURES) as usize,
+        max_image_bytes: max_image_kb.unwrap_or(1024).clamp(1, MAX_IMAGE_KB) as usize * 1024,
+        max_total_bytes: max_total_kb.unwrap_or(4096).clamp(1, MAX_TOTAL_KB) as usize * 1024,
+    };
+    let pdf = fetch_pdf(&paper_url).await?;
+    let range = page_range.clone();
+    let (paper, images) = tokio::try_join!(
+        parse_pdf(pdf.clone(), page_range.as_deref()),
+        pdf::parse(pdf, move |pdf| extract_images(
+            pdf,
+            range.as_deref(),
+            limits
+        ))
+    )?;
+
+    let (captions, captions_without_image) = match_captions(&images.images, &paper.captions);
+    let figures = images
+        .images
+        .iter()
+        .zip(captions)
+        .enumerate()
+        .map(|(i, (image, caption))| Figure {
+            index: i + 1,
+            page: image.page,
+            label: caption.map(|c| c.label.clone()),
+            caption: caption.map(|c| c.text.clone()),
+            width: image.width,
+            height: image.height,
+            mime_type: image.mime_type,
+            size_bytes: image.data.len(),
+        })
+        .collect();
+    let summary = Figures {
+        title: paper.title.clone(),
+        page_count: images.page_count,
+        figures,
+        captions_without_image: captions_without_image.into_iter().cloned().collect(),
+        skipped: images
+            .skipped
+            .into_iter()
+            .map(|s| SkippedImage {
+                page: s.page,
+                reason: s.reason,
+            })
+            .collect(),
+        truncated: images.truncated,
+    };
+
+    let mut content = vec![tool_text_content!(
+        serde_json::to_string(&summary).map_err(McpArxivError::ResponseSerializeError)?
+    )];
+    content.extend(images.images.iter().map(|image| {
+        tool_image_content!(STANDARD.encode(&image.data), image.mime_type.to_string())
+    }));
+    Ok(content)
+}
+
+/// Pairs images with the figure captions on their page, in order. Extra
+/// images on a page share its last figure caption, as they are usually panels
+/// of one figure. Returns the caption of each image and the captions left
+/// without an image.
+fn match_captions<'a>(
+    images: &[Image],
+    captions: &'a [Caption],
+) -> (Vec<Option<&'a Caption>>, Vec<&'a Caption>) {
+    let is_figure = |c: &Caption| c.label.starts_with("Fig");
+    let mut used = vec![false; captions.len()];
+    let mut matched = Vec::with_capacity(images.len());
+    let mut page = 0;
+    let mut on_page = 0;
+    for image in images {
+        if image.page != page {
+            page = image.page;
+            on_page = 0;
+        }
+        let page_captions: Vec<usize> = (0..captions.len())
+            .filter(|&i| captions[i].page == page && is_figure(&captions[i]))
+            .collect();
+        let caption = page_captions.get(on_page).or(page_captions.last()).copied();
+        if let Some(i) = caption {
+            used[i] = true;
+        }
+        matched.push(caption.map(|i| &captions[i]));
+        on_page += 1;
+    }
+
+    let unmatched = captions
+        .iter()
+        .zip(used)
+        .filter(|(_, used)| !used)
+        .map(|(caption, _)| caption)
+        .collect();
+    (matched, unmatched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caption(label: &str, page: u32) -> Caption {
+        Caption {
+            label: label.to_string(),
+            text: format!("{} text", label),
+            page,
+        }
+    }
+
+    fn image(page: u32) -> Image {
+        Image {
+            page,
+            width: 100,
+            height: 100,
+            mime_type: "image/png",
+            data: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_match_captions() {
+        let captions = [
+            caption("Figure 1", 2),
+            caption("Figure 2", 2),
+            caption("Table 1", 3),
+            caption("Figure 3", 4),
+            caption("Figure 4", 5),
+        ];
+        let images = [image(1), image(2), image(2), image(4), image(4), image(4)];
+
+        let (matched, unmatched) = match_captions(&images, &captions);
+        let labels: Vec<Option<&str>> = matched
+            .iter()
+            .map(|c| c.map(|c| c.label.as_str()))
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                None,
+                Some("Figure 1"),
+                Some("Figure 2"),
+                Some("Figure 3"),
+                Some("Figure 3"),
+                Some("Figure 3"),
+            ]
+        );
+        let unmatched: Vec<&str> = unmatched.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(unmatched, ["Table 1", "Figure 4"]);
+    }
+
+    #[tokio::test]
+    async fn test_extract_figures_tool() {
+        match extract_figures_tool(
+            "1706.03762".to_string(),
+            Some("3-4".to_string()),
+            Some(2),
+            None,
+            None,
+        )
+        .await
+        {
+            Ok(content) => {
+                println!("Figures: {:?}", &content[0]);
+                assert!(format!("{:?}", content[0]).contains("figures"));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
@@ -1,85 +1,176 @@
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
-use pdf_extract::extract_text;
-use reqwest;
-use std::fs;
-use std::path::PathBuf;
-use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
+use std::path::{Path, PathBuf};
 use url::Url;
 
 use super::errors::McpArxivError;
+use super::library::Library;
+use super::pdf;
+use super::structure::{PaperText, parse_page_range};
+use crate::output;
+
+/// Where the PDF comes from
+#[derive(Debug, PartialEq)]
+enum PdfSource {
+    Local(PathBuf),
+    /// Read from the library when saved there, downloaded otherwise
+    Arxiv(String),
+    Remote(String),
+}
 
 #[tool(
     name = "ExtractPaperText",
-    description = "Extract text from an arXiv paper PDF.",
-    params(paper_url = "The arXiv paper URL or ID")
+    description = "Extract text from a paper PDF, split into sections. Returns JSON with the title, abstract, pageCount, sections (number, heading, kind, pageStart, pageEnd, text) and figure/table captions. Use sections or page_range to fetch part of the paper only.",
+    params(
+        paper_url = "An arXiv ID or URL, any PDF URL, or a local PDF path",
+        sections = "Sections to return, matched by heading text, number or kind (e.g. ['Methods', '3', 'abstract', 'references', 'appendix'])",
+        page_range = "Pages to extract, 1-based and inclusive (e.g. '3-5', '3', '10-')"
+    )
 )]
-pub async fn extract_paper_text_tool(paper_url: String) -> Result<ToolResponseContent> {
-    // Extract arXiv ID from URL or use directly
-    let arxiv_id = if paper_url.contains("arxiv.org") {
-        let url = Url::parse(&paper_url).map_err(|e| McpArxivError::ApiError(e.to_string()))?;
-        url.path_segments()
-            .ok_or_else(|| McpArxivError::ApiError("Invalid URL".to_string()))?
-            .last()
-            .ok_or_else(|| McpArxivError::ApiError("No paper ID found".to_string()))?
-            .to_string()
-    } else {
-        paper_url
-    };
+pub async fn extract_paper_text_tool(
+    paper_url: String,
+    sections: Option<Vec<String>>,
+    page_range: Option<String>,
+) -> Result<ToolResponseContent> {
+    let mut paper = load_paper(&paper_url, page_range.as_deref()).await?;
+    if let Some(sections) = sections {
+        paper.select(&sections)?;
+    }
 
-    // Construct PDF URL
-    let pdf_url = format!("https://arxiv.org/pdf/{}.pdf", arxiv_id);
+    Ok(tool_text_content!(output::shape(
+        "ExtractPaperText",
+        serde_json::to_string(&paper)?
+    )))
+}
 
-    // Create temp directory if it doesn't exist
-    let temp_dir = PathBuf::from("temp");
-    if !temp_dir.exists() {
-        fs::create_dir(&temp_dir).map_err(|e| McpArxivError::ApiError(e.to_string()))?;
-    }
+/// Loads a PDF from an arXiv ID or URL, any PDF URL or a local path and splits
+/// the pages in `page_range` into sections.
+pub async fn load_paper(
+    paper_url: &str,
+    page_range: Option<&str>,
+) -> Result<PaperText, McpArxivError> {
+    let pdf = fetch_pdf(paper_url).await?;
+    parse_pdf(pdf, page_range).await
+}
 
-    // Download PDF
-    let pdf_path = temp_dir.join(format!("{}.pdf", arxiv_id));
-    let response = reqwest::get(&pdf_url)
-        .await
-        .map_err(McpArxivError::HttpError)?;
-
-    if !response.status().is_success() {
-        return Err(McpArxivError::ApiError(format!(
-            "Failed to download PDF: {}",
-            response.status()
-        ))
-        .into());
+/// Returns the bytes of the PDF for `paper_url`. arXiv papers are read from
+/// the library when saved there.
+pub async fn fetch_pdf(paper_url: &str) -> Result<Vec<u8>, McpArxivError> {
+    match resolve_source(paper_url)? {
+        PdfSource::Local(path) => pdf::read(&path),
+        PdfSource::Arxiv(id) => match Library::open().saved_pdf(&id) {
+            Some(path) => pdf::read(&path),
+            None => download_arxiv_pdf(&id).await,
+        },
+        PdfSource::Remote(pdf_url) => pdf::download(&pdf_url).await,
     }
+}
 
-    let mut file = File::create(&pdf_path)
-        .await
-        .map_err(|e| McpArxivError::ApiError(e.to_string()))?;
-    let content = response.bytes().await.map_err(McpArxivError::HttpError)?;
-    file.write_all(&content)
-        .await
-        .map_err(|e| McpArxivError::ApiError(e.to_string()))?;
+/// Downloads the PDF of an arXiv paper, bypassing the library.
+pub async fn download_arxiv_pdf(id: &str) -> Result<Vec<u8>, McpArxivError> {
+    pdf::download(&format!("https://arxiv.org/pdf/{}.pdf", id)).await
+}
+
+/// Extracts the pages in `page_range` of `pdf` and splits them into sections.
+pub async fn parse_pdf(pdf: Vec<u8>, page_range: Option<&str>) -> Result<PaperText, McpArxivError> {
+    let pages = pdf::extract_pages(pdf).await?;
+    let page_count = pages.len() as u32;
+    let (first, last) = match page_range {
+        Some(range) => parse_page_range(range, page_count)?,
+        None => (1, page_count),
+    };
+    let pages: Vec<(u32, String)> = (1..)
+        .zip(pages)
+        .filter(|(page, _)| (first..=last).contains(page))
+        .collect();
 
-    // Extract text
-    let text = extract_text(&pdf_path).map_err(|e| McpArxivError::ApiError(e.to_string()))?;
+    Ok(PaperText::parse(&pages, page_count))
+}
 
-    // Clean up
-    fs::remove_file(&pdf_path).map_err(|e| McpArxivError::ApiError(e.to_string()))?;
+/// The arXiv ID `paper_url` refers to, if any.
+pub fn arxiv_id(paper_url: &str) -> Option<String> {
+    match resolve_source(paper_url) {
+        Ok(PdfSource::Arxiv(id)) => Some(id),
+        _ => None,
+    }
+}
 
-    Ok(tool_text_content!(text))
+fn resolve_source(paper: &str) -> Result<PdfSource, McpArxivError> {
+    let paper = paper.trim();
+    if let Some(path) = paper.strip_prefix("file://") {
+        return Ok(PdfSource::Local(PathBuf::from(path)));
+    }
+
+    if paper.starts_with("http://") || paper.starts_with("https://") {
+        let url = Url::parse(paper).map_err(|e| McpArxivError::InvalidArgument(e.to_string()))?;
+        let arxiv_id = url
+            .host_str()
+            .filter(|host| *host == "arxiv.org" || host.ends_with(".arxiv.org"))
+            .and_then(|_| {
+                let path = url.path();
+                path.strip_prefix("/abs/")
+                    .or_else(|| path.strip_prefix("/pdf/"))
+            })
+            .map(|id| id.trim_end_matches(".pdf").trim_end_matches('/'))
+            .filter(|id| !id.is_empty());
+        return Ok(match arxiv_id {
+            Some(id) => PdfSource::Arxiv(id.to_string()),
+            None => PdfSource::Remote(paper.to_string()),
+        });
+    }
+
+    let path = Path::new(paper);
+    if path.exists() {
+        return Ok(PdfSource::Local(path.to_path_buf()));
+    }
+    if paper.is_empty() || paper.contains(char::is_whitespace) {
+        return Err(McpArxivError::InvalidArgument(format!(
+            "`{}` is not an arXiv ID, URL or existing file",
+            paper
+        )));
+    }
+    Ok(PdfSource::Arxiv(paper.to_string()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_resolve_source() {
+        assert_eq!(
+            resolve_source("https://arxiv.org/abs/hep-th/9901001v2").unwrap(),
+            PdfSource::Arxiv("hep-th/9901001v2".to_string())
+        );
+        assert_eq!(
+            resolve_source("https://arxiv.org/pdf/2401.00001.pdf").unwrap(),
+            resolve_source("2401.00001").unwrap()
+        );
+        assert!(matches!(
+            resolve_source("https://example.com/paper.pdf").unwrap(),
+            PdfSource::Remote(url) if url == "https://example.com/paper.pdf"
+        ));
+        assert_eq!(
+            resolve_source("file:///tmp/paper.pdf").unwrap(),
+            PdfSource::Local(PathBuf::from("/tmp/paper.pdf"))
+        );
+        assert!(resolve_source("not a paper").is_err());
+    }
+
     #[tokio::test]
     async fn test_extract_paper_features_tool() {
         // Test with a known arXiv paper
-        match extract_paper_text_tool("https://arxiv.org/abs/2401.00001".to_string()).await {
+        match extract_paper_text_tool(
+            "https://arxiv.org/abs/2401.00001".to_string(),
+            Some(vec!["introduction".to_string()]),
+            Some("1-3".to_string()),
+        )
+        .await
+        {
             Ok(content) => {
                 println!("Extracted content: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
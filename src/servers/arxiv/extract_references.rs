@@ -0,0 +1,68 @@
+use super::errors::McpArxivError;
+use super::extract_paper_text::load_paper;
+use super::references::{Reference, parse_references};
+use super::structure::SectionKind;
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+use serde::Serialize;
+
+use crate::output;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtractReferencesResult {
+    count: usize,
+    references: Vec<Reference>,
+}
+
+#[tool(
+    name = "ExtractReferences",
+    description = "Parse the references section of a paper PDF into entries with the raw text, a best-effort authors/title/year split, and any arXiv IDs and DOIs found. Returns JSON.",
+    params(paper_url = "An arXiv ID or URL, any PDF URL, or a local PDF path")
+)]
+pub async fn extract_references_tool(paper_url: String) -> Result<ToolResponseContent> {
+    let paper = load_paper(&paper_url, None).await?;
+    let text = paper
+        .sections
+        .iter()
+        .filter(|s| s.kind == SectionKind::References)
+        .map(|s| s.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.trim().is_empty() {
+        return Err(McpArxivError::InvalidArgument(format!(
+            "no references section found in {}",
+            paper_url
+        ))
+        .into());
+    }
+
+    let references = parse_references(&text);
+    Ok(tool_text_content!(output::shape(
+        "ExtractReferences",
+        serde_json::to_string(&ExtractReferencesResult {
+            count: references.len(),
+            references,
+        })?
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_extract_references_tool() {
+        match extract_references_tool("1706.03762".to_string()).await {
+            Ok(content) => {
+                println!("References: {:?}", content);
+                assert!(format!("{:?}", content).contains("references"));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,713 @@
+//! Raster images embedded in PDFs.
+//!
+//! Images are found by following the `Do` operators of each page's content
+//! stream, including those of nested form XObjects, so they come out in the
+//! order they are drawn. JPEGs are returned as they are stored; other images
+//! are decoded and re-encoded as PNG, halving their size until they fit the
+//! size limit. Figures drawn as vector graphics have no image to extract.
+
+use flate2::Compression;
+use flate2::Crc;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use lopdf::content::Content;
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+use std::collections::HashSet;
+use std::io::{Read, Write};
+
+use super::errors::McpArxivError;
+use super::structure::parse_page_range;
+
+/// Images narrower or shorter than this are icons, logos or rules
+const MIN_SIDE: u32 = 64;
+/// Form XObjects nested deeper than this are not searched
+const MAX_FORM_DEPTH: usize = 8;
+/// Largest image decoded, its size comes from the PDF and is checked before
+/// anything is decompressed
+const MAX_PIXELS: usize = 50_000_000;
+/// Largest decompressed form XObject content stream
+const MAX_FORM_BYTES: usize = 16 * 1024 * 1024;
+/// Room for PNG predictor bytes and padding beyond an image's raw size
+const DECOMPRESS_SLACK: usize = 64 * 1024;
+
+/// Limits on what [`extract_images`] returns.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub max_images: usize,
+    /// Encoded size of a single image
+    pub max_image_bytes: usize,
+    /// Encoded size of all images together
+    pub max_total_bytes: usize,
+}
+
+/// An encoded image and the page it is first drawn on.
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub page: u32,
+    pub width: u32,
+    pub height: u32,
+    pub mime_type: &'static str,
+    pub data: Vec<u8>,
+}
+
+/// An image that could not be returned and why.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skipped {
+    pub page: u32,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct PageImages {
+    pub page_count: u32,
+    pub images: Vec<Image>,
+    pub skipped: Vec<Skipped>,
+    /// Whether more images were drawn after `max_images`, they are not decoded
+    /// so some may not have been extractable
+    pub truncated: bool,
+}
+
+/// Extracts the images drawn on the pages in `page_range` of `pdf`.
+pub fn extract_images(
+    pdf: &[u8],
+    page_range: Option<&str>,
+    limits: Limits,
+) -> Result<PageImages, McpArxivError> {
+    let doc = Document::load_mem(pdf).map_err(|e| McpArxivError::PdfError(e.to_string()))?;
+    let pages = doc.get_pages();
+    let page_count = pages.len() as u32;
+    let (first, last) = match page_range {
+        Some(range) => parse_page_range(range, page_count)?,
+        None => (1, page_count),
+    };
+
+    let mut result = PageImages {
+        page_count,
+        ..Default::default()
+    };
+    let mut seen = HashSet::new();
+    let mut total = 0;
+    'pages: for (&page, &page_id) in pages.range(first..=last) {
+        let mut ids = Vec::new();
+        if let Ok(content) = doc.get_page_content(page_id) {
+            drawn_images(
+                &doc,
+                page_resources(&doc, page_id),
+                &content,
+                0,
+                &mut seen,
+                &mut ids,
+            );
+        }
+
+        for id in ids {
+            let Ok(stream) = doc.get_object(id).and_then(Object::as_stream) else {
+                continue;
+            };
+            // Checked before decoding so the image past the limit is not decoded
+            if result.images.len() == limits.max_images {
+                result.truncated = true;
+                break 'pages;
+            }
+            let image = match encode(&doc, stream, limits.max_image_bytes) {
+                Ok(Some((width, height, mime_type, data))) => Image {
+                    page,
+                    width,
+                    height,
+                    mime_type,
+                    data,
+                },
+                Ok(None) => continue,
+                Err(reason) => {
+                    result.skipped.push(Skipped { page, reason });
+                    continue;
+                }
+            };
+            if total + image.data.len() > limits.max_total_bytes {
+                result.skipped.push(Skipped {
+                    page,
+                    reason: format!(
+                        "{} KB image would exceed the total size limit",
+                        image.data.len().div_ceil(1024)
+                    ),
+                });
+                continue;
+            }
+            total += image.data.len();
+            result.images.push(image);
+        }
+    }
+    Ok(result)
+}
+
+/// The resources of a page, which may be inherited from the page tree.
+fn page_resources(doc: &Document, page_id: ObjectId) -> Option<&Dictionary> {
+    let mut node = doc.get_dictionary(page_id).ok()?;
+    for _ in 0..32 {
+        if let Ok(resources) = doc.get_dict_in_dict(node, b"Resources") {
+            return Some(resources);
+        }
+        node = doc
+            .get_dictionary(node.get(b"Parent").ok()?.as_reference().ok()?)
+            .ok()?;
+    }
+    None
+}
+
+/// Appends the images drawn by `content` to `ids`, in drawing order.
+fn drawn_images(
+    doc: &Document,
+    resources: Option<&Dictionary>,
+    content: &[u8],
+    depth: usize,
+    seen: &mut HashSet<ObjectId>,
+    ids: &mut Vec<ObjectId>,
+) {
+    let Some(xobjects) = resources.and_then(|r| doc.get_dict_in_dict(r, b"XObject").ok()) else {
+        return;
+    };
+    let Ok(content) = Content::decode(content) else {
+        return;
+    };
+    for operation in content.operations.iter().filter(|op| op.operator == "Do") {
+        let Some(id) = operation
+            .operands
+            .first()
+            .and_then(|name| name.as_name().ok())
+            .and_then(|name| xobjects.get(name).ok())
+            .and_then(|xobject| xobject.as_reference().ok())
+        else {
+            continue;
+        };
+        if !seen.insert(id) {
+            continue;
+        }
+        let Ok(stream) = doc.get_object(id).and_then(Object::as_stream) else {
+            continue;
+        };
+        match stream.dict.get(b"Subtype").and_then(Object::as_name) {
+            Ok(b"Image") => ids.push(id),
+            Ok(b"Form") if depth < MAX_FORM_DEPTH => {
+                // Forms without resources use those of the page
+                let form_resources = doc
+                    .get_dict_in_dict(&stream.dict, b"Resources")
+                    .ok()
+                    .or(resources);
+                if let Ok(form) = plain_content(stream, MAX_FORM_BYTES) {
+                    drawn_images(doc, form_resources, &form, depth + 1, seen, ids);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+type Encoded = (u32, u32, &'static str, Vec<u8>);
+
+/// Encodes an image XObject as JPEG or PNG of at most `max_bytes`. Returns
+/// `None` for masks and images too small to be figures.
+fn encode(doc: &Document, stream: &Stream, max_bytes: usize) -> Result<Option<Encoded>, String> {
+    let dict = &stream.dict;
+    let width = dimension(doc, dict, b"Width");
+    let height = dimension(doc, dict, b"Height");
+    let image_mask = dict
+        .get_deref(b"ImageMask", doc)
+        .and_then(Object::as_bool)
+        .unwrap_or(false);
+    if image_mask || width < MIN_SIDE || height < MIN_SIDE {
+        return Ok(None);
+    }
+
+    let filters = stream.filters().unwrap_or_default();
+    match filters.last().copied() {
+        Some(b"DCTDecode") if filters.len() == 1 => {
+            if stream.content.len() > max_bytes {
+                return Err(format!(
+                    "{} KB JPEG exceeds the image size limit",
+                    stream.content.len().div_ceil(1024)
+                ));
+            }
+            if let Ok(ColorSpace::Cmyk) = color_space(doc, dict) {
+                return Err("CMYK JPEGs are not supported".to_string());
+            }
+            Ok(Some((width, height, "image/jpeg", stream.content.clone())))
+        }
+        Some(b"DCTDecode") => Err("JPEGs with other filters are not supported".to_string()),
+        Some(b"JPXDecode") => Err("JPEG 2000 images are not supported".to_string()),
+        Some(b"CCITTFaxDecode" | b"JBIG2Decode") => {
+            Err("fax and JBIG2 images are not supported".to_string())
+        }
+        _ => {
+            let mut raster = decode(doc, stream)?;
+            if let Some(mask) = dict
+                .get(b"SMask")
+                .and_then(Object::as_reference)
+                .and_then(|id| doc.get_object(id))
+                .and_then(Object::as_stream)
+                .ok()
+                .and_then(|mask| decode(doc, mask).ok())
+            {
+                raster.flatten(&mask);
+            }
+            loop {
+                let png = raster.png();
+                if png.len() <= max_bytes {
+                    return Ok(Some((raster.width, raster.height, "image/png", png)));
+                }
+                if raster.width / 2 < MIN_SIDE || raster.height / 2 < MIN_SIDE {
+                    return Err(format!(
+                        "{} KB image exceeds the image size limit",
+                        png.len().div_ceil(1024)
+                    ));
+                }
+                raster = raster.half();
+            }
+        }
+    }
+}
+
+fn dimension(doc: &Document, dict: &Dictionary, key: &[u8]) -> u32 {
+    dict.get_deref(key, doc)
+        .and_then(Object::as_i64)
+        .map_or(0, |v| v.clamp(0, u32::MAX as i64) as u32)
+}
+
+enum ColorSpace {
+    Gray,
+    Rgb,
+    Cmyk,
+    /// Base color space and its colors, one entry per index
+    Indexed(Box<ColorSpace>, Vec<u8>),
+}
+
+impl ColorSpace {
+    fn components(&self) -> usize {
+        match self {
+            ColorSpace::Gray | ColorSpace::Indexed(..) => 1,
+            ColorSpace::Rgb => 3,
+            ColorSpace::Cmyk => 4,
+        }
+    }
+}
+
+fn color_space(doc: &Document, dict: &Dictionary) -> Result<ColorSpace, String> {
+    // Soft masks have no color space and are gray
+    match dict.get_deref(b"ColorSpace", doc) {
+        Ok(object) => resolve_color_space(doc, object),
+        Err(_) => Ok(ColorSpace::Gray),
+    }
+}
+
+fn resolve_color_space(doc: &Document, object: &Object) -> Result<ColorSpace, String> {
+    let unsupported = |name: &[u8]| {
+        format!(
+            "images in the {} color space are not supported",
+            String::from_utf8_lossy(name)
+        )
+    };
+    let object = doc.dereference(object).map_err(|e| e.to_string())?.1;
+    if let Ok(name) = object.as_name() {
+        return match name {
+            b"DeviceGray" | b"CalGray" | b"G" => Ok(ColorSpace::Gray),
+            b"DeviceRGB" | b"CalRGB" | b"RGB" => Ok(ColorSpace::Rgb),
+            b"DeviceCMYK" | b"CMYK" => Ok(ColorSpace::Cmyk),
+            _ => Err(unsupported(name)),
+        };
+    }
+
+    let array = object.as_array().map_err(|e| e.to_string())?;
+    let family = array
+        .first()
+        .and_then(|family| family.as_name().ok())
+        .unwrap_or_default();
+    match family {
+        b"CalGray" => Ok(ColorSpace::Gray),
+        b"CalRGB" => Ok(ColorSpace::Rgb),
+        b"ICCBased" => {
+            let profile = array
+                .get(1)
+                .and_then(|profile| profile.as_reference().ok())
+                .and_then(|id| doc.get_object(id).and_then(Object::as_stream).ok())
+                .ok_or("invalid ICC profile")?;
+            match profile.dict.get(b"N").and_then(Object::as_i64) {
+                Ok(1) => Ok(ColorSpace::Gray),
+                Ok(3) => Ok(ColorSpace::Rgb),
+                Ok(4) => Ok(ColorSpace::Cmyk),
+                _ => Err("invalid ICC profile".to_string()),
+            }
+        }
+        b"Indexed" | b"I" => {
+            let base = resolve_color_space(doc, array.get(1).ok_or("invalid indexed colors")?)?;
+            if matches!(base, ColorSpace::Indexed(..)) {
+                return Err("invalid indexed colors".to_string());
+            }
+            let lookup = doc
+                .dereference(array.get(3).ok_or("invalid indexed colors")?)
+                .map_err(|e| e.to_string())?
+                .1;
+            let colors = match lookup {
+                // At most 256 colors of up to 4 components
+                Object::Stream(stream) => plain_content(stream, 256 * 4)?,
+                other => other.as_str().map_err(|e| e.to_string())?.to_vec(),
+            };
+            Ok(ColorSpace::Indexed(Box::new(base), colors))
+        }
+        _ => Err(unsupported(family)),
+    }
+}
+
+/// 8-bit gray or RGB pixels.
+#[derive(Debug, Clone)]
+struct Raster {
+    width: u32,
+    height: u32,
+    channels: usize,
+    pixels: Vec<u8>,
+}
+
+/// Decompresses `stream`, failing once a Flate stage produces more than
+/// `limit` bytes so a small compressed stream cannot expand without bound.
+fn plain_content(stream: &Stream, limit: usize) -> Result<Vec<u8>, String> {
+    let filters = stream.filters().unwrap_or_default();
+    if filters.first().copied() == Some(b"FlateDecode".as_slice()) {
+        let mut inflated = Vec::new();
+        ZlibDecoder::new(stream.content.as_slice())
+            .take(limit as u64 + 1)
+            .read_to_end(&mut inflated)
+            .map_err(|e| format!("failed to decompress image: {}", e))?;
+        if inflated.len() > limit {
+            return Err("decompressed data is larger than declared".to_string());
+        }
+        // Predictors and further filters are left to lopdf
+        if filters.len() == 1 && stream.dict.get(b"DecodeParms").is_err() {
+            return Ok(inflated);
+        }
+    }
+    stream
+        .get_plain_content()
+        .map_err(|e| format!("failed to decompress image: {}", e))
+}
+
+fn decode(doc: &Document, stream: &Stream) -> Result<Raster, String> {
+    let dict = &stream.dict;
+    let width = dimension(doc, dict, b"Width") as usize;
+    let height = dimension(doc, dict, b"Height") as usize;
+    if width.checked_mul(height).is_none_or(|n| n > MAX_PIXELS) {
+        return Err(format!(
+            "{}x{} image exceeds the {} megapixel limit",
+            width,
+            height,
+            MAX_PIXELS / 1_000_000
+        ));
+    }
+    let bits = dict
+        .get_deref(b"BitsPerComponent", doc)
+        .and_then(Object::as_i64)
+        .unwrap_or(8) as usize;
+    if ![1, 2, 4, 8, 16].contains(&bits) {
+        return Err(format!("{}-bit images are not supported", bits));
+    }
+    let color_space = color_space(doc, dict)?;
+    let components = color_space.components();
+    let too_large = || "image is too large".to_string();
+    let row_bytes = width
+        .checked_mul(components * bits)
+        .ok_or_else(too_large)?
+        .div_ceil(8);
+    let size = row_bytes.checked_mul(height).ok_or_else(too_large)?;
+    // One predictor byte per row
+    let data = plain_content(stream, size + height + DECOMPRESS_SLACK)?;
+    if data.len() < size {
+        return Err("image data is truncated".to_string());
+    }
+    // A `Decode` array of [1 0] inverts gray images
+    let invert = matches!(color_space, ColorSpace::Gray)
+        && dict
+            .get_deref(b"Decode", doc)
+            .and_then(Object::as_array)
+            .ok()
+            .and_then(|decode| decode.first())
+            .and_then(|first| first.as_float().ok())
+            .is_some_and(|first| first >= 1.0);
+
+    let max = ((1u32 << bits.min(8)) - 1) as usize;
+    let channels = match &color_space {
+        ColorSpace::Gray => 1,
+        ColorSpace::Indexed(base, _) if matches!(**base, ColorSpace::Gray) => 1,
+        _ => 3,
+    };
+    let mut pixels = Vec::with_capacity(width * height * channels);
+    let mut samples = Vec::with_capacity(components);
+    for row in data.chunks(row_bytes).take(height) {
+        for x in 0..width {
+            samples.clear();
+            for c in 0..components {
+                let i = x * components + c;
+                let sample = match bits {
+                    16 => row[i * 2] as usize,
+                    8 => row[i] as usize,
+                    _ => (row[i * bits / 8] as usize >> (8 - bits - (i * bits) % 8)) & max,
+                };
+                samples.push(sample);
+            }
+            match &color_space {
+                ColorSpace::Indexed(base, colors) => {
+                    let n = base.components();
+                    let start = samples[0] * n;
+                    let color = colors.get(start..start + n).unwrap_or(&[0, 0, 0, 0][..n]);
+                    push_color(&mut pixels, base, color.iter().map(|&v| v as usize), 255);
+                }
+                ColorSpace::Gray if invert => pixels.push((255 - samples[0] * 255 / max) as u8),
+                _ => push_color(&mut pixels, &color_space, samples.iter().copied(), max),
+            }
+        }
+    }
+    Ok(Raster {
+        width: width as u32,
+        height: height as u32,
+        channels,
+        pixels,
+    })
+}
+
+/// Appends a gray or RGB pixel from components scaled to `0..=max`.
+fn push_color(
+    pixels: &mut Vec<u8>,
+    color_space: &ColorSpace,
+    components: impl Iterator<Item = usize>,
+    max: usize,
+) {
+    let scaled: Vec<usize> = components.map(|v| v.min(max) * 255 / max).collect();
+    match color_space {
+        ColorSpace::Cmyk => {
+            let k = scaled[3];
+            for c in &scaled[..3] {
+                pixels.push(255 - (c + k).min(255) as u8);
+            }
+        }
+        _ => pixels.extend(scaled.iter().map(|&v| v as u8)),
+    }
+}
+
+impl Raster {
+    /// Blends the image onto white with `mask` as its alpha channel.
+    fn flatten(&mut self, mask: &Raster) {
+        if mask.width != self.width || mask.height != self.height || mask.channels != 1 {
+            return;
+        }
+        for (pixel, &alpha) in self.pixels.chunks_mut(self.channels).zip(&mask.pixels) {
+            for v in pixel {
+                let alpha = alpha as u32;
+                *v = ((*v as u32 * alpha + 255 * (255 - alpha)) / 255) as u8;
+            }
+        }
+    }
+
+    /// The image at half its width and height, averaging each 2x2 block.
+    fn half(&self) -> Raster {
+        let (width, height) = (self.width as usize / 2, self.height as usize / 2);
+        let stride = self.width as usize * self.channels;
+        let mut pixels = Vec::with_capacity(width * height * self.channels);
+        for y in 0..height {
+            for x in 0..width {
+                for c in 0..self.channels {
+                    let at = |dx: usize, dy: usize| {
+                        self.pixels[(2 * y + dy) * stride + (2 * x + dx) * self.channels + c] as u32
+                    };
+                    pixels.push(((at(0, 0) + at(1, 0) + at(0, 1) + at(1, 1) + 2) / 4) as u8);
+                }
+            }
+        }
+        Raster {
+            width: width as u32,
+            height: height as u32,
+            channels: self.channels,
+            pixels,
+        }
+    }
+
+    fn png(&self) -> Vec<u8> {
+        let stride = self.width as usize * self.channels;
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        for row in self.pixels.chunks(stride.max(1)) {
+            // No filter
+            let _ = encoder.write_all(&[0]);
+            let _ = encoder.write_all(row);
+        }
+        let data = encoder.finish().unwrap_or_default();
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        // 8 bits per sample, gray or RGB, default compression, filtering and
+        // no interlacing
+        header.extend_from_slice(&[8, if self.channels == 1 { 0 } else { 2 }, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"IDAT", &data);
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    let mut crc = Crc::new();
+    crc.update(kind);
+    crc.update(data);
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    png.extend_from_slice(&crc.sum().to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::dictionary;
+
+    const LIMITS: Limits = Limits {
+        max_images: 10,
+        max_image_bytes: 1024 * 1024,
+        max_total_bytes: 4 * 1024 * 1024,
+    };
+
+    fn image(doc: &mut Document, width: i64, height: i64, filter: &str, data: Vec<u8>) -> ObjectId {
+        let mut dict = dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => width,
+            "Height" => height,
+            "ColorSpace" => "DeviceRGB",
+            "BitsPerComponent" => 8,
+        };
+        if !filter.is_empty() {
+            dict.set("Filter", Object::Name(filter.as_bytes().to_vec()));
+        }
+        doc.add_object(Stream::new(dict, data))
+    }
+
+    /// A one-page PDF drawing a 100x80 RGB image from a form, a tiny image
+    /// and a JPEG 2000 image, in that order.
+    fn pdf() -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pixels: Vec<u8> = (0..100 * 80)
+            .flat_map(|i| [(i % 256) as u8, 0, 255])
+            .collect();
+        let photo = image(&mut doc, 100, 80, "", pixels);
+        let icon = image(&mut doc, 8, 8, "", vec![0; 8 * 8 * 3]);
+        let jpx = image(&mut doc, 200, 200, "JPXDecode", vec![0; 16]);
+        let form = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Form",
+                "BBox" => vec![0.into(), 0.into(), 100.into(), 80.into()],
+                "Resources" => dictionary! { "XObject" => dictionary! { "Im0" => photo } },
+            },
+            b"q 100 0 0 80 0 0 cm /Im0 Do Q".to_vec(),
+        ));
+
+        let pages_id = doc.new_object_id();
+        let contents = doc.add_object(Stream::new(
+            dictionary! {},
+            b"/Fm0 Do /Im1 Do /Im2 Do /Fm0 Do".to_vec(),
+        ));
+        let page = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => contents,
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page.into()],
+                "Count" => 1,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+                "Resources" => dictionary! {
+                    "XObject" => dictionary! { "Fm0" => form, "Im1" => icon, "Im2" => jpx },
+                },
+            }),
+        );
+        let catalog = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        doc.trailer.set("Root", catalog);
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_extract_images() {
+        let result = extract_images(&pdf(), None, LIMITS).unwrap();
+        assert_eq!(result.page_count, 1);
+        assert_eq!(result.images.len(), 1);
+        let image = &result.images[0];
+        assert_eq!((image.page, image.width, image.height), (1, 100, 80));
+        assert_eq!(image.mime_type, "image/png");
+        assert!(image.data.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert_eq!(
+            result.skipped,
+            [Skipped {
+                page: 1,
+                reason: "JPEG 2000 images are not supported".to_string()
+            }]
+        );
+        assert!(!result.truncated);
+
+        let truncated = extract_images(
+            &pdf(),
+            Some("1"),
+            Limits {
+                max_images: 0,
+                ..LIMITS
+            },
+        )
+        .unwrap();
+        assert!(truncated.images.is_empty() && truncated.truncated);
+        assert!(extract_images(&pdf(), Some("2"), LIMITS).is_err());
+    }
+
+    #[test]
+    fn test_images_are_scaled_to_fit() {
+        // Noise does not compress, so the PNG is about as large as the pixels
+        let mut seed = 1u32;
+        let pixels: Vec<u8> = (0..256 * 256)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (seed >> 24) as u8
+            })
+            .collect();
+        let mut doc = Document::with_version("1.5");
+        let id = image(&mut doc, 256, 256, "", pixels);
+        let stream = doc.get_object_mut(id).unwrap().as_stream_mut().unwrap();
+        stream.dict.set("ColorSpace", "DeviceGray");
+        let stream = doc.get_object(id).unwrap().as_stream().unwrap();
+
+        let (width, height, _, png) = encode(&doc, stream, 20 * 1024).unwrap().unwrap();
+        assert_eq!((width, height), (128, 128));
+        assert!(png.len() <= 20 * 1024);
+        assert!(encode(&doc, stream, 1024).is_err());
+    }
+
+    #[test]
+    fn test_oversized_images_are_not_decompressed() {
+        let mut zeros = ZlibEncoder::new(Vec::new(), Compression::fast());
+        zeros.write_all(&vec![0; 2 * 1024 * 1024]).unwrap();
+        let zeros = zeros.finish().unwrap();
+
+        let mut doc = Document::with_version("1.5");
+        let huge = image(&mut doc, 100_000, 100_000, "FlateDecode", zeros.clone());
+        let bomb = image(&mut doc, 100, 80, "FlateDecode", zeros);
+        let reason = |id| {
+            let stream = doc.get_object(id).unwrap().as_stream().unwrap();
+            encode(&doc, stream, 1024 * 1024).unwrap_err()
+        };
+
+        assert_eq!(
+            reason(huge),
+            "100000x100000 image exceeds the 50 megapixel limit"
+        );
+        assert_eq!(reason(bomb), "decompressed data is larger than declared");
+    }
+}
@@ -0,0 +1,188 @@
+use super::atom::{Paper, fetch_papers, query, split_version};
+use super::errors::McpArxivError;
+use super::similarity::{key_terms, rank};
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+use serde::Serialize;
+
+/// Key terms searched for in the source paper's categories
+const CATEGORY_TERMS: usize = 6;
+/// Key terms that must all appear in papers from other categories
+const CROSS_CATEGORY_TERMS: usize = 3;
+const MAX_CANDIDATES: u32 = 500;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RelatedPaper {
+    id: String,
+    title: String,
+    authors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    primary_category: Option<String>,
+    published: String,
+    score: f64,
+    shared_terms: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RelatedPapers {
+    id: String,
+    title: String,
+    key_terms: Vec<String>,
+    candidates: usize,
+    related: Vec<RelatedPaper>,
+}
+
+#[tool(
+    name = "FindRelatedPapers",
+    description = "Find papers related to an arXiv paper. Searches arXiv for papers in the same categories that share key terms of its title and abstract, then ranks them by TF-IDF cosine similarity of title and abstract. Returns JSON with the key terms searched for and the related papers with their score (0 to 1) and shared terms.",
+    params(
+        id = "The arXiv ID (e.g. '1706.03762')",
+        limit = "Maximum number of related papers to return (default: 10)",
+        max_candidates = "Maximum number of search results to rank (default: 150, at most 500)"
+    )
+)]
+pub async fn find_related_papers_tool(
+    id: String,
+    limit: Option<u32>,
+    max_candidates: Option<u32>,
+) -> Result<ToolResponseContent> {
+    let (id, _) = split_version(id.trim());
+    let source = fetch_papers(std::slice::from_ref(&id)).await?.remove(0);
+    let terms = key_terms(&source, CATEGORY_TERMS);
+    if terms.is_empty() {
+        return Err(McpArxivError::InvalidArgument(format!(
+            "{} has no title or abstract terms to search for",
+            id
+        ))
+        .into());
+    }
+
+    let max_candidates = max_candidates.unwrap_or(150).clamp(1, MAX_CANDIDATES);
+    let mut candidates = Vec::new();
+    let queries = candidate_queries(&source, &terms, max_candidates);
+    for (search_query, max_results) in queries {
+        let feed = query(&[
+            ("search_query", search_query),
+            ("sortBy", "relevance".to_string()),
+            ("max_results", max_results.to_string()),
+        ])
+        .await?;
+        for paper in feed.papers {
+            if paper.id != source.id && !candidates.iter().any(|c: &Paper| c.id == paper.id) {
+                candidates.push(paper);
+            }
+        }
+    }
+
+    let related = rank(&source, &candidates)
+        .into_iter()
+        .take(limit.unwrap_or(10) as usize)
+        .map(|similarity| {
+            let paper = &candidates[similarity.index];
+            RelatedPaper {
+                id: paper.id.clone(),
+                title: paper.title.split_whitespace().collect::<Vec<_>>().join(" "),
+                authors: paper.authors.iter().map(|a| a.name.clone()).collect(),
+                primary_category: paper.primary_category.clone(),
+                published: paper.published.clone(),
+                score: similarity.score,
+                shared_terms: similarity.shared_terms,
+            }
+        })
+        .collect();
+
+    Ok(tool_text_content!(
+        serde_json::to_string(&RelatedPapers {
+            id,
+            title: source
+                .title
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+            key_terms: terms,
+            candidates: candidates.len(),
+            related,
+        })
+        .map_err(McpArxivError::ResponseSerializeError)?
+    ))
+}
+
+/// Queries for candidates and how many results to take from each: papers in
+/// the source's categories with any of its key terms, and papers anywhere
+/// with all of its top key terms.
+fn candidate_queries(source: &Paper, terms: &[String], max_candidates: u32) -> Vec<(String, u32)> {
+    let any_term = terms
+        .iter()
+        .map(|t| format!("ti:{0} OR abs:{0}", t))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+    let all_terms = terms
+        .iter()
+        .take(CROSS_CATEGORY_TERMS)
+        .map(|t| format!("abs:{}", t))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let categories = source
+        .primary_category
+        .iter()
+        .chain(&source.secondary_categories)
+        .map(|c| format!("cat:{}", c))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+
+    if categories.is_empty() {
+        return vec![(format!("({})", any_term), max_candidates)];
+    }
+    let cross_category = max_candidates / 3;
+    let mut queries = vec![(
+        format!("({}) AND ({})", categories, any_term),
+        max_candidates - cross_category,
+    )];
+    if cross_category > 0 {
+        queries.push((format!("({})", all_terms), cross_category));
+    }
+    queries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_queries() {
+        let source = Paper {
+            primary_category: Some("cs.CL".to_string()),
+            secondary_categories: vec!["cs.LG".to_string()],
+            ..Default::default()
+        };
+        let terms = ["attention", "transformer"].map(String::from);
+        assert_eq!(
+            candidate_queries(&source, &terms, 150),
+            [
+                (
+                    "(cat:cs.CL OR cat:cs.LG) AND (ti:attention OR abs:attention OR ti:transformer OR abs:transformer)"
+                        .to_string(),
+                    100
+                ),
+                ("(abs:attention AND abs:transformer)".to_string(), 50),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_related_papers_tool() {
+        match find_related_papers_tool("1706.03762".to_string(), Some(5), Some(30)).await {
+            Ok(content) => {
+                println!("Related papers: {:?}", content);
+                assert!(format!("{:?}", content).contains("sharedTerms"));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
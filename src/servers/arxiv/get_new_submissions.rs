@@ -0,0 +1,78 @@
+use super::errors::McpArxivError;
+use super::watcher::{self, Match, WatchConfig};
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+use serde::Serialize;
+
+use crate::output;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NewSubmissions {
+    /// Unix seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_poll: Option<u64>,
+    categories: Vec<String>,
+    count: usize,
+    /// Pass back as `ack` to remove these papers, ahead of them so it survives
+    /// truncation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+    papers: Vec<Match>,
+}
+
+#[tool(
+    name = "GetNewSubmissions",
+    description = "Get new papers in the watched arXiv categories (ARXIV_WATCH_CATEGORIES) that match the watch keywords or authors. Returns JSON with lastPoll, count, papers, each with the filters it matched, and a cursor. Papers are returned again until the cursor is passed back as ack. Subscribe to the arxiv://watch/new-submissions resource to be notified of new matches.",
+    params(
+        poll = "Check arXiv now instead of waiting for the next scheduled poll (default: false)",
+        ack = "Cursor from an earlier response, its papers are removed before returning the rest"
+    )
+)]
+pub async fn get_new_submissions_tool(
+    poll: Option<bool>,
+    ack: Option<String>,
+) -> Result<ToolResponseContent> {
+    let config = WatchConfig::from_env()?;
+    if poll.unwrap_or(false) {
+        let config = config.as_ref().ok_or_else(|| {
+            McpArxivError::InvalidArgument(
+                "no categories are watched, set ARXIV_WATCH_CATEGORIES (e.g. cs.CL,cs.LG)"
+                    .to_string(),
+            )
+        })?;
+        watcher::poll(config).await?;
+    }
+
+    let checkpoint = watcher::pending(ack.as_deref()).await?;
+    Ok(tool_text_content!(output::shape(
+        "GetNewSubmissions",
+        serde_json::to_string(&NewSubmissions {
+            last_poll: checkpoint.last_poll,
+            categories: config.map(|c| c.categories).unwrap_or_default(),
+            count: checkpoint.pending.len(),
+            cursor: checkpoint.cursor().map(str::to_string),
+            papers: checkpoint.pending,
+        })?
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_new_submissions_tool() {
+        match get_new_submissions_tool(None, None).await {
+            Ok(content) => {
+                println!("New submissions: {:?}", content);
+                assert!(format!("{:?}", content).contains("\\\"count\\\""));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
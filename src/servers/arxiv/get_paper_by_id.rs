@@ -1,39 +1,24 @@
+use super::atom::fetch_papers;
 use super::errors::McpArxivError;
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
-use reqwest::Client;
 
 #[tool(
     name = "GetPaperById",
-    description = "Fetch a specific paper by its ArXiv ID.",
+    description = "Fetch a specific paper by its ArXiv ID. Returns the paper as JSON.",
     params(id = "The ArXiv ID (e.g. '2101.00001v2')")
 )]
 pub async fn get_paper_by_id_tool(id: String) -> Result<ToolResponseContent> {
-    let client = Client::new();
-    let base_url = "http://export.arxiv.org/api/query";
-
-    // Build query parameters
-    let params = [("id_list", id)];
-
-    // Send the request
-    let res = client
-        .get(base_url)
-        .query(&params)
-        .send()
-        .await
-        .map_err(McpArxivError::HttpError)?;
-
-    // Check if the response is successful
-    if !res.status().is_success() {
-        let error_text = res.text().await.map_err(McpArxivError::HttpError)?;
-        return Err(McpArxivError::ApiError(format!("ArXiv API error: {}", error_text)).into());
-    }
-
-    // Parse the response
-    let result = res.text().await.map_err(McpArxivError::HttpError)?;
-
-    Ok(tool_text_content!(result))
+    let paper = fetch_papers(&[id])
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| McpArxivError::ApiError("Empty response".to_string()))?;
+
+    Ok(tool_text_content!(
+        serde_json::to_string(&paper).map_err(McpArxivError::ResponseSerializeError)?
+    ))
 }
 
 #[cfg(test)]
@@ -45,7 +30,7 @@ mod tests {
         match get_paper_by_id_tool("2101.00001v2".to_string()).await {
             Ok(content) => {
                 println!("Paper result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -0,0 +1,148 @@
+use super::client;
+use super::errors::McpArxivError;
+use super::latex::{SourceTree, select_section, strip_comments};
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+use serde::Serialize;
+
+use crate::output;
+
+const DEFAULT_MAX_SOURCE_MB: u64 = 50;
+
+/// `ARXIV_MAX_SOURCE_MB` in bytes.
+fn max_source_bytes() -> u64 {
+    crate::config::var("ARXIV_MAX_SOURCE_MB")
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|mb| *mb > 0)
+        .unwrap_or(DEFAULT_MAX_SOURCE_MB)
+        * 1024
+        * 1024
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PaperSource {
+    main_file: String,
+    /// Text files in the archive, figures are not listed
+    files: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    missing_files: Vec<String>,
+    /// Files that include themselves, their commands are left in place
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    include_cycles: Vec<String>,
+    /// Set when the inlined source hit the size cap and was cut short
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+    latex: String,
+}
+
+#[tool(
+    name = "GetPaperSource",
+    description = "Download the LaTeX source of an arXiv paper. Finds the main .tex file, inlines \\input and \\include files and returns JSON with mainFile, files and the latex. Optionally strips comments or returns a single section.",
+    params(
+        id = "The arXiv ID (e.g. '2101.00001v2')",
+        strip_comments = "Remove LaTeX comments (default: false)",
+        section = "Return only the section whose title contains this text, or 'abstract'"
+    )
+)]
+pub async fn get_paper_source_tool(
+    id: String,
+    strip_comments: Option<bool>,
+    section: Option<String>,
+) -> Result<ToolResponseContent> {
+    let id = id.trim().trim_start_matches("arXiv:");
+    if id.is_empty() || id.contains(char::is_whitespace) || id.contains("..") {
+        return Err(McpArxivError::InvalidArgument(format!("invalid arXiv ID `{}`", id)).into());
+    }
+
+    let bytes = download_eprint(id).await?;
+    let tree = tokio::task::spawn_blocking(move || SourceTree::from_eprint(&bytes))
+        .await
+        .map_err(|e| McpArxivError::SourceError(e.to_string()))??;
+    let main_file = tree
+        .main_file()
+        .ok_or_else(|| {
+            McpArxivError::SourceError("no .tex file with \\documentclass found".to_string())
+        })?
+        .to_string();
+    let inlined = tree.inline(&main_file)?;
+    let mut latex = inlined.latex;
+
+    if strip_comments.unwrap_or(false) {
+        latex = self::strip_comments(&latex);
+    }
+    if let Some(section) = section {
+        latex = select_section(&latex, &section)?;
+    }
+
+    Ok(tool_text_content!(output::shape(
+        "GetPaperSource",
+        serde_json::to_string(&PaperSource {
+            main_file,
+            files: tree.files.keys().cloned().collect(),
+            missing_files: inlined.missing,
+            include_cycles: inlined.cycles,
+            truncated: inlined.truncated,
+            latex,
+        })?
+    )))
+}
+
+/// Downloads an e-print, stopping once it passes `ARXIV_MAX_SOURCE_MB`.
+async fn download_eprint(id: &str) -> Result<Vec<u8>, McpArxivError> {
+    let limit = max_source_bytes();
+    let too_large = || {
+        McpArxivError::SourceError(format!(
+            "the source of {} is larger than the {} MB limit (ARXIV_MAX_SOURCE_MB)",
+            id,
+            limit / 1024 / 1024
+        ))
+    };
+
+    let mut res = client::get(&format!("https://arxiv.org/e-print/{}", id), &[]).await?;
+    if !res.status().is_success() {
+        return Err(McpArxivError::ApiError(format!(
+            "Failed to download source for {}: {}",
+            id,
+            res.status()
+        )));
+    }
+    if res.content_length().is_some_and(|len| len > limit) {
+        return Err(too_large());
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = res.chunk().await.map_err(McpArxivError::HttpError)? {
+        if (bytes.len() + chunk.len()) as u64 > limit {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_paper_source_tool() {
+        match get_paper_source_tool(
+            "1706.03762".to_string(),
+            Some(true),
+            Some("introduction".to_string()),
+        )
+        .await
+        {
+            Ok(content) => {
+                println!("Source: {:?}", content);
+                assert!(format!("{:?}", content).contains("mainFile"));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
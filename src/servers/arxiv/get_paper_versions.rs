@@ -0,0 +1,185 @@
+use super::atom::{fetch_papers, split_version};
+use super::errors::McpArxivError;
+use super::oai::{ArXivRawMetadata, ArXivVersion, Metadata, OaiClient};
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+use serde::Serialize;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaperVersion {
+    pub version: u32,
+    /// ISO 8601 when arXiv's date could be parsed, as given otherwise
+    pub submitted: String,
+    /// As reported by arXiv, e.g. `11kb`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PaperVersions {
+    id: String,
+    title: String,
+    latest_version: u32,
+    versions: Vec<PaperVersion>,
+}
+
+#[tool(
+    name = "GetPaperVersions",
+    description = "List all versions of an arXiv paper with their submission dates, sizes, source types and comments. Returns JSON with id, title, latestVersion and versions.",
+    params(id = "The arXiv ID, with or without version (e.g. '2101.00001')")
+)]
+pub async fn get_paper_versions_tool(id: String) -> Result<ToolResponseContent> {
+    let (id, _) = split_version(id.trim());
+    let raw = fetch_raw_metadata(&id).await?;
+    let mut versions = versions(&raw);
+
+    // arXivRaw only has the comments of the latest version, the API has them
+    // for each version
+    let ids: Vec<String> = versions
+        .iter()
+        .map(|v| format!("{}v{}", id, v.version))
+        .collect();
+    match fetch_papers(&ids).await {
+        Ok(papers) => {
+            for (version, paper) in versions.iter_mut().zip(papers) {
+                version.comments = paper.comments;
+            }
+        }
+        Err(e) => {
+            tracing::warn!("Failed to fetch comments of each version of {}: {}", id, e);
+            if let Some(latest) = versions.last_mut() {
+                latest.comments = raw.comments.clone();
+            }
+        }
+    }
+
+    Ok(tool_text_content!(
+        serde_json::to_string(&PaperVersions {
+            id,
+            title: raw.title.split_whitespace().collect::<Vec<_>>().join(" "),
+            latest_version: versions.last().map_or(1, |v| v.version),
+            versions,
+        })
+        .map_err(McpArxivError::ResponseSerializeError)?
+    ))
+}
+
+/// The `arXivRaw` record of a paper, which lists all of its versions.
+pub async fn fetch_raw_metadata(id: &str) -> Result<ArXivRawMetadata, McpArxivError> {
+    let record = OaiClient::default().get_record(id, "arXivRaw").await?;
+    match record.metadata {
+        Some(Metadata::ArXivRaw(raw)) => Ok(raw),
+        _ => Err(McpArxivError::PaperNotFound(id.to_string())),
+    }
+}
+
+/// Versions in `raw`, oldest first.
+pub fn versions(raw: &ArXivRawMetadata) -> Vec<PaperVersion> {
+    let mut versions: Vec<PaperVersion> = raw
+        .versions
+        .iter()
+        .enumerate()
+        .map(|(i, v)| version(v, i as u32 + 1))
+        .collect();
+    versions.sort_by_key(|v| v.version);
+    versions
+}
+
+fn version(v: &ArXivVersion, position: u32) -> PaperVersion {
+    PaperVersion {
+        version: v
+            .version
+            .trim_start_matches('v')
+            .parse()
+            .unwrap_or(position),
+        submitted: parse_date(&v.date).unwrap_or_else(|| v.date.clone()),
+        size_bytes: v.size.as_deref().and_then(parse_size),
+        size: v.size.clone(),
+        source_type: v.source_type.clone(),
+        comments: None,
+    }
+}
+
+/// `Mon, 2 Apr 2007 19:18:42 GMT` to `2007-04-02T19:18:42Z`.
+fn parse_date(date: &str) -> Option<String> {
+    let parts: Vec<&str> = date.split_whitespace().collect();
+    let [_, day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+    let month = MONTHS.iter().position(|m| m == month)? + 1;
+    let day: u32 = day.parse().ok()?;
+    let year: u32 = year.parse().ok()?;
+    Some(format!("{:04}-{:02}-{:02}T{}Z", year, month, day, time))
+}
+
+/// `11kb` to bytes.
+fn parse_size(size: &str) -> Option<u64> {
+    let size = size.trim().to_ascii_lowercase();
+    let (number, unit) = size.split_at(size.find(|c: char| !c.is_ascii_digit())?);
+    let number: u64 = number.parse().ok()?;
+    match unit.trim() {
+        "b" => Some(number),
+        "kb" => Some(number * 1024),
+        "mb" => Some(number * 1024 * 1024),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions() {
+        let raw = ArXivRawMetadata {
+            versions: vec![
+                ArXivVersion {
+                    version: "v1".to_string(),
+                    date: "Mon, 2 Apr 2007 19:18:42 GMT".to_string(),
+                    size: Some("10kb".to_string()),
+                    source_type: Some("D".to_string()),
+                },
+                ArXivVersion {
+                    version: "v2".to_string(),
+                    date: "sometime".to_string(),
+                    size: Some("1mb".to_string()),
+                    source_type: None,
+                },
+            ],
+            ..Default::default()
+        };
+        let versions = versions(&raw);
+        assert_eq!(versions[0].version, 1);
+        assert_eq!(versions[0].submitted, "2007-04-02T19:18:42Z");
+        assert_eq!(versions[0].size_bytes, Some(10240));
+        assert_eq!(versions[1].submitted, "sometime");
+        assert_eq!(versions[1].size_bytes, Some(1048576));
+    }
+
+    #[tokio::test]
+    async fn test_get_paper_versions_tool() {
+        match get_paper_versions_tool("0704.0001".to_string()).await {
+            Ok(content) => {
+                println!("Versions: {:?}", content);
+                assert!(format!("{:?}", content).contains("latestVersion"));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
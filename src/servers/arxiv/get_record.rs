@@ -0,0 +1,46 @@
+use super::errors::McpArxivError;
+use super::oai::OaiClient;
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+
+#[tool(
+    name = "GetRecord",
+    description = "Fetch the OAI-PMH metadata record of a single ArXiv paper as JSON.",
+    params(
+        identifier = "The ArXiv ID (e.g. '0704.0001') or OAI identifier (e.g. 'oai:arXiv.org:0704.0001')",
+        metadata_prefix = "Metadata format: oai_dc, arXiv or arXivRaw (default: arXiv)"
+    )
+)]
+pub async fn get_record_tool(
+    identifier: String,
+    metadata_prefix: Option<String>,
+) -> Result<ToolResponseContent> {
+    let metadata_prefix = metadata_prefix.unwrap_or_else(|| "arXiv".to_string());
+    let record = OaiClient::default()
+        .get_record(&identifier, &metadata_prefix)
+        .await?;
+
+    Ok(tool_text_content!(
+        serde_json::to_string(&record).map_err(McpArxivError::ResponseSerializeError)?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_record_tool() {
+        match get_record_tool("0704.0001".to_string(), Some("arXivRaw".to_string())).await {
+            Ok(content) => {
+                println!("Record result: {:?}", content);
+                assert!(!format!("{:?}", content).is_empty());
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
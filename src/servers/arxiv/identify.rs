@@ -0,0 +1,36 @@
+use super::errors::McpArxivError;
+use super::oai::OaiClient;
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+
+#[tool(
+    name = "Identify",
+    description = "Describe the ArXiv OAI-PMH repository: earliest datestamp, granularity and deleted record policy."
+)]
+pub async fn identify_tool() -> Result<ToolResponseContent> {
+    let identify = OaiClient::default().identify().await?;
+
+    Ok(tool_text_content!(
+        serde_json::to_string(&identify).map_err(McpArxivError::ResponseSerializeError)?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_identify_tool() {
+        match identify_tool().await {
+            Ok(content) => {
+                println!("Identify result: {:?}", content);
+                assert!(!format!("{:?}", content).is_empty());
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
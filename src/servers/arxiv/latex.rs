@@ -0,0 +1,562 @@
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::Read;
+
+use super::errors::McpArxivError;
+
+/// Only text files are kept from source archives, figures are skipped
+const TEXT_EXTENSIONS: &[&str] = &["tex", "ltx", "sty", "cls", "bbl", "bib", "txt", "json"];
+/// Largest single file kept from an archive, a guard against gzip bombs
+const MAX_FILE_BYTES: u64 = 20 * 1024 * 1024;
+/// Largest decompressed e-print
+const MAX_UNPACKED_BYTES: u64 = MAX_FILE_BYTES * 10;
+/// Longest text `inline` returns, files that each include the next several
+/// times would otherwise grow it exponentially
+const MAX_INLINED_BYTES: usize = MAX_FILE_BYTES as usize;
+
+/// Sectioning commands from outermost to innermost
+const SECTION_LEVELS: &[&str] = &["part", "chapter", "section", "subsection", "subsubsection"];
+
+/// Text files from an arXiv e-print, keyed by path inside the archive.
+#[derive(Debug, Default)]
+pub struct SourceTree {
+    pub files: BTreeMap<String, String>,
+}
+
+/// A file with its `\input` and `\include` files inlined.
+#[derive(Debug, Default)]
+pub struct Inlined {
+    pub latex: String,
+    /// Included files that are not in the archive
+    pub missing: Vec<String>,
+    /// Included files that were already being inlined, i.e. include themselves
+    pub cycles: Vec<String>,
+    /// Whether the text reached `MAX_INLINED_BYTES` and the rest was dropped
+    pub truncated: bool,
+}
+
+impl Inlined {
+    /// Appends `text` up to the size cap. Returns false once the cap is reached.
+    fn push(&mut self, text: &str) -> bool {
+        if self.truncated {
+            return false;
+        }
+        let room = MAX_INLINED_BYTES - self.latex.len();
+        if text.len() <= room {
+            self.latex.push_str(text);
+            return true;
+        }
+        let mut end = room;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.latex.push_str(&text[..end]);
+        self.truncated = true;
+        false
+    }
+}
+
+#[derive(Deserialize)]
+struct ReadmeJson {
+    #[serde(default)]
+    sources: Vec<ReadmeSource>,
+}
+
+#[derive(Deserialize)]
+struct ReadmeSource {
+    filename: String,
+    #[serde(default)]
+    usage: String,
+}
+
+impl SourceTree {
+    /// Unpacks an e-print, which is a gzipped tarball, a gzipped single file,
+    /// or either of those uncompressed. Decompresses up to 200 MB, so async
+    /// callers run it with `spawn_blocking`.
+    pub fn from_eprint(bytes: &[u8]) -> Result<SourceTree, McpArxivError> {
+        let source_error = |e: std::io::Error| McpArxivError::SourceError(e.to_string());
+
+        let data = if bytes.starts_with(&[0x1f, 0x8b]) {
+            let mut data = Vec::new();
+            // One byte over the limit tells a truncated archive from one that fits
+            GzDecoder::new(bytes)
+                .take(MAX_UNPACKED_BYTES + 1)
+                .read_to_end(&mut data)
+                .map_err(source_error)?;
+            if data.len() as u64 > MAX_UNPACKED_BYTES {
+                return Err(McpArxivError::SourceError(format!(
+                    "the source is too large, it unpacks to more than {} MB",
+                    MAX_UNPACKED_BYTES / 1024 / 1024
+                )));
+            }
+            data
+        } else {
+            bytes.to_vec()
+        };
+
+        if data.starts_with(b"%PDF") {
+            return Err(McpArxivError::SourceError(
+                "no source available, the authors only submitted a PDF".to_string(),
+            ));
+        }
+
+        let mut tree = SourceTree::default();
+        if data.get(257..262) == Some(b"ustar".as_slice()) {
+            let mut archive = tar::Archive::new(data.as_slice());
+            for entry in archive.entries().map_err(source_error)? {
+                let entry = entry.map_err(source_error)?;
+                let path = entry.path().map_err(source_error)?;
+                let path = path.to_string_lossy().trim_start_matches("./").to_string();
+                let text_file = path
+                    .rsplit_once('.')
+                    .is_some_and(|(_, ext)| TEXT_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+                if !entry.header().entry_type().is_file()
+                    || !text_file
+                    || entry.size() > MAX_FILE_BYTES
+                {
+                    continue;
+                }
+                let mut content = Vec::new();
+                entry
+                    .take(MAX_FILE_BYTES)
+                    .read_to_end(&mut content)
+                    .map_err(source_error)?;
+                tree.files
+                    .insert(path, String::from_utf8_lossy(&content).to_string());
+            }
+        } else {
+            tree.files.insert(
+                "main.tex".to_string(),
+                String::from_utf8_lossy(&data).to_string(),
+            );
+        }
+
+        if tree.files.is_empty() {
+            return Err(McpArxivError::SourceError(
+                "the source archive contains no text files".to_string(),
+            ));
+        }
+        Ok(tree)
+    }
+
+    /// The top-level file: the one named in `00README.json`, else the `.tex`
+    /// file with `\documentclass` and `\begin{document}`.
+    pub fn main_file(&self) -> Option<&str> {
+        if let Some(readme) = self.files.get("00README.json")
+            && let Ok(readme) = serde_json::from_str::<ReadmeJson>(readme)
+            && let Some((path, _)) = readme
+                .sources
+                .iter()
+                .filter(|s| s.usage == "toplevel")
+                .find_map(|s| self.files.get_key_value(&s.filename))
+        {
+            return Some(path.as_str());
+        }
+
+        let score = |(path, text): &(&String, &String)| {
+            let text = strip_comments(text);
+            let mut score = 0;
+            if text.contains("\\documentclass") || text.contains("\\documentstyle") {
+                score += 4;
+            }
+            if text.contains("\\begin{document}") {
+                score += 2;
+            }
+            if matches!(path.as_str(), "main.tex" | "ms.tex" | "paper.tex") {
+                score += 1;
+            }
+            score
+        };
+        self.files
+            .iter()
+            .filter(|(path, _)| path.ends_with(".tex") || path.ends_with(".ltx"))
+            .filter(|file| score(file) >= 4)
+            .max_by_key(|file| (score(file), std::cmp::Reverse(file.0.matches('/').count())))
+            .map(|(path, _)| path.as_str())
+    }
+
+    /// Replaces `\input` and `\include` with the contents of the named files.
+    /// Commands naming files that are missing or already being inlined are
+    /// left in place.
+    pub fn inline(&self, path: &str) -> Result<Inlined, McpArxivError> {
+        let (path, text) = self
+            .files
+            .get_key_value(path)
+            .ok_or_else(|| McpArxivError::SourceError(format!("{} not found", path)))?;
+        let mut inlined = Inlined::default();
+        self.inline_text(text, &mut vec![path.as_str()], &mut inlined);
+        Ok(inlined)
+    }
+
+    /// Appends `text` to `inlined`. `stack` holds the files being inlined, the
+    /// one `text` comes from last.
+    fn inline_text<'a>(&'a self, text: &str, stack: &mut Vec<&'a str>, inlined: &mut Inlined) {
+        for line in text.split_inclusive('\n') {
+            let code_end = comment_start(line).unwrap_or(line.len());
+            let mut code = &line[..code_end];
+            while let Some((before, name, after)) = include_command(code) {
+                if !inlined.push(before) {
+                    return;
+                }
+                let command = &code[before.len()..code.len() - after.len()];
+                match self.resolve(name) {
+                    Some((path, content)) if !stack.contains(&path) => {
+                        stack.push(path);
+                        self.inline_text(content, stack, inlined);
+                        stack.pop();
+                        if !content.ends_with('\n') && !inlined.push("\n") {
+                            return;
+                        }
+                    }
+                    found => {
+                        let names = match found {
+                            Some(_) => &mut inlined.cycles,
+                            None => &mut inlined.missing,
+                        };
+                        if !names.iter().any(|n| n == name) {
+                            names.push(name.to_string());
+                        }
+                        if !inlined.push(command) {
+                            return;
+                        }
+                    }
+                }
+                if inlined.truncated {
+                    return;
+                }
+                code = after;
+            }
+            if !inlined.push(code) || !inlined.push(&line[code_end..]) {
+                return;
+            }
+        }
+    }
+
+    fn resolve(&self, name: &str) -> Option<(&str, &String)> {
+        let name = name.trim().trim_start_matches("./");
+        self.files
+            .get_key_value(name)
+            .or_else(|| self.files.get_key_value(&format!("{}.tex", name)))
+            .map(|(path, content)| (path.as_str(), content))
+    }
+}
+
+/// Finds the first `\input{name}`, `\include{name}` or `\input name` in
+/// `code`, returning the text before it, the file name and the text after.
+fn include_command(code: &str) -> Option<(&str, &str, &str)> {
+    let mut search = 0;
+    while let Some(offset) = code[search..].find('\\') {
+        let start = search + offset;
+        let rest = &code[start + 1..];
+        for command in ["include", "input"] {
+            let Some(args) = rest.strip_prefix(command) else {
+                continue;
+            };
+            // `\inputencoding` and friends are different commands
+            if args.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                continue;
+            }
+            let trimmed = args.trim_start();
+            if let Some(braced) = trimmed.strip_prefix('{') {
+                let end = braced.find('}')?;
+                return Some((&code[..start], &braced[..end], &braced[end + 1..]));
+            }
+            if command == "input" && args.starts_with(' ') {
+                let end = trimmed
+                    .find(|c: char| c.is_whitespace() || c == '\\' || c == '}')
+                    .unwrap_or(trimmed.len());
+                if end > 0 {
+                    return Some((&code[..start], &trimmed[..end], &trimmed[end..]));
+                }
+            }
+        }
+        search = start + 1;
+    }
+    None
+}
+
+/// Byte offset of the `%` starting a comment, if any. `\%` is a literal
+/// percent sign but `\\%` is a line break followed by a comment.
+fn comment_start(line: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    let mut backslashes = 0;
+    for (i, b) in bytes.iter().enumerate() {
+        match b {
+            b'\\' => backslashes += 1,
+            b'%' if backslashes % 2 == 0 => return Some(i),
+            _ => backslashes = 0,
+        }
+    }
+    None
+}
+
+/// Removes comments, dropping lines that held nothing but a comment and
+/// `comment` environments.
+pub fn strip_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_comment_env = false;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("\\begin{comment}") {
+            in_comment_env = true;
+            continue;
+        }
+        if in_comment_env {
+            in_comment_env = !trimmed.starts_with("\\end{comment}");
+            continue;
+        }
+        match comment_start(line) {
+            Some(0) => continue,
+            Some(i) if line[..i].trim().is_empty() => continue,
+            // Keep the `%` when it ends a line, it suppresses the line break
+            Some(i) => {
+                out.push_str(&line[..i]);
+                out.push('%');
+            }
+            None => out.push_str(line),
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Returns the part of `latex` under the first heading matching `name`, up to
+/// the next heading at the same or an outer level. `abstract` selects the
+/// abstract environment.
+pub fn select_section(latex: &str, name: &str) -> Result<String, McpArxivError> {
+    let wanted = name.trim().to_lowercase();
+    if wanted == "abstract"
+        && let Some(start) = latex.find("\\begin{abstract}")
+    {
+        let end = latex[start..]
+            .find("\\end{abstract}")
+            .map_or(latex.len(), |e| start + e + "\\end{abstract}".len());
+        return Ok(latex[start..end].to_string());
+    }
+
+    let headings = headings(latex);
+    let found = headings
+        .iter()
+        .position(|h| h.title.to_lowercase().contains(&wanted))
+        .ok_or_else(|| {
+            McpArxivError::InvalidArgument(format!(
+                "no section matches `{}`, available sections: {}",
+                name,
+                headings
+                    .iter()
+                    .map(|h| h.title.as_str())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ))
+        })?;
+
+    let heading = &headings[found];
+    let end = headings[found + 1..]
+        .iter()
+        .find(|h| h.level <= heading.level)
+        .map(|h| h.start)
+        .or_else(|| {
+            [
+                "\\bibliography{",
+                "\\begin{thebibliography}",
+                "\\end{document}",
+            ]
+            .iter()
+            .filter_map(|marker| latex[heading.start..].find(marker))
+            .min()
+            .map(|offset| heading.start + offset)
+        })
+        .unwrap_or(latex.len());
+    Ok(latex[heading.start..end].trim_end().to_string())
+}
+
+struct Heading {
+    level: usize,
+    title: String,
+    start: usize,
+}
+
+fn headings(latex: &str) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut offset = 0;
+    for line in latex.split_inclusive('\n') {
+        let code = &line[..comment_start(line).unwrap_or(line.len())];
+        let trimmed = code.trim_start();
+        for (level, command) in SECTION_LEVELS.iter().enumerate() {
+            let Some(rest) = trimmed
+                .strip_prefix('\\')
+                .and_then(|r| r.strip_prefix(command))
+            else {
+                continue;
+            };
+            let rest = rest.trim_start_matches('*').trim_start();
+            // Skip an optional short title, `\section[Short]{Long title}`
+            let rest = match rest.strip_prefix('[') {
+                Some(short) => short.split_once(']').map_or("", |(_, r)| r),
+                None => rest,
+            };
+            if let Some(title) = rest.strip_prefix('{') {
+                headings.push(Heading {
+                    level,
+                    title: braced(title),
+                    start: offset,
+                });
+                break;
+            }
+        }
+        offset += line.len();
+    }
+    headings
+}
+
+/// Text up to the brace closing an already opened group.
+fn braced(text: &str) -> String {
+    let mut depth = 1;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return text[..i].to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+    text.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{Compression, write::GzEncoder};
+    use std::io::Write;
+
+    const MAIN: &str = "\\documentclass{article}
+% \\input{commented}
+\\begin{document}
+\\begin{abstract}
+We prove 50\\% of things.
+\\end{abstract}
+\\input{sections/intro}
+\\include{method}
+\\section{Conclusion} % the end
+Done.\\\\% trailing
+\\bibliography{refs}
+\\end{document}
+";
+
+    fn tarball() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, content) in [
+            ("main.tex", MAIN),
+            ("sections/intro.tex", "\\section{Introduction}\nHello.\n"),
+            (
+                "method.tex",
+                "\\section[Method]{Our Method}\n\\subsection{Data}\nData.\n",
+            ),
+            ("appendix.tex", "\\section{Extra}\n"),
+            ("fig.png", "\u{89}PNG"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        let tar = builder.into_inner().unwrap();
+
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(&tar).unwrap();
+        gz.finish().unwrap()
+    }
+
+    #[test]
+    fn test_unpack_and_inline() {
+        let tree = SourceTree::from_eprint(&tarball()).unwrap();
+        assert!(!tree.files.contains_key("fig.png"));
+        assert_eq!(tree.main_file(), Some("main.tex"));
+
+        let Inlined { latex, missing, .. } = tree.inline("main.tex").unwrap();
+        assert!(missing.is_empty());
+        assert!(latex.contains("% \\input{commented}"));
+        assert!(latex.contains("\\section{Introduction}\nHello.\n"));
+        assert!(latex.contains("\\subsection{Data}"));
+
+        let stripped = strip_comments(&latex);
+        assert!(!stripped.contains("commented"));
+        assert!(stripped.contains("We prove 50\\% of things."));
+        assert!(stripped.contains("\\section{Conclusion} %\n"));
+        assert!(stripped.contains("Done.\\\\%\n"));
+
+        assert_eq!(
+            select_section(&stripped, "method").unwrap(),
+            "\\section[Method]{Our Method}\n\\subsection{Data}\nData."
+        );
+        assert_eq!(
+            select_section(&stripped, "conclusion").unwrap(),
+            "\\section{Conclusion} %\nDone.\\\\%"
+        );
+        assert!(
+            select_section(&stripped, "abstract")
+                .unwrap()
+                .contains("50\\%")
+        );
+        assert!(select_section(&stripped, "results").is_err());
+    }
+
+    #[test]
+    fn test_single_file_and_pdf_only() {
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(b"\\documentclass{revtex4}\n\\begin{document}\n\\end{document}\n")
+            .unwrap();
+        let tree = SourceTree::from_eprint(&gz.finish().unwrap()).unwrap();
+        assert_eq!(tree.main_file(), Some("main.tex"));
+
+        assert!(SourceTree::from_eprint(b"%PDF-1.5").is_err());
+    }
+
+    #[test]
+    fn test_missing_input_is_kept() {
+        let mut tree = SourceTree::default();
+        tree.files.insert(
+            "main.tex".to_string(),
+            "a \\input{gone} b \\inputencoding{x}\n".to_string(),
+        );
+        let inlined = tree.inline("main.tex").unwrap();
+        assert_eq!(inlined.latex, "a \\input{gone} b \\inputencoding{x}\n");
+        assert_eq!(inlined.missing, ["gone"]);
+        assert!(inlined.cycles.is_empty());
+    }
+
+    #[test]
+    fn test_include_cycles_and_size_cap() {
+        let mut tree = SourceTree::default();
+        tree.files
+            .insert("main.tex".to_string(), "\\input{a}\n".to_string());
+        tree.files
+            .insert("a.tex".to_string(), "A \\input{main}\n".to_string());
+        let inlined = tree.inline("main.tex").unwrap();
+        assert_eq!(inlined.latex, "A \\input{main}\n\n");
+        assert_eq!(inlined.cycles, ["main"]);
+        assert!(inlined.missing.is_empty());
+        assert!(!inlined.truncated);
+
+        // Each file includes the next twice, 2^30 copies of the last one
+        let mut tree = SourceTree::default();
+        for i in 0..30 {
+            tree.files.insert(
+                format!("f{}.tex", i),
+                format!("\\input{{f{0}}}\\input{{f{0}}}\n", i + 1),
+            );
+        }
+        tree.files.insert("f30.tex".to_string(), "x".repeat(1000));
+        let inlined = tree.inline("f0.tex").unwrap();
+        assert!(inlined.truncated);
+        assert_eq!(inlined.latex.len(), MAX_INLINED_BYTES);
+    }
+}
@@ -0,0 +1,517 @@
+//! Papers saved on disk for offline reading and full-text search.
+//!
+//! Each paper lives in its own directory under `ARXIV_LIBRARY_DIR`:
+//! `entry.json` holds the arXiv metadata, `text.json` the extracted sections
+//! and `paper.pdf` the PDF itself.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::atom::{Paper, split_version};
+use super::errors::McpArxivError;
+use super::structure::PaperText;
+
+/// Default library location, relative to the working directory
+pub const DEFAULT_LIBRARY_DIR: &str = "arxiv-library";
+
+/// BM25 term frequency saturation and length normalisation
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+/// Title and abstract terms count this many times over body terms
+const TITLE_BOOST: u32 = 3;
+const SNIPPET_WORDS: usize = 30;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "is", "it", "of", "on",
+    "or", "that", "the", "this", "to", "we", "with",
+];
+
+lazy_static! {
+    /// Search index, rebuilt when the library directory changes
+    static ref INDEX: Mutex<Option<Index>> = Mutex::new(None);
+    /// New-style (`2101.00001`) and old-style (`hep-th/9901001`, `math.AG/0001001`)
+    /// IDs, which also keeps entry directories inside the library
+    static ref ARXIV_ID: Regex =
+        Regex::new(r"^(\d{4}\.\d{4,5}|[a-z]+(-[a-z]+)?(\.[A-Z]{2})?/\d{7})(v\d+)?$").unwrap();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+    pub paper: Paper,
+    /// Unix time in seconds
+    pub saved_at: u64,
+    pub page_count: u32,
+}
+
+/// A search result.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hit {
+    pub id: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub score: f64,
+    pub snippet: String,
+}
+
+struct Document {
+    entry: Entry,
+    body: String,
+    term_counts: HashMap<String, u32>,
+    length: u32,
+}
+
+struct Index {
+    dir: PathBuf,
+    modified: Option<SystemTime>,
+    documents: Vec<Document>,
+    document_frequency: HashMap<String, u32>,
+    average_length: f64,
+}
+
+pub struct Library {
+    dir: PathBuf,
+}
+
+impl Library {
+    /// The library at `ARXIV_LIBRARY_DIR`.
+    pub fn open() -> Library {
+        Library::new(
+            crate::config::var("ARXIV_LIBRARY_DIR")
+                .filter(|d| !d.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_LIBRARY_DIR.to_string()),
+        )
+    }
+
+    pub fn new(dir: impl Into<PathBuf>) -> Library {
+        Library { dir: dir.into() }
+    }
+
+    fn entry_dir(&self, id: &str) -> Result<PathBuf, McpArxivError> {
+        let id = id.trim();
+        if !ARXIV_ID.is_match(id) {
+            return Err(McpArxivError::InvalidArgument(format!(
+                "invalid arXiv ID `{}`",
+                id
+            )));
+        }
+        let (id, _) = split_version(id);
+        Ok(self.dir.join(id.replace('/', "_")))
+    }
+
+    /// Path of the saved PDF for `id`, if saved. An ID with a version only
+    /// matches that version.
+    pub fn saved_pdf(&self, id: &str) -> Option<PathBuf> {
+        let dir = self.entry_dir(id).ok()?;
+        let entry: Entry = read_json(&dir.join("entry.json")).ok()?;
+        let (_, version) = split_version(id.trim());
+        let pdf = dir.join("paper.pdf");
+        (version.is_none() || version == entry.paper.version)
+            .then_some(pdf)
+            .filter(|p| p.is_file())
+    }
+
+    /// Saves or replaces a paper and its PDF. Writes up to `ARXIV_MAX_PDF_MB`,
+    /// so async callers run it with `spawn_blocking`.
+    pub fn save(
+        &self,
+        paper: &Paper,
+        text: &PaperText,
+        pdf: &[u8],
+    ) -> Result<Entry, McpArxivError> {
+        let dir = self.entry_dir(&paper.id)?;
+        fs::create_dir_all(&dir).map_err(|e| library_error(&dir, e))?;
+
+        let entry = Entry {
+            paper: paper.clone(),
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            page_count: text.page_count,
+        };
+        // Each file is renamed into place, so readers see the old or the new
+        // version and never a partly written one
+        write_atomic(&dir.join("paper.pdf"), pdf)?;
+        write_json(&dir.join("text.json"), text)?;
+        // Written last, a directory without it is an interrupted save
+        write_json(&dir.join("entry.json"), &entry)?;
+
+        self.invalidate();
+        Ok(entry)
+    }
+
+    /// Saved papers, most recently saved first.
+    pub fn list(&self) -> Result<Vec<Entry>, McpArxivError> {
+        let mut entries: Vec<Entry> = self
+            .entry_dirs()?
+            .iter()
+            .filter_map(|dir| read_json(&dir.join("entry.json")).ok())
+            .collect();
+        entries.sort_by(|a, b| {
+            b.saved_at
+                .cmp(&a.saved_at)
+                .then_with(|| a.paper.id.cmp(&b.paper.id))
+        });
+        Ok(entries)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<Entry, McpArxivError> {
+        let dir = self.entry_dir(id)?;
+        let entry: Entry = read_json(&dir.join("entry.json"))
+            .map_err(|_| McpArxivError::NotInLibrary(id.to_string()))?;
+        fs::remove_dir_all(&dir).map_err(|e| library_error(&dir, e))?;
+        self.invalidate();
+        Ok(entry)
+    }
+
+    /// Ranks saved papers against `query` with BM25.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<Hit>, McpArxivError> {
+        let mut terms = tokenize(query);
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() {
+            return Err(McpArxivError::InvalidArgument(
+                "the query has no searchable words".to_string(),
+            ));
+        }
+
+        let mut index = INDEX.lock().unwrap_or_else(|e| e.into_inner());
+        let modified = fs::metadata(&self.dir).and_then(|m| m.modified()).ok();
+        if !index
+            .as_ref()
+            .is_some_and(|i| i.dir == self.dir && i.modified == modified)
+        {
+            *index = Some(self.build_index(modified)?);
+        }
+        let Some(index) = index.as_ref() else {
+            return Ok(Vec::new());
+        };
+
+        let count = index.documents.len() as f64;
+        let mut hits: Vec<Hit> = index
+            .documents
+            .iter()
+            .filter_map(|doc| {
+                let score: f64 = terms
+                    .iter()
+                    .filter_map(|term| {
+                        let tf = *doc.term_counts.get(term)? as f64;
+                        let df = *index.document_frequency.get(term)? as f64;
+                        let idf = ((count - df + 0.5) / (df + 0.5) + 1.0).ln();
+                        let norm = K1 * (1.0 - B + B * doc.length as f64 / index.average_length);
+                        Some(idf * tf * (K1 + 1.0) / (tf + norm))
+                    })
+                    .sum();
+                (score > 0.0).then(|| Hit {
+                    id: doc.entry.paper.id.clone(),
+                    title: doc.entry.paper.title.clone(),
+                    authors: doc
+                        .entry
+                        .paper
+                        .authors
+                        .iter()
+                        .map(|a| a.name.clone())
+                        .collect(),
+                    score: (score * 1000.0).round() / 1000.0,
+                    snippet: snippet(&doc.body, &terms),
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    fn build_index(&self, modified: Option<SystemTime>) -> Result<Index, McpArxivError> {
+        let mut documents = Vec::new();
+        for dir in self.entry_dirs()? {
+            let (Ok(entry), Ok(text)) = (
+                read_json::<Entry>(&dir.join("entry.json")),
+                read_json::<PaperText>(&dir.join("text.json")),
+            ) else {
+                continue;
+            };
+
+            let body = text
+                .sections
+                .iter()
+                .map(|s| s.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            let mut term_counts: HashMap<String, u32> = HashMap::new();
+            let heading = format!("{} {}", entry.paper.title, entry.paper.summary);
+            for term in tokenize(&heading) {
+                *term_counts.entry(term).or_default() += TITLE_BOOST;
+            }
+            for term in tokenize(&body) {
+                *term_counts.entry(term).or_default() += 1;
+            }
+            let length = term_counts.values().sum();
+
+            documents.push(Document {
+                entry,
+                body,
+                term_counts,
+                length,
+            });
+        }
+
+        let mut document_frequency: HashMap<String, u32> = HashMap::new();
+        for doc in &documents {
+            for term in doc.term_counts.keys() {
+                *document_frequency.entry(term.clone()).or_default() += 1;
+            }
+        }
+        let average_length = if documents.is_empty() {
+            1.0
+        } else {
+            documents.iter().map(|d| d.length as f64).sum::<f64>() / documents.len() as f64
+        };
+
+        Ok(Index {
+            dir: self.dir.clone(),
+            modified,
+            documents,
+            document_frequency,
+            average_length: average_length.max(1.0),
+        })
+    }
+
+    fn entry_dirs(&self) -> Result<Vec<PathBuf>, McpArxivError> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut dirs: Vec<PathBuf> = fs::read_dir(&self.dir)
+            .map_err(|e| library_error(&self.dir, e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.join("entry.json").is_file())
+            .collect();
+        dirs.sort();
+        Ok(dirs)
+    }
+
+    fn invalidate(&self) {
+        *INDEX.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+/// Lowercased words of two or more characters, without stopwords.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 2)
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// The window of the body holding the most distinct query terms.
+fn snippet(body: &str, terms: &[String]) -> String {
+    let words: Vec<&str> = body.split_whitespace().collect();
+    if words.is_empty() {
+        return String::new();
+    }
+    let matches = |word: &str| {
+        let word = tokenize(word);
+        terms.iter().any(|t| word.contains(t))
+    };
+
+    let mut best = (0, 0);
+    let mut start = 0;
+    while start < words.len() {
+        let window = &words[start..(start + SNIPPET_WORDS).min(words.len())];
+        let found = terms
+            .iter()
+            .filter(|t| window.iter().any(|w| tokenize(w).contains(t)))
+            .count();
+        if found > best.1 {
+            best = (start, found);
+        }
+        start += SNIPPET_WORDS / 2;
+    }
+
+    // Start the snippet at the first match in the window, keeping a little
+    // context before it
+    let window_end = (best.0 + SNIPPET_WORDS).min(words.len());
+    let first_match = (best.0..window_end)
+        .find(|i| matches(words[*i]))
+        .unwrap_or(best.0);
+    let from = first_match.saturating_sub(5).max(best.0);
+    let to = (from + SNIPPET_WORDS).min(words.len());
+
+    let mut snippet = words[from..to].join(" ");
+    if from > 0 {
+        snippet.insert_str(0, "...");
+    }
+    if to < words.len() {
+        snippet.push_str("...");
+    }
+    snippet
+}
+
+fn library_error(path: &Path, e: std::io::Error) -> McpArxivError {
+    McpArxivError::LibraryError(format!("{}: {}", path.display(), e))
+}
+
+/// Writes through a temporary file with a random name, so concurrent saves of
+/// one paper do not write to the same file.
+fn write_atomic(path: &Path, data: &[u8]) -> Result<(), McpArxivError> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let partial = path.with_file_name(format!(
+        "{}.{:032x}.part",
+        file_name,
+        rand::random::<u128>()
+    ));
+    fs::write(&partial, data)
+        .and_then(|_| fs::rename(&partial, path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&partial);
+            library_error(path, e)
+        })
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), McpArxivError> {
+    write_atomic(
+        path,
+        &serde_json::to_vec_pretty(value).map_err(McpArxivError::ResponseSerializeError)?,
+    )
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<T, McpArxivError> {
+    let data = fs::read(path).map_err(|e| library_error(path, e))?;
+    serde_json::from_slice(&data)
+        .map_err(|e| McpArxivError::LibraryError(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::servers::arxiv::atom::Author;
+
+    fn save(library: &Library, id: &str, title: &str, body: &str) {
+        let paper = Paper {
+            id: id.to_string(),
+            title: title.to_string(),
+            authors: vec![Author {
+                name: "Ada Lovelace".to_string(),
+                affiliations: vec![],
+            }],
+            ..Default::default()
+        };
+        let text = PaperText::parse(&[(1, body.to_string())], 1);
+        library.save(&paper, &text, b"%PDF-1.4").unwrap();
+    }
+
+    #[test]
+    fn test_save_search_remove() {
+        let dir = std::env::temp_dir().join(format!("arxiv-library-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let library = Library::new(&dir);
+
+        save(
+            &library,
+            "2101.00001",
+            "Sparse attention",
+            "Attention is computed over a sparse set of tokens. Sparse kernels are fast.",
+        );
+        save(
+            &library,
+            "hep-th/9901001",
+            "String dualities",
+            "We study dualities of strings. Attention to detail matters in the proof, \
+             which spans many pages of algebra before the main result on dualities.",
+        );
+        save(
+            &library,
+            "2101.00003",
+            "Graph colouring",
+            "Colour the graph greedily.",
+        );
+        assert!(dir.join("hep-th_9901001").join("paper.pdf").is_file());
+        assert!(library.saved_pdf("hep-th/9901001").is_some());
+        assert!(library.saved_pdf("hep-th/9901001v3").is_none());
+
+        let hits = library.search("sparse attention", 10).unwrap();
+        let ids: Vec<&str> = hits.iter().map(|h| h.id.as_str()).collect();
+        assert_eq!(ids, ["2101.00001", "hep-th/9901001"]);
+        assert!(hits[0].score > hits[1].score);
+        assert!(hits[0].snippet.starts_with("Attention is computed"));
+        assert!(library.search("the of", 10).is_err());
+
+        assert_eq!(library.list().unwrap().len(), 3);
+        library.remove("2101.00001v2").unwrap();
+        assert!(library.remove("2101.00001").is_err());
+        let hits = library.search("sparse", 10).unwrap();
+        assert!(hits.is_empty());
+
+        // IDs that would name a directory outside the library are rejected
+        for id in ["..", ".", "../2101.00003", "2101.00003/..", ""] {
+            assert!(matches!(
+                library.remove(id),
+                Err(McpArxivError::InvalidArgument(_))
+            ));
+            assert!(library.saved_pdf(id).is_none());
+        }
+        let mut paper = library.list().unwrap()[0].paper.clone();
+        paper.id = "..".to_string();
+        let text = PaperText::parse(&[], 0);
+        assert!(library.save(&paper, &text, b"%PDF-1.4").is_err());
+        assert_eq!(library.list().unwrap().len(), 2);
+        assert!(dir.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_saves_of_one_paper() {
+        let dir = std::env::temp_dir().join(format!("arxiv-library-race-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let saves: Vec<_> = (0..4)
+            .map(|i| {
+                let dir = dir.clone();
+                std::thread::spawn(move || {
+                    let library = Library::new(dir);
+                    save(
+                        &library,
+                        "2101.00001",
+                        "Sparse attention",
+                        &format!("Body {}", i),
+                    );
+                })
+            })
+            .collect();
+        for save in saves {
+            save.join().unwrap();
+        }
+
+        let files: Vec<String> = fs::read_dir(dir.join("2101.00001"))
+            .unwrap()
+            .map(|f| f.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.ends_with(".part"))
+            .collect();
+        assert!(files.is_empty());
+        assert_eq!(Library::new(&dir).list().unwrap().len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_snippet() {
+        let body = (0..100)
+            .map(|i| format!("w{}", i))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let body = body.replace("w60", "needle");
+        let snippet = snippet(&body, &["needle".to_string()]);
+        assert!(snippet.starts_with("...w55 w56"));
+        assert!(snippet.contains("needle"));
+        assert!(snippet.ends_with("..."));
+    }
+}
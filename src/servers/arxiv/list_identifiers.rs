@@ -0,0 +1,70 @@
+use super::errors::McpArxivError;
+use super::oai::{ListArgs, OaiClient};
+use crate::output;
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+
+#[tool(
+    name = "ListIdentifiers",
+    description = "List record headers (identifier, datestamp, sets) from ArXiv using OAI-PMH, without metadata. Returns a resumptionToken when more remain.",
+    params(
+        from = "Start date for records (YYYY-MM-DD)",
+        until = "End date for records (YYYY-MM-DD)",
+        metadata_prefix = "Metadata format the records must be available in (default: oai_dc)",
+        set = "Optional set identifier to filter records (e.g. 'cs', 'physics:hep-th')",
+        resumption_token = "Token from a previous response to continue the listing, other filters are ignored",
+        max_pages = "Number of pages to fetch by following resumption tokens automatically (default: 1, at most 20)"
+    )
+)]
+pub async fn list_identifiers_tool(
+    from: Option<String>,
+    until: Option<String>,
+    metadata_prefix: Option<String>,
+    set: Option<String>,
+    resumption_token: Option<String>,
+    max_pages: Option<u32>,
+) -> Result<ToolResponseContent> {
+    let args = ListArgs {
+        metadata_prefix: metadata_prefix.unwrap_or_else(|| "oai_dc".to_string()),
+        from,
+        until,
+        set,
+        resumption_token,
+        max_pages: max_pages.unwrap_or(1),
+    };
+    let page = OaiClient::default().list_identifiers(&args).await?;
+
+    Ok(tool_text_content!(output::shape(
+        "ListIdentifiers",
+        serde_json::to_string(&page).map_err(McpArxivError::ResponseSerializeError)?
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_identifiers_tool() {
+        match list_identifiers_tool(
+            Some("2024-01-01".to_string()),
+            Some("2024-01-01".to_string()),
+            None,
+            Some("cs".to_string()),
+            None,
+            None,
+        )
+        .await
+        {
+            Ok(content) => {
+                println!("OAI-PMH result: {:?}", content);
+                assert!(!format!("{:?}", content).is_empty());
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
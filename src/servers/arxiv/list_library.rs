@@ -0,0 +1,44 @@
+use super::library::Library;
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+use serde_json::{Value, json};
+
+use crate::output;
+
+#[tool(
+    name = "ListLibrary",
+    description = "List papers saved in the local library, most recently saved first. Returns JSON with id, version, title, authors, pageCount and savedAt (Unix seconds)."
+)]
+pub async fn list_library_tool() -> Result<ToolResponseContent> {
+    let entries: Vec<Value> = Library::open()
+        .list()?
+        .into_iter()
+        .map(|entry| {
+            json!({
+                "id": entry.paper.id,
+                "version": entry.paper.version,
+                "title": entry.paper.title,
+                "authors": entry.paper.authors.iter().map(|a| a.name.as_str()).collect::<Vec<_>>(),
+                "pageCount": entry.page_count,
+                "savedAt": entry.saved_at,
+            })
+        })
+        .collect();
+
+    Ok(tool_text_content!(output::shape(
+        "ListLibrary",
+        serde_json::to_string(&entries)?
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_library_tool() {
+        let content = list_library_tool().await.unwrap();
+        assert!(format!("{:?}", content).contains('['));
+    }
+}
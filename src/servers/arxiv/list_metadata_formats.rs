@@ -0,0 +1,39 @@
+use super::errors::McpArxivError;
+use super::oai::OaiClient;
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+
+#[tool(
+    name = "ListMetadataFormats",
+    description = "List the metadata formats ArXiv can return over OAI-PMH, optionally for one paper.",
+    params(identifier = "Optional ArXiv ID or OAI identifier to list the formats of")
+)]
+pub async fn list_metadata_formats_tool(identifier: Option<String>) -> Result<ToolResponseContent> {
+    let formats = OaiClient::default()
+        .list_metadata_formats(identifier.as_deref())
+        .await?;
+
+    Ok(tool_text_content!(
+        serde_json::to_string(&formats).map_err(McpArxivError::ResponseSerializeError)?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_metadata_formats_tool() {
+        match list_metadata_formats_tool(None).await {
+            Ok(content) => {
+                println!("Formats result: {:?}", content);
+                assert!(!format!("{:?}", content).is_empty());
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
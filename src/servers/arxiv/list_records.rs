@@ -1,59 +1,44 @@
 use super::errors::McpArxivError;
+use super::oai::{ListArgs, OaiClient};
+use crate::output;
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
-use reqwest::Client;
 
 #[tool(
     name = "ListRecords",
-    description = "Bulk harvest metadata from ArXiv using OAI-PMH interface.",
+    description = "Bulk harvest metadata from ArXiv using OAI-PMH interface. Returns JSON records and a resumptionToken when more remain.",
     params(
         from = "Start date for records (YYYY-MM-DD)",
         until = "End date for records (YYYY-MM-DD)",
-        metadata_prefix = "Metadata format (default: oai_dc)",
-        set = "Optional set identifier to filter records"
+        metadata_prefix = "Metadata format: oai_dc, arXiv or arXivRaw (default: oai_dc)",
+        set = "Optional set identifier to filter records (e.g. 'cs', 'physics:hep-th')",
+        resumption_token = "Token from a previous response to continue the harvest, other filters are ignored",
+        max_pages = "Number of pages to fetch by following resumption tokens automatically (default: 1, at most 20)"
     )
 )]
 pub async fn list_records_tool(
-    from: String,
-    until: String,
+    from: Option<String>,
+    until: Option<String>,
     metadata_prefix: Option<String>,
     set: Option<String>,
+    resumption_token: Option<String>,
+    max_pages: Option<u32>,
 ) -> Result<ToolResponseContent> {
-    let client = Client::new();
-    let base_url = "http://export.arxiv.org/oai2";
+    let args = ListArgs {
+        metadata_prefix: metadata_prefix.unwrap_or_else(|| "oai_dc".to_string()),
+        from,
+        until,
+        set,
+        resumption_token,
+        max_pages: max_pages.unwrap_or(1),
+    };
+    let page = OaiClient::default().list_records(&args).await?;
 
-    // Build query parameters
-    let metadata_prefix_str = metadata_prefix.unwrap_or_else(|| "oai_dc".to_string());
-    let mut params = vec![
-        ("verb", "ListRecords"),
-        ("from", &from),
-        ("until", &until),
-        ("metadataPrefix", &metadata_prefix_str),
-    ];
-
-    if let Some(set_id) = &set {
-        params.push(("set", set_id));
-    }
-
-    // Send the request
-    let res = client
-        .get(base_url)
-        .query(&params)
-        .send()
-        .await
-        .map_err(McpArxivError::HttpError)?;
-
-    // Check if the response is successful
-    if !res.status().is_success() {
-        let error_text = res.text().await.map_err(McpArxivError::HttpError)?;
-        return Err(McpArxivError::ApiError(format!("OAI-PMH API error: {}", error_text)).into());
-    }
-
-    // Parse the response
-    let result = res.text().await.map_err(McpArxivError::HttpError)?;
-
-    Ok(tool_text_content!(result))
+    Ok(tool_text_content!(output::shape(
+        "ListRecords",
+        serde_json::to_string(&page).map_err(McpArxivError::ResponseSerializeError)?
+    )))
 }
 
 #[cfg(test)]
@@ -63,16 +48,18 @@ mod tests {
     #[tokio::test]
     async fn test_list_records_tool() {
         match list_records_tool(
-            "2024-01-01".to_string(),
-            "2024-01-01".to_string(),
+            Some("2024-01-01".to_string()),
+            Some("2024-01-01".to_string()),
             Some("oai_dc".to_string()),
             None,
+            None,
+            None,
         )
         .await
         {
             Ok(content) => {
                 println!("OAI-PMH result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
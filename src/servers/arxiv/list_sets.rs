@@ -0,0 +1,45 @@
+use super::errors::McpArxivError;
+use super::oai::OaiClient;
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+
+#[tool(
+    name = "ListSets",
+    description = "List the OAI-PMH sets (subject areas) that ArXiv records can be harvested by.",
+    params(
+        resumption_token = "Token from a previous response to continue the listing",
+        max_pages = "Number of pages to fetch by following resumption tokens automatically (default: 1, at most 20)"
+    )
+)]
+pub async fn list_sets_tool(
+    resumption_token: Option<String>,
+    max_pages: Option<u32>,
+) -> Result<ToolResponseContent> {
+    let page = OaiClient::default()
+        .list_sets(resumption_token, max_pages.unwrap_or(1))
+        .await?;
+
+    Ok(tool_text_content!(
+        serde_json::to_string(&page).map_err(McpArxivError::ResponseSerializeError)?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_sets_tool() {
+        match list_sets_tool(None, None).await {
+            Ok(content) => {
+                println!("Sets result: {:?}", content);
+                assert!(!format!("{:?}", content).is_empty());
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
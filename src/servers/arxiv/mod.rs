@@ -1,13 +1,70 @@
+pub mod advanced_search;
+pub mod atom;
+pub mod author_profile;
+pub mod chunk;
+pub mod chunk_paper;
+pub mod citation;
+pub mod client;
+pub mod diff;
+pub mod diff_paper_versions;
 pub mod errors;
+pub mod export_citation;
+pub mod extract_figures;
 pub mod extract_paper_text;
+pub mod extract_references;
+pub mod figures;
+pub mod find_related_papers;
+pub mod get_new_submissions;
 pub mod get_paper_by_id;
+pub mod get_paper_source;
+pub mod get_paper_versions;
+pub mod get_record;
+pub mod identify;
+pub mod latex;
+pub mod library;
+pub mod list_identifiers;
+pub mod list_library;
+pub mod list_metadata_formats;
 pub mod list_records;
+pub mod list_sets;
+pub mod oai;
+pub mod pdf;
+pub mod query;
+pub mod references;
+pub mod remove_paper;
+pub mod save_paper;
 pub mod search_by_author;
+pub mod search_library;
 pub mod search_papers;
 pub mod server;
+pub mod similarity;
+pub mod structure;
+pub mod taxonomy;
+pub mod watcher;
+pub mod xml;
 
+pub use advanced_search::*;
+pub use author_profile::*;
+pub use chunk_paper::*;
+pub use diff_paper_versions::*;
+pub use export_citation::*;
+pub use extract_figures::*;
 pub use extract_paper_text::*;
+pub use extract_references::*;
+pub use find_related_papers::*;
+pub use get_new_submissions::*;
 pub use get_paper_by_id::*;
+pub use get_paper_source::*;
+pub use get_paper_versions::*;
+pub use get_record::*;
+pub use identify::*;
+pub use list_identifiers::*;
+pub use list_library::*;
+pub use list_metadata_formats::*;
 pub use list_records::*;
+pub use list_sets::*;
+pub use remove_paper::*;
+pub use save_paper::*;
 pub use search_by_author::*;
+pub use search_library::*;
 pub use search_papers::*;
@@ -0,0 +1,708 @@
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::time::Duration;
+
+use super::client;
+use super::errors::McpArxivError;
+use super::xml::Element;
+
+/// Times a request is repeated after `503 Retry-After` before giving up
+const MAX_RETRIES: u32 = 5;
+
+/// Wait used when a 503 has no usable `Retry-After`, and the cap for ones that do
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(10);
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(120);
+
+/// Pages one list call follows, each waits its turn with every other arXiv request
+const MAX_PAGES: u32 = 20;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Identify {
+    pub repository_name: String,
+    pub base_url: String,
+    pub protocol_version: String,
+    pub admin_emails: Vec<String>,
+    pub earliest_datestamp: String,
+    pub deleted_record: String,
+    pub granularity: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Set {
+    pub spec: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataFormat {
+    pub prefix: String,
+    pub schema: String,
+    pub namespace: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Header {
+    pub identifier: String,
+    pub datestamp: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sets: Vec<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Record {
+    pub header: Header,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "format")]
+pub enum Metadata {
+    #[serde(rename = "oai_dc")]
+    DublinCore(DublinCore),
+    #[serde(rename = "arXiv")]
+    ArXiv(ArXivMetadata),
+    #[serde(rename = "arXivRaw")]
+    ArXivRaw(ArXivRawMetadata),
+    /// A format without a typed parser, identified by its root element
+    #[serde(rename = "other")]
+    Other { element: String },
+}
+
+/// `oai_dc` elements, each of which may repeat.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DublinCore {
+    pub title: Vec<String>,
+    pub creator: Vec<String>,
+    pub subject: Vec<String>,
+    pub description: Vec<String>,
+    pub date: Vec<String>,
+    #[serde(rename = "type")]
+    pub kind: Vec<String>,
+    pub identifier: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArXivAuthor {
+    pub keyname: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forenames: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub affiliations: Vec<String>,
+}
+
+/// The `arXiv` format: structured authors, no version history.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArXivMetadata {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    pub authors: Vec<ArXivAuthor>,
+    pub title: String,
+    pub categories: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report_no: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub msc_class: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acm_class: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(rename = "abstract")]
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArXivVersion {
+    /// e.g. `v2`
+    pub version: String,
+    pub date: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_type: Option<String>,
+}
+
+/// The `arXivRaw` format: authors as submitted plus every version.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArXivRawMetadata {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submitter: Option<String>,
+    pub versions: Vec<ArXivVersion>,
+    pub title: String,
+    pub authors: String,
+    pub categories: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report_no: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub msc_class: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acm_class: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(rename = "abstract")]
+    pub summary: String,
+}
+
+/// Items from one or more list responses. `resumption_token` is set when the
+/// harvest stopped before the end of the list and can be passed back to continue.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resumption_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub complete_list_size: Option<u64>,
+    pub pages_fetched: u32,
+}
+
+/// Selective harvesting arguments shared by `ListRecords` and `ListIdentifiers`.
+#[derive(Debug, Clone, Default)]
+pub struct ListArgs {
+    pub metadata_prefix: String,
+    pub from: Option<String>,
+    pub until: Option<String>,
+    pub set: Option<String>,
+    /// Continues an earlier harvest, all other arguments are ignored
+    pub resumption_token: Option<String>,
+    /// Pages to follow automatically, at least one
+    pub max_pages: u32,
+}
+
+impl ListArgs {
+    fn params(&self) -> Vec<(&'static str, String)> {
+        if let Some(token) = &self.resumption_token {
+            return vec![("resumptionToken", token.clone())];
+        }
+        let mut params = vec![("metadataPrefix", self.metadata_prefix.clone())];
+        for (key, value) in [
+            ("from", &self.from),
+            ("until", &self.until),
+            ("set", &self.set),
+        ] {
+            if let Some(value) = value.as_ref().filter(|v| !v.is_empty()) {
+                params.push((key, value.clone()));
+            }
+        }
+        params
+    }
+}
+
+pub struct OaiClient {
+    base_url: String,
+}
+
+impl Default for OaiClient {
+    fn default() -> Self {
+        OaiClient::new(&client::api_url("/oai2"))
+    }
+}
+
+impl OaiClient {
+    pub fn new(base_url: &str) -> Self {
+        OaiClient {
+            base_url: base_url.to_string(),
+        }
+    }
+
+    pub async fn identify(&self) -> Result<Identify, McpArxivError> {
+        let response = self.request("Identify", &[]).await?;
+        let identify = child(&response, "Identify")?;
+        let text = |name: &str| identify.child_text(name).unwrap_or_default();
+
+        Ok(Identify {
+            repository_name: text("repositoryName"),
+            base_url: text("baseURL"),
+            protocol_version: text("protocolVersion"),
+            admin_emails: identify
+                .children_named("adminEmail")
+                .map(Element::collapsed_text)
+                .collect(),
+            earliest_datestamp: text("earliestDatestamp"),
+            deleted_record: text("deletedRecord"),
+            granularity: text("granularity"),
+        })
+    }
+
+    pub async fn list_metadata_formats(
+        &self,
+        identifier: Option<&str>,
+    ) -> Result<Vec<MetadataFormat>, McpArxivError> {
+        let params: Vec<(&str, String)> = identifier
+            .map(|id| vec![("identifier", oai_identifier(id))])
+            .unwrap_or_default();
+        let response = self.request("ListMetadataFormats", &params).await?;
+
+        Ok(child(&response, "ListMetadataFormats")?
+            .children_named("metadataFormat")
+            .map(|format| MetadataFormat {
+                prefix: format.child_text("metadataPrefix").unwrap_or_default(),
+                schema: format.child_text("schema").unwrap_or_default(),
+                namespace: format.child_text("metadataNamespace").unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    pub async fn list_sets(
+        &self,
+        resumption_token: Option<String>,
+        max_pages: u32,
+    ) -> Result<Page<Set>, McpArxivError> {
+        let params = resumption_token
+            .map(|token| vec![("resumptionToken", token)])
+            .unwrap_or_default();
+        self.list("ListSets", params, max_pages, "set", |set| {
+            Ok(Set {
+                spec: set.child_text("setSpec").unwrap_or_default(),
+                name: set.child_text("setName").unwrap_or_default(),
+            })
+        })
+        .await
+    }
+
+    pub async fn list_identifiers(&self, args: &ListArgs) -> Result<Page<Header>, McpArxivError> {
+        self.list(
+            "ListIdentifiers",
+            args.params(),
+            args.max_pages,
+            "header",
+            parse_header,
+        )
+        .await
+    }
+
+    pub async fn list_records(&self, args: &ListArgs) -> Result<Page<Record>, McpArxivError> {
+        self.list(
+            "ListRecords",
+            args.params(),
+            args.max_pages,
+            "record",
+            parse_record,
+        )
+        .await
+    }
+
+    pub async fn get_record(
+        &self,
+        identifier: &str,
+        metadata_prefix: &str,
+    ) -> Result<Record, McpArxivError> {
+        let params = [
+            ("identifier", oai_identifier(identifier)),
+            ("metadataPrefix", metadata_prefix.to_string()),
+        ];
+        let response = self.request("GetRecord", &params).await?;
+        parse_record(child(child(&response, "GetRecord")?, "record")?)
+    }
+
+    /// Runs a list verb, following `resumptionToken`s for up to `max_pages` pages,
+    /// at most `MAX_PAGES`.
+    async fn list<T>(
+        &self,
+        verb: &str,
+        mut params: Vec<(&'static str, String)>,
+        max_pages: u32,
+        item: &str,
+        parse_item: impl Fn(&Element) -> Result<T, McpArxivError>,
+    ) -> Result<Page<T>, McpArxivError> {
+        let mut page = Page {
+            items: Vec::new(),
+            resumption_token: None,
+            complete_list_size: None,
+            pages_fetched: 0,
+        };
+
+        loop {
+            let response = match self.request(verb, &params).await {
+                Ok(response) => response,
+                // An empty selection is reported as an error by OAI-PMH
+                Err(McpArxivError::OaiError { code, .. }) if code == "noRecordsMatch" => {
+                    return Ok(page);
+                }
+                Err(e) => return Err(e),
+            };
+            let list = child(&response, verb)?;
+            page.pages_fetched += 1;
+
+            for element in list.children_named(item) {
+                page.items.push(parse_item(element)?);
+            }
+
+            let token = list.child("resumptionToken");
+            if let Some(size) = token.and_then(|t| t.attr("completeListSize")) {
+                page.complete_list_size = size.parse().ok();
+            }
+            // An empty token marks the last page
+            page.resumption_token = token.map(Element::collapsed_text).filter(|t| !t.is_empty());
+
+            match &page.resumption_token {
+                Some(token) if page.pages_fetched < max_pages.clamp(1, MAX_PAGES) => {
+                    params = vec![("resumptionToken", token.clone())];
+                }
+                _ => return Ok(page),
+            }
+        }
+    }
+
+    /// Sends one request, waiting out `503 Retry-After` responses, and returns the
+    /// root `OAI-PMH` element.
+    async fn request(
+        &self,
+        verb: &str,
+        params: &[(&str, String)],
+    ) -> Result<Element, McpArxivError> {
+        let mut query: Vec<(&str, String)> = vec![("verb", verb.to_string())];
+        query.extend(params.iter().cloned());
+
+        let mut retries = 0;
+        let res = loop {
+            let res = client::get(&self.base_url, &query).await?;
+
+            if res.status() != StatusCode::SERVICE_UNAVAILABLE || retries >= MAX_RETRIES {
+                break res;
+            }
+            retries += 1;
+
+            let wait = retry_after(res.headers().get(reqwest::header::RETRY_AFTER));
+            tracing::info!(
+                "OAI-PMH {} asked to retry in {}s ({}/{})",
+                verb,
+                wait.as_secs(),
+                retries,
+                MAX_RETRIES
+            );
+            tokio::time::sleep(wait).await;
+        };
+
+        if !res.status().is_success() {
+            let error_text = res.text().await.map_err(McpArxivError::HttpError)?;
+            return Err(McpArxivError::ApiError(format!(
+                "OAI-PMH API error: {}",
+                error_text
+            )));
+        }
+
+        let body = res.text().await.map_err(McpArxivError::HttpError)?;
+        let root = Element::parse(&body)?;
+        if let Some(error) = root.child("error") {
+            return Err(McpArxivError::OaiError {
+                code: error.attr("code").unwrap_or_default().to_string(),
+                message: error.collapsed_text(),
+            });
+        }
+        Ok(root)
+    }
+}
+
+fn retry_after(value: Option<&reqwest::header::HeaderValue>) -> Duration {
+    value
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_RETRY_AFTER)
+        .min(MAX_RETRY_AFTER)
+}
+
+/// Accepts bare arXiv IDs as well as full `oai:arXiv.org:` identifiers.
+pub fn oai_identifier(id: &str) -> String {
+    if id.starts_with("oai:") {
+        id.to_string()
+    } else {
+        format!("oai:arXiv.org:{}", id)
+    }
+}
+
+fn child<'a>(element: &'a Element, name: &str) -> Result<&'a Element, McpArxivError> {
+    element.child(name).ok_or_else(|| {
+        McpArxivError::ParseError(format!("missing <{}> in <{}>", name, element.name))
+    })
+}
+
+fn parse_header(header: &Element) -> Result<Header, McpArxivError> {
+    Ok(Header {
+        identifier: header.child_text("identifier").unwrap_or_default(),
+        datestamp: header.child_text("datestamp").unwrap_or_default(),
+        sets: header
+            .children_named("setSpec")
+            .map(Element::collapsed_text)
+            .collect(),
+        deleted: header.attr("status") == Some("deleted"),
+    })
+}
+
+fn parse_record(record: &Element) -> Result<Record, McpArxivError> {
+    let header = parse_header(child(record, "header")?)?;
+    let metadata = record
+        .child("metadata")
+        .and_then(|m| m.children.first())
+        .map(parse_metadata);
+
+    Ok(Record { header, metadata })
+}
+
+pub fn parse_metadata(format: &Element) -> Metadata {
+    let text = |name: &str| format.child_text(name);
+    let all = |name: &str| {
+        format
+            .children_named(name)
+            .map(Element::collapsed_text)
+            .collect::<Vec<_>>()
+    };
+    let categories = || {
+        text("categories")
+            .map(|c| c.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default()
+    };
+
+    match format.name.as_str() {
+        "dc" => Metadata::DublinCore(DublinCore {
+            title: all("title"),
+            creator: all("creator"),
+            subject: all("subject"),
+            description: all("description"),
+            date: all("date"),
+            kind: all("type"),
+            identifier: all("identifier"),
+        }),
+        "arXiv" => Metadata::ArXiv(ArXivMetadata {
+            id: text("id").unwrap_or_default(),
+            created: text("created"),
+            updated: text("updated"),
+            authors: format
+                .child("authors")
+                .map(|authors| {
+                    authors
+                        .children_named("author")
+                        .map(|author| ArXivAuthor {
+                            keyname: author.child_text("keyname").unwrap_or_default(),
+                            forenames: author.child_text("forenames"),
+                            suffix: author.child_text("suffix"),
+                            affiliations: author
+                                .children_named("affiliation")
+                                .map(Element::collapsed_text)
+                                .collect(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            title: text("title").unwrap_or_default(),
+            categories: categories(),
+            comments: text("comments"),
+            report_no: text("report-no"),
+            msc_class: text("msc-class"),
+            acm_class: text("acm-class"),
+            journal_ref: text("journal-ref"),
+            doi: text("doi"),
+            license: text("license"),
+            summary: text("abstract").unwrap_or_default(),
+        }),
+        "arXivRaw" => Metadata::ArXivRaw(ArXivRawMetadata {
+            id: text("id").unwrap_or_default(),
+            submitter: text("submitter"),
+            versions: format
+                .children_named("version")
+                .map(|version| ArXivVersion {
+                    version: version.attr("version").unwrap_or_default().to_string(),
+                    date: version.child_text("date").unwrap_or_default(),
+                    size: version.child_text("size"),
+                    source_type: version.child_text("source_type"),
+                })
+                .collect(),
+            title: text("title").unwrap_or_default(),
+            authors: text("authors").unwrap_or_default(),
+            categories: categories(),
+            comments: text("comments"),
+            proxy: text("proxy"),
+            report_no: text("report-no"),
+            msc_class: text("msc-class"),
+            acm_class: text("acm-class"),
+            journal_ref: text("journal-ref"),
+            doi: text("doi"),
+            license: text("license"),
+            summary: text("abstract").unwrap_or_default(),
+        }),
+        other => Metadata::Other {
+            element: other.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const PAGE_ONE: &str = r#"<OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/">
+      <ListRecords>
+        <record>
+          <header><identifier>oai:arXiv.org:0704.0001</identifier><datestamp>2024-01-01</datestamp><setSpec>physics:hep-ph</setSpec></header>
+          <metadata>
+            <arXiv xmlns="http://arxiv.org/OAI/arXiv/">
+              <id>0704.0001</id><created>2007-04-02</created>
+              <authors><author><keyname>Balázs</keyname><forenames>C.</forenames><affiliation>Argonne</affiliation></author></authors>
+              <title>Calculation of prompt
+                diphoton production</title>
+              <categories>hep-ph hep-ex</categories>
+              <journal-ref>Phys.Rev.D76:013009,2007</journal-ref>
+              <abstract>A fully differential calculation.</abstract>
+            </arXiv>
+          </metadata>
+        </record>
+        <record>
+          <header status="deleted"><identifier>oai:arXiv.org:0704.0002</identifier><datestamp>2024-01-01</datestamp></header>
+        </record>
+        <resumptionToken cursor="0" completeListSize="3">token-2</resumptionToken>
+      </ListRecords>
+    </OAI-PMH>"#;
+
+    const PAGE_TWO: &str = r#"<OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/">
+      <ListRecords>
+        <record>
+          <header><identifier>oai:arXiv.org:0704.0003</identifier><datestamp>2024-01-02</datestamp></header>
+          <metadata>
+            <arXivRaw xmlns="http://arxiv.org/OAI/arXivRaw/">
+              <id>0704.0003</id><submitter>Paul Harvey</submitter>
+              <version version="v1"><date>Mon, 2 Apr 2007 19:18:42 GMT</date><size>10kb</size><source_type>D</source_type></version>
+              <version version="v2"><date>Tue, 3 Apr 2007 10:00:00 GMT</date><size>11kb</size></version>
+              <title>Evolution of the Earth-Moon system</title>
+              <authors>Paul Harvey, Bruce Merrifield</authors>
+              <categories>astro-ph</categories>
+              <abstract>The evolution.</abstract>
+            </arXivRaw>
+          </metadata>
+        </record>
+        <resumptionToken cursor="2" completeListSize="3"></resumptionToken>
+      </ListRecords>
+    </OAI-PMH>"#;
+
+    /// Serves a 503 first, then `PAGE_ONE` and `PAGE_TWO` depending on whether
+    /// the request carries a resumption token.
+    async fn stub_server(requests: Arc<AtomicUsize>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 8192];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+
+                let response = if requests.fetch_add(1, Ordering::SeqCst) == 0 {
+                    "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+                } else {
+                    let body = if request.contains("resumptionToken=token-2") {
+                        PAGE_TWO
+                    } else {
+                        PAGE_ONE
+                    };
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                };
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_list_records_follows_tokens_and_retries() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let client = OaiClient::new(&stub_server(requests.clone()).await);
+
+        let args = ListArgs {
+            metadata_prefix: "arXiv".to_string(),
+            max_pages: 1,
+            ..Default::default()
+        };
+        let first = client.list_records(&args).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(first.items.len(), 2);
+        assert_eq!(first.resumption_token.as_deref(), Some("token-2"));
+        assert_eq!(first.complete_list_size, Some(3));
+        assert!(first.items[1].header.deleted);
+
+        let Some(Metadata::ArXiv(paper)) = &first.items[0].metadata else {
+            panic!("expected arXiv metadata");
+        };
+        assert_eq!(paper.title, "Calculation of prompt diphoton production");
+        assert_eq!(paper.categories, ["hep-ph", "hep-ex"]);
+        assert_eq!(paper.authors[0].affiliations, ["Argonne"]);
+
+        let all = client
+            .list_records(&ListArgs {
+                max_pages: 10,
+                ..args
+            })
+            .await
+            .unwrap();
+        assert_eq!(all.pages_fetched, 2);
+        assert_eq!(all.items.len(), 3);
+        assert_eq!(all.resumption_token, None);
+
+        let Some(Metadata::ArXivRaw(raw)) = &all.items[2].metadata else {
+            panic!("expected arXivRaw metadata");
+        };
+        assert_eq!(raw.versions.len(), 2);
+        assert_eq!(raw.versions[1].version, "v2");
+        assert_eq!(raw.submitter.as_deref(), Some("Paul Harvey"));
+    }
+
+    #[test]
+    fn test_parse_dublin_core_and_errors() {
+        let dc = Element::parse(
+            r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/">
+              <dc:title>Sparsity-certifying Graph Decompositions</dc:title>
+              <dc:creator>Streinu, Ileana</dc:creator>
+              <dc:creator>Theran, Louis</dc:creator>
+              <dc:identifier>http://arxiv.org/abs/0704.0002</dc:identifier>
+            </oai_dc:dc>"#,
+        )
+        .unwrap();
+        let Metadata::DublinCore(dc) = parse_metadata(&dc) else {
+            panic!("expected Dublin Core");
+        };
+        assert_eq!(dc.creator, ["Streinu, Ileana", "Theran, Louis"]);
+
+        assert_eq!(oai_identifier("0704.0001"), "oai:arXiv.org:0704.0001");
+        assert_eq!(retry_after(None), DEFAULT_RETRY_AFTER);
+    }
+}
@@ -0,0 +1,172 @@
+//! Loading and parsing PDFs in memory.
+//!
+//! Downloads are streamed with a size cap and never touch the disk, so
+//! concurrent calls cannot race on files and a failed call leaves nothing
+//! behind. Parsing is CPU-bound and runs on the blocking thread pool, a few
+//! PDFs at a time, with a timeout.
+
+use lazy_static::lazy_static;
+use pdf_extract::extract_text_from_mem_by_pages;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+use super::client;
+use super::errors::McpArxivError;
+
+const PDF_MAGIC: &[u8] = b"%PDF-";
+const DEFAULT_MAX_PDF_MB: u64 = 50;
+const DEFAULT_PARSE_TIMEOUT_SECS: u64 = 120;
+
+lazy_static! {
+    /// Parses running at once, each holds a whole PDF and its text in memory
+    static ref PARSE_PERMITS: Arc<Semaphore> = Arc::new(Semaphore::new(
+        std::thread::available_parallelism().map_or(2, |n| n.get().min(4))
+    ));
+}
+
+/// `ARXIV_MAX_PDF_MB` in bytes.
+fn max_bytes() -> u64 {
+    crate::config::var("ARXIV_MAX_PDF_MB")
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|mb| *mb > 0)
+        .unwrap_or(DEFAULT_MAX_PDF_MB)
+        * 1024
+        * 1024
+}
+
+/// `ARXIV_PDF_TIMEOUT_SECS`
+fn parse_timeout() -> Duration {
+    Duration::from_secs(
+        crate::config::var("ARXIV_PDF_TIMEOUT_SECS")
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_PARSE_TIMEOUT_SECS),
+    )
+}
+
+fn too_large(what: &str, limit: u64) -> McpArxivError {
+    McpArxivError::PdfError(format!(
+        "{} is larger than the {} MB limit (ARXIV_MAX_PDF_MB)",
+        what,
+        limit / 1024 / 1024
+    ))
+}
+
+/// Downloads the PDF at `url`, failing as soon as it exceeds the size limit.
+pub async fn download(url: &str) -> Result<Vec<u8>, McpArxivError> {
+    let limit = max_bytes();
+    let mut response = client::get(url, &[]).await?;
+    if !response.status().is_success() {
+        return Err(McpArxivError::ApiError(format!(
+            "Failed to download PDF: {}",
+            response.status()
+        )));
+    }
+    if response.content_length().is_some_and(|len| len > limit) {
+        return Err(too_large(url, limit));
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(McpArxivError::HttpError)? {
+        if (bytes.len() + chunk.len()) as u64 > limit {
+            return Err(too_large(url, limit));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    check_pdf(&bytes, url)?;
+    Ok(bytes)
+}
+
+/// Reads a local PDF. The header is checked before the rest is read, so a
+/// path cannot be used to read arbitrary files.
+pub fn read(path: &Path) -> Result<Vec<u8>, McpArxivError> {
+    let read_error = |e: std::io::Error| {
+        McpArxivError::PdfError(format!("Failed to read {}: {}", path.display(), e))
+    };
+    let mut file = fs::File::open(path).map_err(read_error)?;
+    let mut header = [0u8; 5];
+    file.read_exact(&mut header).map_err(read_error)?;
+    check_pdf(&header, &path.display().to_string())?;
+
+    let limit = max_bytes();
+    if file.metadata().map_err(read_error)?.len() > limit {
+        return Err(too_large(&path.display().to_string(), limit));
+    }
+    let mut bytes = header.to_vec();
+    file.read_to_end(&mut bytes).map_err(read_error)?;
+    Ok(bytes)
+}
+
+/// Rejects anything that is not a PDF before it reaches the parser.
+pub fn check_pdf(bytes: &[u8], name: &str) -> Result<(), McpArxivError> {
+    if bytes.starts_with(PDF_MAGIC) {
+        Ok(())
+    } else {
+        Err(McpArxivError::PdfError(format!("{} is not a PDF", name)))
+    }
+}
+
+/// Extracts the text of each page.
+pub async fn extract_pages(bytes: Vec<u8>) -> Result<Vec<String>, McpArxivError> {
+    parse(bytes, |bytes| {
+        extract_text_from_mem_by_pages(bytes).map_err(|e| McpArxivError::PdfError(e.to_string()))
+    })
+    .await
+}
+
+/// Runs `parser` over `bytes` on the blocking thread pool. A parse that times
+/// out keeps its permit until the thread finishes, so stuck parses cannot
+/// pile up.
+pub async fn parse<T, F>(bytes: Vec<u8>, parser: F) -> Result<T, McpArxivError>
+where
+    T: Send + 'static,
+    F: FnOnce(&[u8]) -> Result<T, McpArxivError> + Send + 'static,
+{
+    let permit = PARSE_PERMITS
+        .clone()
+        .acquire_owned()
+        .await
+        .map_err(|e| McpArxivError::PdfError(e.to_string()))?;
+    let timeout = parse_timeout();
+    let parse = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        parser(&bytes)
+    });
+
+    match tokio::time::timeout(timeout, parse).await {
+        Ok(Ok(result)) => result,
+        // The parsers panic on some malformed PDFs
+        Ok(Err(e)) => Err(McpArxivError::PdfError(format!(
+            "the PDF parser failed: {}",
+            e
+        ))),
+        Err(_) => Err(McpArxivError::PdfError(format!(
+            "parsing took longer than {} seconds (ARXIV_PDF_TIMEOUT_SECS)",
+            timeout.as_secs()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_rejects_other_files() {
+        let err = read(Path::new("Cargo.toml")).unwrap_err();
+        assert!(err.to_string().contains("is not a PDF"));
+        assert!(check_pdf(b"%PDF-1.7\n", "x").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_extract_pages_reports_parser_failures() {
+        let err = extract_pages(b"%PDF-1.7\nnot really".to_vec())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, McpArxivError::PdfError(_)));
+    }
+}
@@ -0,0 +1,389 @@
+use serde_json::Value;
+
+use super::errors::McpArxivError;
+use super::taxonomy::{canonical_category, similar_categories};
+
+/// Searchable fields of the arXiv API, see
+/// <https://info.arxiv.org/help/api/user-manual.html#query_details>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    Abstract,
+    Author,
+    Comment,
+    JournalRef,
+    Category,
+    ReportNumber,
+    All,
+}
+
+impl Field {
+    pub fn parse(name: &str) -> Result<Field, McpArxivError> {
+        Ok(match name.trim().to_ascii_lowercase().as_str() {
+            "ti" | "title" => Field::Title,
+            "abs" | "abstract" => Field::Abstract,
+            "au" | "author" => Field::Author,
+            "co" | "comment" | "comments" => Field::Comment,
+            "jr" | "journal_ref" => Field::JournalRef,
+            "cat" | "category" => Field::Category,
+            "rn" | "report_number" => Field::ReportNumber,
+            "all" | "text" => Field::All,
+            other => {
+                return Err(McpArxivError::InvalidQuery(format!(
+                    "unknown field `{}`, expected one of title, abstract, author, comment, journal_ref, category, report_number, text",
+                    other
+                )));
+            }
+        })
+    }
+
+    fn prefix(&self) -> &'static str {
+        match self {
+            Field::Title => "ti",
+            Field::Abstract => "abs",
+            Field::Author => "au",
+            Field::Comment => "co",
+            Field::JournalRef => "jr",
+            Field::Category => "cat",
+            Field::ReportNumber => "rn",
+            Field::All => "all",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    And,
+    Or,
+    AndNot,
+}
+
+impl Operator {
+    fn parse(name: &str) -> Result<Operator, McpArxivError> {
+        match name.trim().to_ascii_uppercase().as_str() {
+            "AND" => Ok(Operator::And),
+            "OR" => Ok(Operator::Or),
+            "ANDNOT" | "AND_NOT" | "NOT" => Ok(Operator::AndNot),
+            other => Err(McpArxivError::InvalidQuery(format!(
+                "unknown operator `{}`, expected AND, OR or ANDNOT",
+                other
+            ))),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Operator::And => "AND",
+            Operator::Or => "OR",
+            Operator::AndNot => "ANDNOT",
+        }
+    }
+}
+
+/// A term or a boolean group of clauses.
+///
+/// ```json
+/// { "op": "ANDNOT", "clauses": [
+///     { "field": "title", "value": "neural network" },
+///     { "field": "category", "value": "cs.NE" }
+/// ] }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Clause {
+    Term { field: Field, value: String },
+    Group { op: Operator, clauses: Vec<Clause> },
+}
+
+impl Clause {
+    pub fn from_value(value: &Value) -> Result<Clause, McpArxivError> {
+        let invalid =
+            |message: &str| McpArxivError::InvalidQuery(format!("{}: {}", message, value));
+
+        let object = value
+            .as_object()
+            .ok_or_else(|| invalid("clause must be an object"))?;
+        if let Some(clauses) = object.get("clauses") {
+            let op = match object.get("op") {
+                Some(op) => Operator::parse(
+                    op.as_str()
+                        .ok_or_else(|| invalid("`op` must be a string"))?,
+                )?,
+                None => Operator::And,
+            };
+            let clauses = clauses
+                .as_array()
+                .ok_or_else(|| invalid("`clauses` must be an array"))?
+                .iter()
+                .map(Clause::from_value)
+                .collect::<Result<Vec<_>, _>>()?;
+            if clauses.is_empty() {
+                return Err(invalid("group has no clauses"));
+            }
+            // A single clause compiles to just that clause, dropping the negation
+            if op == Operator::AndNot && clauses.len() < 2 {
+                return Err(invalid(
+                    "ANDNOT needs a clause to keep and at least one to exclude",
+                ));
+            }
+            return Ok(Clause::Group { op, clauses });
+        }
+
+        let field = object
+            .get("field")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid("term needs a string `field`"))?;
+        let term = object
+            .get("value")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid("term needs a string `value`"))?;
+        Ok(Clause::Term {
+            field: Field::parse(field)?,
+            value: term.to_string(),
+        })
+    }
+
+    pub fn compile(&self) -> Result<String, McpArxivError> {
+        match self {
+            Clause::Term { field, value } => term(*field, value),
+            Clause::Group { op, clauses } => {
+                let parts = clauses
+                    .iter()
+                    .map(Clause::compile)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(join(&parts, *op))
+            }
+        }
+    }
+}
+
+/// Structured search, every set field must match.
+#[derive(Debug, Clone, Default)]
+pub struct QuerySpec {
+    pub title: Option<String>,
+    pub abstract_text: Option<String>,
+    /// Every author must match
+    pub authors: Vec<String>,
+    /// Any of the categories may match
+    pub categories: Vec<String>,
+    pub text: Option<String>,
+    pub submitted_from: Option<String>,
+    pub submitted_to: Option<String>,
+    pub groups: Vec<Clause>,
+}
+
+impl QuerySpec {
+    /// Compiles the spec into an arXiv `search_query` string.
+    pub fn compile(&self) -> Result<String, McpArxivError> {
+        let mut parts = Vec::new();
+
+        for (field, value) in [
+            (Field::Title, &self.title),
+            (Field::Abstract, &self.abstract_text),
+            (Field::All, &self.text),
+        ] {
+            if let Some(value) = value.as_ref().filter(|v| !v.trim().is_empty()) {
+                parts.push(term(field, value)?);
+            }
+        }
+        for author in self.authors.iter().filter(|a| !a.trim().is_empty()) {
+            parts.push(term(Field::Author, author)?);
+        }
+
+        let categories = self
+            .categories
+            .iter()
+            .filter(|c| !c.trim().is_empty())
+            .map(|c| term(Field::Category, c))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !categories.is_empty() {
+            parts.push(join(&categories, Operator::Or));
+        }
+
+        if self.submitted_from.is_some() || self.submitted_to.is_some() {
+            let from = match &self.submitted_from {
+                Some(date) => submitted_date(date, "0000")?,
+                None => "199101010000".to_string(),
+            };
+            let to = match &self.submitted_to {
+                Some(date) => submitted_date(date, "2359")?,
+                None => "299912312359".to_string(),
+            };
+            if from > to {
+                return Err(McpArxivError::InvalidQuery(
+                    "submitted_from is after submitted_to".to_string(),
+                ));
+            }
+            parts.push(format!("submittedDate:[{} TO {}]", from, to));
+        }
+
+        for group in &self.groups {
+            parts.push(group.compile()?);
+        }
+
+        if parts.is_empty() {
+            return Err(McpArxivError::InvalidQuery(
+                "at least one search field is required".to_string(),
+            ));
+        }
+        Ok(parts.join(" AND "))
+    }
+}
+
+fn join(parts: &[String], op: Operator) -> String {
+    match parts {
+        [single] => single.clone(),
+        _ => format!("({})", parts.join(&format!(" {} ", op.as_str()))),
+    }
+}
+
+fn term(field: Field, value: &str) -> Result<String, McpArxivError> {
+    if field == Field::Category {
+        let category = canonical_category(value).ok_or_else(|| {
+            let similar = similar_categories(value);
+            McpArxivError::InvalidQuery(if similar.is_empty() {
+                format!("unknown category `{}`", value.trim())
+            } else {
+                format!(
+                    "unknown category `{}`, did you mean one of: {}",
+                    value.trim(),
+                    similar.join(", ")
+                )
+            })
+        })?;
+        return Ok(format!("cat:{}", category));
+    }
+
+    Ok(format!("{}:{}", field.prefix(), phrase(value)?))
+}
+
+/// Quotes `value` as a phrase when needed, including values that would read as
+/// an operator. The API has no escape for `"` inside a phrase, so quotes are
+/// dropped along with the whitespace arXiv would ignore anyway.
+fn phrase(value: &str) -> Result<String, McpArxivError> {
+    let cleaned = value
+        .replace('"', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if cleaned.is_empty() {
+        return Err(McpArxivError::InvalidQuery(format!(
+            "empty search term `{}`",
+            value
+        )));
+    }
+    let operator = ["AND", "OR", "ANDNOT"]
+        .iter()
+        .any(|op| cleaned.eq_ignore_ascii_case(op));
+    if operator || cleaned.contains(char::is_whitespace) || cleaned.contains(['(', ')', ':']) {
+        Ok(format!("\"{}\"", cleaned))
+    } else {
+        Ok(cleaned)
+    }
+}
+
+/// Accepts `YYYY-MM-DD`, `YYYYMMDD` or `YYYYMMDDHHMM` and returns `YYYYMMDDHHMM`.
+fn submitted_date(date: &str, default_time: &str) -> Result<String, McpArxivError> {
+    let digits: String = date.chars().filter(|c| c.is_ascii_digit()).collect();
+    let well_formed = date
+        .trim()
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '-' | ' ' | ':' | 'T'));
+    let full = match digits.len() {
+        8 if well_formed => format!("{}{}", digits, default_time),
+        12 if well_formed => digits,
+        _ => {
+            return Err(McpArxivError::InvalidQuery(format!(
+                "invalid date `{}`, expected YYYY-MM-DD or YYYYMMDDHHMM",
+                date
+            )));
+        }
+    };
+
+    let month: u32 = full[4..6].parse().unwrap_or_default();
+    let day: u32 = full[6..8].parse().unwrap_or_default();
+    let hour: u32 = full[8..10].parse().unwrap_or_default();
+    let minute: u32 = full[10..12].parse().unwrap_or_default();
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return Err(McpArxivError::InvalidQuery(format!(
+            "invalid date `{}`",
+            date
+        )));
+    }
+    Ok(full)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compile_structured_fields() {
+        let spec = QuerySpec {
+            title: Some("graph \"neural\" networks".to_string()),
+            authors: vec!["Hinton, Geoffrey".to_string(), "LeCun".to_string()],
+            categories: vec!["cs.lg".to_string(), "stat.ML".to_string()],
+            submitted_from: Some("2023-01-01".to_string()),
+            submitted_to: Some("20231231".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            spec.compile().unwrap(),
+            "ti:\"graph neural networks\" AND au:\"Hinton, Geoffrey\" AND au:LeCun \
+             AND (cat:cs.LG OR cat:stat.ML) AND submittedDate:[202301010000 TO 202312312359]"
+        );
+    }
+
+    #[test]
+    fn test_compile_groups() {
+        let group = Clause::from_value(&json!({
+            "op": "ANDNOT",
+            "clauses": [
+                { "op": "OR", "clauses": [
+                    { "field": "abstract", "value": "diffusion" },
+                    { "field": "abs", "value": "score matching" }
+                ] },
+                { "field": "category", "value": "cs.CV" }
+            ]
+        }))
+        .unwrap();
+        let spec = QuerySpec {
+            groups: vec![group],
+            ..Default::default()
+        };
+        assert_eq!(
+            spec.compile().unwrap(),
+            "((abs:diffusion OR abs:\"score matching\") ANDNOT cat:cs.CV)"
+        );
+
+        let operator = Clause::from_value(&json!({ "field": "title", "value": "OR" })).unwrap();
+        assert_eq!(operator.compile().unwrap(), "ti:\"OR\"");
+    }
+
+    #[test]
+    fn test_compile_rejects_invalid_input() {
+        let category = QuerySpec {
+            categories: vec!["cs.XYZ".to_string()],
+            ..Default::default()
+        };
+        let err = category.compile().unwrap_err().to_string();
+        assert!(err.contains("cs.LG"));
+
+        let date = QuerySpec {
+            submitted_from: Some("2023-13-01".to_string()),
+            ..Default::default()
+        };
+        assert!(date.compile().is_err());
+
+        assert!(QuerySpec::default().compile().is_err());
+        assert!(Clause::from_value(&json!({ "field": "doi", "value": "x" })).is_err());
+        assert!(Clause::from_value(&json!({ "op": "XOR", "clauses": [] })).is_err());
+        assert!(
+            Clause::from_value(&json!({
+                "op": "NOT",
+                "clauses": [{ "field": "category", "value": "cs.CV" }]
+            }))
+            .is_err()
+        );
+    }
+}
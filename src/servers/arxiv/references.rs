@@ -0,0 +1,297 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+
+lazy_static! {
+    static ref DOI: Regex = Regex::new(r#"\b10\.\d{4,9}/[^\s"<>]+"#).unwrap();
+    /// `arXiv:2101.00001v2`, `arxiv.org/abs/2101.00001`, `10.48550/arXiv.2101.00001`
+    static ref PREFIXED_ARXIV_ID: Regex = Regex::new(
+        r"(?i)arxiv(?:\.org)?[:\s./]*(?:preprint\s+)?(?:abs/|pdf/)?(\d{4}\.\d{4,5}(?:v\d+)?|[a-z][a-z-]*(?:\.[a-z]{2})?/\d{7}(?:v\d+)?)"
+    )
+    .unwrap();
+    static ref BARE_ARXIV_ID: Regex = Regex::new(r"\b(\d{2})(\d{2})\.(\d{4,5})(v\d+)?\b").unwrap();
+    static ref URL: Regex = Regex::new(r"https?://\S+").unwrap();
+    static ref YEAR: Regex = Regex::new(r"\b(19\d{2}|20\d{2})[a-z]?\b").unwrap();
+    static ref BRACKET_LABEL: Regex = Regex::new(r"^\[([^\]\s]{1,12})\]\s*").unwrap();
+    static ref NUMBER_LABEL: Regex = Regex::new(r"^(\d{1,3})\.\s+").unwrap();
+    static ref AUTHOR_START: Regex = Regex::new(r"^\p{Lu}[\p{L}'’-]+,\s").unwrap();
+    static ref TRAILING_YEAR: Regex = Regex::new(r"[\s,.]*\(?(19|20)\d{2}[a-z]?\)?$").unwrap();
+    static ref QUOTED: Regex = Regex::new(r#"[“"]([^”"]{8,})[”"]"#).unwrap();
+}
+
+/// One entry of a paper's references section.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reference {
+    /// Label as printed, e.g. `12` for `[12]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub text: String,
+    /// Best-effort guess, reference styles vary too much to be sure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authors: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub arxiv_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dois: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Style {
+    Bracket,
+    Numbered,
+    AuthorYear,
+}
+
+/// Splits the text of a references section into entries.
+pub fn parse_references(text: &str) -> Vec<Reference> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let style = match lines.iter().find(|l| !l.is_empty()) {
+        Some(first) if BRACKET_LABEL.is_match(first) => Style::Bracket,
+        Some(first) if NUMBER_LABEL.is_match(first) => Style::Numbered,
+        _ => Style::AuthorYear,
+    };
+
+    let mut entries: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut next_number = 1;
+    for line in lines {
+        let starts_entry = match style {
+            Style::Bracket => BRACKET_LABEL.is_match(line),
+            // Only the next number in sequence, so wrapped lines starting with
+            // a number are not mistaken for entries
+            Style::Numbered => NUMBER_LABEL
+                .captures(line)
+                .is_some_and(|c| c[1].parse::<u32>() == Ok(next_number)),
+            Style::AuthorYear => {
+                line.is_empty() || (current.ends_with('.') && AUTHOR_START.is_match(line))
+            }
+        };
+        if starts_entry {
+            if !current.trim().is_empty() {
+                entries.push(std::mem::take(&mut current));
+            }
+            current.clear();
+            if style == Style::Numbered {
+                next_number += 1;
+            }
+        }
+        append_line(&mut current, line);
+    }
+    if !current.trim().is_empty() {
+        entries.push(current);
+    }
+
+    entries.iter().map(|e| parse_entry(e, style)).collect()
+}
+
+/// Joins wrapped lines, undoing hyphenation and keeping URLs and DOIs whole.
+fn append_line(entry: &mut String, line: &str) {
+    if line.is_empty() {
+        return;
+    }
+    if entry.is_empty() {
+        entry.push_str(line);
+        return;
+    }
+    let glued = entry.ends_with('/')
+        || (entry.ends_with('-') && !entry.ends_with(" -") && line.starts_with(char::is_lowercase));
+    if entry.ends_with('-') && glued && !entry.ends_with("--") {
+        entry.pop();
+    }
+    if !glued {
+        entry.push(' ');
+    }
+    entry.push_str(line);
+}
+
+fn parse_entry(entry: &str, style: Style) -> Reference {
+    let (label, text) = match style {
+        Style::Bracket => strip_label(&BRACKET_LABEL, entry),
+        Style::Numbered => strip_label(&NUMBER_LABEL, entry),
+        Style::AuthorYear => (None, entry.trim().to_string()),
+    };
+
+    let dois = find_dois(&text);
+    let arxiv_ids = find_arxiv_ids(&text);
+
+    // Years are searched for once identifiers are removed, `2012.01234` is not
+    // a publication year
+    let mut plain = URL.replace_all(&text, " ").to_string();
+    for id in dois.iter().chain(&arxiv_ids) {
+        plain = plain.replace(id.as_str(), " ");
+    }
+    let year = YEAR.captures(&plain).and_then(|c| c[1].parse().ok());
+
+    let (authors, title) = authors_and_title(&text);
+    Reference {
+        label,
+        text,
+        authors,
+        title,
+        year,
+        arxiv_ids,
+        dois,
+    }
+}
+
+fn strip_label(pattern: &Regex, entry: &str) -> (Option<String>, String) {
+    let entry = entry.trim();
+    match pattern.captures(entry) {
+        Some(c) => (
+            Some(c[1].to_string()),
+            entry[c.get(0).map_or(0, |m| m.end())..].to_string(),
+        ),
+        None => (None, entry.to_string()),
+    }
+}
+
+/// Handles `A. Turing, "Title," Venue` and `A. Turing. Title. Venue` with an
+/// optional year between authors and title.
+fn authors_and_title(text: &str) -> (Option<String>, Option<String>) {
+    let clean = |s: &str| {
+        let s = s.trim().trim_matches([',', '.', ' ']).trim();
+        (!s.is_empty()).then(|| s.to_string())
+    };
+
+    if let Some(quoted) = QUOTED.captures(text)
+        && let Some(whole) = quoted.get(0)
+    {
+        return (clean(&text[..whole.start()]), clean(&quoted[1]));
+    }
+
+    let segments = sentences(text);
+    let authors = segments
+        .first()
+        .and_then(|s| clean(&TRAILING_YEAR.replace(s, "")));
+    let mut rest = segments.iter().skip(1);
+    let mut title = rest.next();
+    if title.is_some_and(|t| YEAR.is_match(t) && t.trim().len() <= 6) {
+        title = rest.next();
+    }
+    let title = title
+        .and_then(|t| clean(t))
+        .filter(|t| t.split_whitespace().count() >= 2);
+    (authors, title)
+}
+
+/// Splits on `. ` unless the period ends an initial such as `A.`
+fn sentences(text: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    for (i, _) in text.match_indices(". ") {
+        let word = text[start..i].rsplit(' ').next().unwrap_or_default();
+        let initial = word.chars().count() <= 1
+            || (word.contains('.') && word.chars().filter(|c| c.is_alphabetic()).count() <= 2);
+        if !initial {
+            segments.push(&text[start..i]);
+            start = i + 2;
+        }
+    }
+    segments.push(&text[start..]);
+    segments
+}
+
+pub fn find_dois(text: &str) -> Vec<String> {
+    let mut dois: Vec<String> = Vec::new();
+    for m in DOI.find_iter(text) {
+        let doi = m.as_str().trim_end_matches(['.', ',', ';', ')', ']']);
+        if !dois.iter().any(|d| d.eq_ignore_ascii_case(doi)) {
+            dois.push(doi.to_string());
+        }
+    }
+    dois
+}
+
+pub fn find_arxiv_ids(text: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    let mut push = |id: &str| {
+        if !ids.iter().any(|i| i == id) {
+            ids.push(id.to_string());
+        }
+    };
+
+    for c in PREFIXED_ARXIV_ID.captures_iter(text) {
+        push(&c[1]);
+    }
+    // New-style IDs are distinctive enough to be found without a prefix, as
+    // long as they are not part of a DOI
+    let without_dois = DOI.replace_all(text, " ");
+    for c in BARE_ARXIV_ID.captures_iter(&without_dois) {
+        let month: u32 = c[2].parse().unwrap_or_default();
+        let yymm: u32 = format!("{}{}", &c[1], &c[2]).parse().unwrap_or_default();
+        // Five digit numbers were introduced in January 2015
+        let digits_ok = (c[3].len() == 5) == (yymm >= 1501);
+        if (1..=12).contains(&month) && digits_ok {
+            push(c.get(0).map_or("", |m| m.as_str()));
+        }
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bracket_references() {
+        let text = "[1] A. Vaswani, N. Shazeer, and N. Parmar, “Attention is all you need,” in
+NeurIPS, 2017. arXiv:1706.03762
+[2] K. He and J. Sun. Deep residual learning for image recog-
+nition. In CVPR, 2016. doi: 10.1109/CVPR.2016.90.
+[3] T. Brown et al. Language models are few-shot learners. https://arxiv.org/abs/2005.14165v4";
+        let refs = parse_references(text);
+        assert_eq!(refs.len(), 3);
+
+        assert_eq!(refs[0].label.as_deref(), Some("1"));
+        assert_eq!(refs[0].title.as_deref(), Some("Attention is all you need"));
+        assert_eq!(
+            refs[0].authors.as_deref(),
+            Some("A. Vaswani, N. Shazeer, and N. Parmar")
+        );
+        assert_eq!(refs[0].year, Some(2017));
+        assert_eq!(refs[0].arxiv_ids, ["1706.03762"]);
+
+        assert_eq!(
+            refs[1].title.as_deref(),
+            Some("Deep residual learning for image recognition")
+        );
+        assert_eq!(refs[1].dois, ["10.1109/CVPR.2016.90"]);
+        assert!(refs[1].arxiv_ids.is_empty());
+
+        assert_eq!(refs[2].authors.as_deref(), Some("T. Brown et al"));
+        assert_eq!(refs[2].arxiv_ids, ["2005.14165v4"]);
+        assert_eq!(refs[2].year, None);
+    }
+
+    #[test]
+    fn test_parse_author_year_references() {
+        let text = "Devlin, J., Chang, M. 2019. BERT: Pre-training of deep
+bidirectional transformers. In NAACL.
+Gell-Mann, M. 1964. A schematic model of baryons and mesons.
+Physics Letters, 8(3). arXiv preprint hep-th/9901001.";
+        let refs = parse_references(text);
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].year, Some(2019));
+        assert_eq!(refs[0].authors.as_deref(), Some("Devlin, J., Chang, M"));
+        assert_eq!(
+            refs[0].title.as_deref(),
+            Some("BERT: Pre-training of deep bidirectional transformers")
+        );
+        assert_eq!(refs[1].arxiv_ids, ["hep-th/9901001"]);
+    }
+
+    #[test]
+    fn test_find_arxiv_ids() {
+        assert_eq!(
+            find_arxiv_ids(
+                "see 10.48550/arXiv.2101.00001 and 1412.6980, not 1234.5678 or 9913.12345"
+            ),
+            ["2101.00001", "1412.6980"]
+        );
+    }
+}
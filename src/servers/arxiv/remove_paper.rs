@@ -0,0 +1,27 @@
+use super::library::Library;
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+
+#[tool(
+    name = "RemovePaper",
+    description = "Remove a paper and its PDF from the local library.",
+    params(id = "The arXiv ID of the saved paper")
+)]
+pub async fn remove_paper_tool(id: String) -> Result<ToolResponseContent> {
+    let entry = Library::open().remove(&id)?;
+    Ok(tool_text_content!(format!(
+        "Removed {} ({}) from the library",
+        entry.paper.id, entry.paper.title
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_remove_paper_tool_missing() {
+        assert!(remove_paper_tool("0000.00000".to_string()).await.is_err());
+    }
+}
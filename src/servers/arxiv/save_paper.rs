@@ -0,0 +1,59 @@
+use super::atom::fetch_papers;
+use super::errors::McpArxivError;
+use super::extract_paper_text::{download_arxiv_pdf, parse_pdf};
+use super::library::Library;
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+use serde_json::json;
+
+#[tool(
+    name = "SavePaper",
+    description = "Save an arXiv paper to the local library: its metadata, PDF and extracted text. Saved papers can be searched with SearchLibrary and read offline with ExtractPaperText. Saving a paper again replaces it.",
+    params(id = "The arXiv ID (e.g. '2101.00001v2')")
+)]
+pub async fn save_paper_tool(id: String) -> Result<ToolResponseContent> {
+    let id = id.trim().to_string();
+    let paper = fetch_papers(std::slice::from_ref(&id)).await?.remove(0);
+
+    // Always download, a saved copy may be an older version
+    let pdf = download_arxiv_pdf(&id).await?;
+    let text = parse_pdf(pdf.clone(), None).await?;
+    let sections = text.sections.len();
+    let library = Library::open();
+    let entry = tokio::task::spawn_blocking(move || library.save(&paper, &text, &pdf))
+        .await
+        .map_err(|e| McpArxivError::LibraryError(e.to_string()))??;
+
+    Ok(tool_text_content!(
+        serde_json::to_string(&json!({
+            "id": entry.paper.id,
+            "version": entry.paper.version,
+            "title": entry.paper.title,
+            "pageCount": entry.page_count,
+            "sections": sections,
+            "savedAt": entry.saved_at,
+        }))
+        .map_err(McpArxivError::ResponseSerializeError)?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_save_paper_tool() {
+        match save_paper_tool("2101.00001".to_string()).await {
+            Ok(content) => {
+                println!("Saved: {:?}", content);
+                assert!(format!("{:?}", content).contains("pageCount"));
+                Library::open().remove("2101.00001").unwrap();
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use super::errors::McpArxivError;
+use super::library::Library;
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+
+#[tool(
+    name = "SearchLibrary",
+    description = "Full-text search over papers saved with SavePaper, ranked with BM25. Works offline. Returns JSON results with id, title, authors, score and a snippet of matching text.",
+    params(
+        query = "Words to search for in titles, abstracts and full text",
+        limit = "Maximum number of results to return (default: 10)"
+    )
+)]
+pub async fn search_library_tool(query: String, limit: Option<u32>) -> Result<ToolResponseContent> {
+    let hits = Library::open().search(&query, limit.unwrap_or(10) as usize)?;
+    Ok(tool_text_content!(
+        serde_json::to_string(&hits).map_err(McpArxivError::ResponseSerializeError)?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_search_library_tool() {
+        let content = search_library_tool("attention".to_string(), Some(5))
+            .await
+            .unwrap();
+        assert!(format!("{:?}", content).contains('['));
+        assert!(search_library_tool("the".to_string(), None).await.is_err());
+    }
+}
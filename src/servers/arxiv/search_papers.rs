@@ -1,12 +1,12 @@
+use super::atom;
 use super::errors::McpArxivError;
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
-use reqwest::Client;
 
 #[tool(
     name = "SearchPapers",
-    description = "Search for papers on ArXiv using various criteria.",
+    description = "Search for papers on ArXiv using various criteria. Returns JSON with totalResults, startIndex and the matching papers.",
     params(
         query = "The search query string (e.g. 'quantum computing', 'au:\"Einstein, Albert\"', 'cat:cs.CV')",
         start = "Starting index for results (default: 0)",
@@ -22,9 +22,6 @@ pub async fn search_papers_tool(
     sort_by: Option<String>,
     sort_order: Option<String>,
 ) -> Result<ToolResponseContent> {
-    let client = Client::new();
-    let base_url = "http://export.arxiv.org/api/query";
-
     // Build query parameters
     let mut params = vec![
         ("search_query", query),
@@ -39,24 +36,11 @@ pub async fn search_papers_tool(
         params.push(("sortOrder", order));
     }
 
-    // Send the request
-    let res = client
-        .get(base_url)
-        .query(&params)
-        .send()
-        .await
-        .map_err(McpArxivError::HttpError)?;
-
-    // Check if the response is successful
-    if !res.status().is_success() {
-        let error_text = res.text().await.map_err(McpArxivError::HttpError)?;
-        return Err(McpArxivError::ApiError(format!("ArXiv API error: {}", error_text)).into());
-    }
-
-    // Parse the response
-    let result = res.text().await.map_err(McpArxivError::HttpError)?;
+    let feed = atom::query(&params).await?;
 
-    Ok(tool_text_content!(result))
+    Ok(tool_text_content!(
+        serde_json::to_string(&feed).map_err(McpArxivError::ResponseSerializeError)?
+    ))
 }
 
 #[cfg(test)]
@@ -76,7 +60,7 @@ mod tests {
         {
             Ok(content) => {
                 println!("Search result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
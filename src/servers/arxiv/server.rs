@@ -1,6 +1,7 @@
 use super::*;
+use crate::output::ContinueOutputTool;
+use crate::server::Server;
 use mcp_core::protocol::Protocol;
-use mcp_core::server::Server;
 use mcp_core::types::ServerCapabilities;
 use serde_json::json;
 
@@ -12,8 +13,33 @@ pub fn protocol() -> Protocol {
         })
         .register_tool(GetPaperByIdTool::tool(), GetPaperByIdTool::call())
         .register_tool(SearchPapersTool::tool(), SearchPapersTool::call())
+        .register_tool(AdvancedSearchTool::tool(), AdvancedSearchTool::call())
         .register_tool(ListRecordsTool::tool(), ListRecordsTool::call())
+        .register_tool(ListIdentifiersTool::tool(), ListIdentifiersTool::call())
+        .register_tool(GetRecordTool::tool(), GetRecordTool::call())
+        .register_tool(IdentifyTool::tool(), IdentifyTool::call())
+        .register_tool(ListSetsTool::tool(), ListSetsTool::call())
+        .register_tool(
+            ListMetadataFormatsTool::tool(),
+            ListMetadataFormatsTool::call(),
+        )
         .register_tool(SearchByAuthorTool::tool(), SearchByAuthorTool::call())
+        .register_tool(AuthorProfileTool::tool(), AuthorProfileTool::call())
         .register_tool(ExtractPaperTextTool::tool(), ExtractPaperTextTool::call())
+        .register_tool(ExtractFiguresTool::tool(), ExtractFiguresTool::call())
+        .register_tool(ExtractReferencesTool::tool(), ExtractReferencesTool::call())
+        .register_tool(ChunkPaperTool::tool(), ChunkPaperTool::call())
+        .register_tool(GetPaperSourceTool::tool(), GetPaperSourceTool::call())
+        .register_tool(FindRelatedPapersTool::tool(), FindRelatedPapersTool::call())
+        .register_tool(GetPaperVersionsTool::tool(), GetPaperVersionsTool::call())
+        .register_tool(DiffPaperVersionsTool::tool(), DiffPaperVersionsTool::call())
+        .register_tool(ExportCitationTool::tool(), ExportCitationTool::call())
+        .register_tool(SavePaperTool::tool(), SavePaperTool::call())
+        .register_tool(SearchLibraryTool::tool(), SearchLibraryTool::call())
+        .register_tool(ListLibraryTool::tool(), ListLibraryTool::call())
+        .register_tool(RemovePaperTool::tool(), RemovePaperTool::call())
+        .register_tool(GetNewSubmissionsTool::tool(), GetNewSubmissionsTool::call())
+        .register_resource(watcher::resource(), watcher::reader())
+        .register_tool(ContinueOutputTool::tool(), ContinueOutputTool::call())
         .build()
 }
@@ -0,0 +1,237 @@
+//! TF-IDF similarity between papers on their titles and abstracts.
+//!
+//! Scores only depend on the papers given: document frequencies are counted
+//! over the source paper and its candidates, and ties are broken by arXiv ID,
+//! so the same candidates always rank the same way.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::atom::Paper;
+use super::library::tokenize;
+
+/// Title words count this many times over abstract words
+const TITLE_WEIGHT: usize = 2;
+/// Shared terms listed per result
+const SHARED_TERMS: usize = 8;
+
+/// Words too common in abstracts to tell papers apart
+const COMMON_WORDS: &[&str] = &[
+    "about", "also", "approach", "based", "been", "between", "both", "but", "can", "each",
+    "existing", "first", "has", "have", "how", "however", "into", "its", "method", "more", "need",
+    "new", "not", "novel", "one", "only", "other", "our", "over", "paper", "present", "propose",
+    "proposed", "result", "show", "such", "than", "their", "these", "they", "two", "use", "used",
+    "using", "via", "well", "when", "which", "while", "work",
+];
+
+/// A candidate's similarity to the source paper.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Similarity {
+    /// Position of the candidate in the slice given to [`rank`]
+    pub index: usize,
+    /// Cosine similarity, from 0 to 1
+    pub score: f64,
+    /// Terms of both papers, the most significant first
+    pub shared_terms: Vec<String>,
+}
+
+/// Terms of a paper's title and abstract, title terms repeated.
+pub fn terms(paper: &Paper) -> Vec<String> {
+    let title = normalize(&paper.title);
+    let mut terms: Vec<String> = (0..TITLE_WEIGHT).flat_map(|_| title.clone()).collect();
+    terms.extend(normalize(&paper.summary));
+    terms
+}
+
+/// The `limit` most frequent terms of a paper, longer terms first on ties.
+pub fn key_terms(paper: &Paper, limit: usize) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for term in terms(paper).into_iter().filter(|t| t.chars().count() >= 4) {
+        *counts.entry(term).or_default() += 1;
+    }
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| {
+        b.1.cmp(&a.1)
+            .then_with(|| b.0.len().cmp(&a.0.len()))
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    counts
+        .into_iter()
+        .take(limit)
+        .map(|(term, _)| term)
+        .collect()
+}
+
+/// Ranks `candidates` by TF-IDF cosine similarity to `source`, most similar
+/// first. Candidates sharing no terms with the source are left out.
+pub fn rank(source: &Paper, candidates: &[Paper]) -> Vec<Similarity> {
+    let documents: Vec<BTreeMap<String, usize>> = std::iter::once(source)
+        .chain(candidates)
+        .map(|paper| {
+            let mut counts = BTreeMap::new();
+            for term in terms(paper) {
+                *counts.entry(term).or_default() += 1;
+            }
+            counts
+        })
+        .collect();
+
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for document in &documents {
+        for term in document.keys() {
+            *document_frequency.entry(term).or_default() += 1;
+        }
+    }
+    let count = documents.len() as f64;
+    // Sublinear term frequency with smoothed inverse document frequency
+    let vector = |document: &BTreeMap<String, usize>| -> BTreeMap<String, f64> {
+        let weights: BTreeMap<String, f64> = document
+            .iter()
+            .map(|(term, &tf)| {
+                let df = document_frequency[term.as_str()] as f64;
+                let idf = ((count + 1.0) / (df + 1.0)).ln() + 1.0;
+                (term.clone(), (1.0 + (tf as f64).ln()) * idf)
+            })
+            .collect();
+        let norm = weights.values().map(|w| w * w).sum::<f64>().sqrt();
+        weights
+            .into_iter()
+            .map(|(term, w)| (term, if norm > 0.0 { w / norm } else { 0.0 }))
+            .collect()
+    };
+
+    let source = vector(&documents[0]);
+    let mut ranked: Vec<Similarity> = documents[1..]
+        .iter()
+        .enumerate()
+        .filter_map(|(index, document)| {
+            let candidate = vector(document);
+            let mut shared: Vec<(&String, f64)> = source
+                .iter()
+                .filter_map(|(term, w)| Some((term, w * candidate.get(term)?)))
+                .collect();
+            if shared.is_empty() {
+                return None;
+            }
+            let score: f64 = shared.iter().map(|(_, w)| w).sum();
+            shared.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            Some(Similarity {
+                index,
+                score: (score.min(1.0) * 10_000.0).round() / 10_000.0,
+                shared_terms: shared
+                    .into_iter()
+                    .take(SHARED_TERMS)
+                    .map(|(term, _)| term.clone())
+                    .collect(),
+            })
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| candidates[a.index].id.cmp(&candidates[b.index].id))
+    });
+    ranked
+}
+
+/// Words of `text` without stopwords, common words and numbers, with plurals
+/// reduced to their singular.
+fn normalize(text: &str) -> Vec<String> {
+    tokenize(text)
+        .into_iter()
+        .filter(|w| !w.chars().all(|c| c.is_ascii_digit()))
+        .map(singular)
+        .filter(|w| !COMMON_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+fn singular(word: String) -> String {
+    if word.len() > 4 && word.ends_with("ies") {
+        format!("{}y", &word[..word.len() - 3])
+    } else if word.len() > 3
+        && word.ends_with('s')
+        && !["ss", "us", "is"].iter().any(|end| word.ends_with(end))
+    {
+        word[..word.len() - 1].to_string()
+    } else {
+        word
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::servers::arxiv::atom::parse_feed;
+
+    const FEED: &str = r#"<feed xmlns="http://www.w3.org/2005/Atom" xmlns:arxiv="http://arxiv.org/schemas/atom">
+  <entry>
+    <id>http://arxiv.org/abs/1706.03762v7</id>
+    <title>Attention Is All You Need</title>
+    <summary>We propose the Transformer, a network architecture based solely on attention
+      mechanisms, dispensing with recurrence and convolutions. Experiments on machine
+      translation tasks show these models to be superior in quality.</summary>
+    <arxiv:primary_category term="cs.CL"/>
+  </entry>
+  <entry>
+    <id>http://arxiv.org/abs/1409.0473v7</id>
+    <title>Neural Machine Translation by Jointly Learning to Align and Translate</title>
+    <summary>Neural machine translation uses an encoder and a decoder. We extend it with
+      an attention mechanism that searches for relevant parts of the source sentence.</summary>
+    <arxiv:primary_category term="cs.CL"/>
+  </entry>
+  <entry>
+    <id>http://arxiv.org/abs/1810.04805v2</id>
+    <title>BERT: Pre-training of Deep Bidirectional Transformers</title>
+    <summary>We introduce BERT, a language representation model built from Transformer
+      encoders with attention, pre-trained on unlabeled text.</summary>
+    <arxiv:primary_category term="cs.CL"/>
+  </entry>
+  <entry>
+    <id>http://arxiv.org/abs/1512.03385v1</id>
+    <title>Deep Residual Learning for Image Recognition</title>
+    <summary>We present a residual learning framework to ease the training of very deep
+      convolutional networks for image recognition.</summary>
+    <arxiv:primary_category term="cs.CV"/>
+  </entry>
+  <entry>
+    <id>http://arxiv.org/abs/astro-ph/0005074v1</id>
+    <title>Galaxy Rotation Curves</title>
+    <summary>Rotation curves of spiral galaxies constrain dark matter halos.</summary>
+    <arxiv:primary_category term="astro-ph"/>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_key_terms() {
+        let papers = parse_feed(FEED).unwrap().papers;
+        assert_eq!(singular("networks".to_string()), "network");
+        assert_eq!(singular("queries".to_string()), "query");
+        assert_eq!(singular("analysis".to_string()), "analysis");
+        assert_eq!(
+            key_terms(&papers[0], 4),
+            ["attention", "architecture", "convolution", "transformer"]
+        );
+    }
+
+    #[test]
+    fn test_rank() {
+        let papers = parse_feed(FEED).unwrap().papers;
+        let ranked = rank(&papers[0], &papers[1..]);
+        let ids: Vec<&str> = ranked
+            .iter()
+            .map(|s| papers[1 + s.index].id.as_str())
+            .collect();
+        assert_eq!(ids, ["1409.0473", "1810.04805", "1512.03385"]);
+        assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+        assert!(ranked[0].shared_terms.contains(&"translation".to_string()));
+        assert!(ranked[1].shared_terms.contains(&"transformer".to_string()));
+        // Scores do not depend on the order of the candidates
+        let mut reversed = papers[1..].to_vec();
+        reversed.reverse();
+        let scores: Vec<f64> = rank(&papers[0], &reversed)
+            .iter()
+            .map(|s| s.score)
+            .collect();
+        assert_eq!(scores, ranked.iter().map(|s| s.score).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,713 @@
+use serde::{Deserialize, Serialize};
+
+use super::errors::McpArxivError;
+
+/// Unnumbered headings recognised on their own line
+const NAMED_HEADINGS: &[&str] = &[
+    "abstract",
+    "introduction",
+    "related work",
+    "background",
+    "preliminaries",
+    "method",
+    "methods",
+    "methodology",
+    "approach",
+    "experiments",
+    "experimental setup",
+    "evaluation",
+    "results",
+    "discussion",
+    "limitations",
+    "conclusion",
+    "conclusions",
+    "acknowledgments",
+    "acknowledgements",
+    "acknowledgment",
+    "acknowledgement",
+    "references",
+    "bibliography",
+    "appendix",
+    "appendices",
+    "supplementary material",
+];
+
+/// Headings longer than this are taken to be ordinary sentences
+const MAX_HEADING_WORDS: usize = 12;
+const MAX_HEADING_CHARS: usize = 90;
+
+/// Marks a page change inside a section's text until it is tidied
+const PAGE_BREAK: char = '\u{E000}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SectionKind {
+    /// Everything before the first heading: title, authors, affiliations
+    Front,
+    Abstract,
+    Body,
+    References,
+    Appendix,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Section {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<String>,
+    pub heading: String,
+    pub kind: SectionKind,
+    pub page_start: u32,
+    pub page_end: u32,
+    pub text: String,
+    /// Byte offsets in `text` where a page after `page_start` begins
+    #[serde(skip)]
+    pub page_breaks: Vec<(usize, u32)>,
+}
+
+/// A figure or table caption and the page it appears on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Caption {
+    /// `Figure 3` or `Table 1`
+    pub label: String,
+    pub text: String,
+    pub page: u32,
+}
+
+/// Text of a paper split along its headings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PaperText {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(rename = "abstract", skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub page_count: u32,
+    pub sections: Vec<Section>,
+    pub captions: Vec<Caption>,
+}
+
+struct Heading {
+    number: Option<String>,
+    title: String,
+    /// Text following an inline heading such as `Abstract—We study ...`
+    rest: Option<String>,
+}
+
+#[derive(Default)]
+struct Outline {
+    /// Last top-level section number, headings must continue the sequence
+    last_top: u32,
+    /// Last appendix letter, `A` sections only count after the references
+    last_letter: Option<char>,
+    in_back_matter: bool,
+}
+
+impl PaperText {
+    /// Splits extracted page texts into sections. `pages` holds 1-based page
+    /// numbers with their text, in order.
+    pub fn parse(pages: &[(u32, String)], page_count: u32) -> PaperText {
+        let mut outline = Outline::default();
+        let first_page = pages.first().map(|(p, _)| *p).unwrap_or(1);
+        let mut current = Section {
+            number: None,
+            heading: "Front matter".to_string(),
+            kind: SectionKind::Front,
+            page_start: first_page,
+            page_end: first_page,
+            text: String::new(),
+            page_breaks: Vec::new(),
+        };
+        let mut sections = Vec::new();
+        let mut captions: Vec<Caption> = Vec::new();
+        let mut in_caption = false;
+
+        for (page, text) in pages {
+            for line in text.lines() {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    in_caption = false;
+                    current.text.push('\n');
+                    continue;
+                }
+
+                if let Some(heading) = outline.heading(trimmed) {
+                    in_caption = false;
+                    let kind = outline.kind(&heading);
+                    let next = Section {
+                        number: heading.number,
+                        heading: heading.title,
+                        kind,
+                        page_start: *page,
+                        page_end: *page,
+                        text: heading.rest.map(|r| r + "\n").unwrap_or_default(),
+                        page_breaks: Vec::new(),
+                    };
+                    let previous = std::mem::replace(&mut current, next);
+                    if previous.kind != SectionKind::Front || !previous.text.trim().is_empty() {
+                        sections.push(previous);
+                    }
+                    continue;
+                }
+
+                if let Some((label, rest)) = caption_start(trimmed) {
+                    captions.push(Caption {
+                        label,
+                        text: rest,
+                        page: *page,
+                    });
+                    in_caption = true;
+                } else if in_caption
+                    && let Some(caption) = captions.last_mut()
+                    && !caption.text.ends_with('.')
+                {
+                    // Captions wrap mid-sentence, a finished sentence ends them
+                    caption.text.push(' ');
+                    caption.text.push_str(trimmed);
+                }
+
+                if current.page_end != *page {
+                    current.text.push(PAGE_BREAK);
+                    current.text.push_str(&format!("{}\n", page));
+                }
+                current.page_end = *page;
+                current.text.push_str(trimmed);
+                current.text.push('\n');
+            }
+        }
+        sections.push(current);
+
+        for section in &mut sections {
+            (section.text, section.page_breaks) = tidy(&section.text);
+        }
+        for caption in &mut captions {
+            caption.text = caption
+                .text
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+
+        let title = sections
+            .first()
+            .filter(|s| s.kind == SectionKind::Front && s.page_start == 1)
+            .and_then(|s| front_title(&s.text));
+        let summary = sections
+            .iter()
+            .find(|s| s.kind == SectionKind::Abstract)
+            .map(|s| s.text.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|s| !s.is_empty());
+
+        PaperText {
+            title,
+            summary,
+            page_count,
+            sections,
+            captions,
+        }
+    }
+
+    /// Keeps the sections matching any of `wanted`, by heading text, section
+    /// number or kind (`abstract`, `references`, `appendix`). Subsections of a
+    /// matched numbered section are kept too.
+    pub fn select(&mut self, wanted: &[String]) -> Result<(), McpArxivError> {
+        let wanted: Vec<String> = wanted
+            .iter()
+            .map(|w| w.trim().trim_end_matches('.').to_lowercase())
+            .filter(|w| !w.is_empty())
+            .collect();
+        if wanted.is_empty() {
+            return Ok(());
+        }
+
+        let matches = |section: &Section, w: &str| {
+            let kind = match w {
+                "abstract" => Some(SectionKind::Abstract),
+                "references" | "bibliography" => Some(SectionKind::References),
+                "appendix" | "appendices" => Some(SectionKind::Appendix),
+                _ => None,
+            };
+            kind == Some(section.kind)
+                || section
+                    .number
+                    .as_deref()
+                    .is_some_and(|n| n.eq_ignore_ascii_case(w))
+                || section.heading.to_lowercase().contains(w)
+        };
+        let matched: Vec<String> = self
+            .sections
+            .iter()
+            .filter(|s| wanted.iter().any(|w| matches(s, w)))
+            .filter_map(|s| s.number.clone())
+            .collect();
+
+        let sections: Vec<Section> = self
+            .sections
+            .iter()
+            .filter(|s| {
+                wanted.iter().any(|w| matches(s, w))
+                    || s.number
+                        .as_deref()
+                        .is_some_and(|n| matched.iter().any(|m| n.starts_with(&format!("{}.", m))))
+            })
+            .cloned()
+            .collect();
+
+        if sections.is_empty() {
+            let available: Vec<String> = self
+                .sections
+                .iter()
+                .map(|s| match &s.number {
+                    Some(n) => format!("{} {}", n, s.heading),
+                    None => s.heading.clone(),
+                })
+                .collect();
+            return Err(McpArxivError::InvalidArgument(format!(
+                "no section matches {:?}, available sections: {}",
+                wanted,
+                available.join("; ")
+            )));
+        }
+
+        self.captions.retain(|c| {
+            sections
+                .iter()
+                .any(|s| (s.page_start..=s.page_end).contains(&c.page))
+        });
+        self.sections = sections;
+        Ok(())
+    }
+}
+
+impl Outline {
+    fn heading(&mut self, line: &str) -> Option<Heading> {
+        if let Some(heading) = inline_abstract(line) {
+            return Some(heading);
+        }
+        if line.chars().count() > MAX_HEADING_CHARS
+            || line.split_whitespace().count() > MAX_HEADING_WORDS + 1
+        {
+            return None;
+        }
+
+        let (first, rest) = match line.split_once(char::is_whitespace) {
+            Some((first, rest)) => (first, rest.trim()),
+            None => (line, ""),
+        };
+
+        if is_named(line) {
+            if matches!(normalized(line).as_str(), "references" | "bibliography") {
+                self.in_back_matter = true;
+            }
+            return Some(Heading {
+                number: None,
+                title: line.trim_end_matches([':', '.']).to_string(),
+                rest: None,
+            });
+        }
+
+        // `Appendix A: Proofs` or `Appendix B`
+        if first.eq_ignore_ascii_case("appendix") {
+            let (letter, title) = match rest.split_once(char::is_whitespace) {
+                Some((letter, title)) => (letter, title.trim()),
+                None => (rest, ""),
+            };
+            let letter = letter.trim_end_matches([':', '.']);
+            if letter.len() == 1 && letter.chars().all(|c| c.is_ascii_uppercase()) {
+                self.in_back_matter = true;
+                self.last_letter = letter.chars().next();
+                let title = title.trim_start_matches([':', '.', ' ']);
+                return Some(Heading {
+                    number: Some(letter.to_string()),
+                    title: if title.is_empty() {
+                        format!("Appendix {}", letter)
+                    } else {
+                        title.to_string()
+                    },
+                    rest: None,
+                });
+            }
+            return None;
+        }
+
+        if !heading_title(rest) {
+            return None;
+        }
+        let number = first.trim_end_matches('.');
+        let parts: Vec<&str> = number.split('.').collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            return None;
+        }
+
+        let numbered = |number: &str| {
+            Some(Heading {
+                number: Some(number.to_string()),
+                title: rest.trim_end_matches(':').to_string(),
+                rest: None,
+            })
+        };
+
+        // `3 Method`, `3.2 Training`, or `IV. EXPERIMENTS`
+        let top = parts[0].parse::<u32>().ok().or_else(|| {
+            if parts.len() == 1 && first.ends_with('.') {
+                roman(parts[0])
+            } else {
+                None
+            }
+        });
+        if let Some(top) = top
+            && parts[1..].iter().all(|p| p.parse::<u32>().is_ok())
+        {
+            let expected = if parts.len() == 1 {
+                self.last_top + 1
+            } else {
+                self.last_top
+            };
+            if top == expected && !self.in_back_matter {
+                self.last_top = top;
+                return numbered(number);
+            }
+            return None;
+        }
+
+        // `A Proofs`, `B.2 Hyperparameters` after the references
+        let mut letter = parts[0].chars();
+        if self.in_back_matter
+            && let (Some(c), None) = (letter.next(), letter.next())
+            && c.is_ascii_uppercase()
+            && parts[1..].iter().all(|p| p.parse::<u32>().is_ok())
+        {
+            let expected = match (parts.len(), self.last_letter) {
+                (1, Some(last)) => char::from_u32(last as u32 + 1),
+                (1, None) => Some('A'),
+                (_, last) => last,
+            };
+            if Some(c) == expected {
+                self.last_letter = Some(c);
+                return numbered(number);
+            }
+        }
+        None
+    }
+
+    fn kind(&self, heading: &Heading) -> SectionKind {
+        match normalized(&heading.title).as_str() {
+            "abstract" => SectionKind::Abstract,
+            "references" | "bibliography" => SectionKind::References,
+            "appendix" | "appendices" | "supplementary material" => SectionKind::Appendix,
+            _ if self.in_back_matter && !is_acknowledgment(&heading.title) => SectionKind::Appendix,
+            _ => SectionKind::Body,
+        }
+    }
+}
+
+fn normalized(heading: &str) -> String {
+    heading
+        .trim_end_matches([':', '.'])
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn is_named(line: &str) -> bool {
+    NAMED_HEADINGS.contains(&normalized(line).as_str())
+}
+
+fn is_acknowledgment(heading: &str) -> bool {
+    normalized(heading).starts_with("acknowledg")
+}
+
+/// A heading title starts with a capital letter and does not read like a
+/// sentence or an equation.
+fn heading_title(title: &str) -> bool {
+    title.chars().next().is_some_and(char::is_uppercase)
+        && !title.ends_with(['.', ',', ';'])
+        && title.chars().filter(|c| c.is_alphabetic()).count() * 2 > title.chars().count()
+}
+
+fn roman(numeral: &str) -> Option<u32> {
+    const NUMERALS: &[&str] = &[
+        "I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X", "XI", "XII", "XIII", "XIV",
+        "XV",
+    ];
+    NUMERALS
+        .iter()
+        .position(|n| *n == numeral)
+        .map(|i| i as u32 + 1)
+}
+
+/// `Abstract—We study`, `Abstract. We study` or `Abstract: We study`
+fn inline_abstract(line: &str) -> Option<Heading> {
+    let rest = line
+        .strip_prefix("Abstract")
+        .or_else(|| line.strip_prefix("ABSTRACT"))?;
+    let rest = rest
+        .strip_prefix(['—', '–', ':', '.'])
+        .or_else(|| rest.strip_prefix(" —"))
+        .or_else(|| rest.strip_prefix(" -"))?
+        .trim();
+    (!rest.is_empty()).then(|| Heading {
+        number: None,
+        title: "Abstract".to_string(),
+        rest: Some(rest.to_string()),
+    })
+}
+
+/// `Figure 2: Caption`, `Fig. 2. Caption` or `Table 1: Caption`
+fn caption_start(line: &str) -> Option<(String, String)> {
+    let (kind, rest) = [
+        ("Figure", "Figure"),
+        ("Fig.", "Figure"),
+        ("FIGURE", "Figure"),
+        ("Table", "Table"),
+        ("TABLE", "Table"),
+    ]
+    .iter()
+    .find_map(|(prefix, kind)| Some((*kind, line.strip_prefix(prefix)?)))?;
+
+    let rest = rest.trim_start();
+    let end = rest.find([':', '.', '|'])?;
+    let number = &rest[..end];
+    if number.is_empty()
+        || number.len() > 4
+        || !number.chars().all(|c| c.is_ascii_alphanumeric())
+        || !number.chars().any(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    Some((
+        format!("{} {}", kind, number),
+        rest[end + 1..].trim().to_string(),
+    ))
+}
+
+/// The first lines of the front matter up to a blank line, skipping the
+/// arXiv identifier stamp.
+fn front_title(front: &str) -> Option<String> {
+    let title: Vec<&str> = front
+        .lines()
+        .map(str::trim)
+        .skip_while(|l| l.is_empty() || l.starts_with("arXiv:"))
+        .take_while(|l| !l.is_empty())
+        .take(3)
+        .collect();
+    let title = title.join(" ");
+    (!title.is_empty() && title.chars().count() <= 250).then_some(title)
+}
+
+/// Trims lines and collapses runs of blank lines to one paragraph break.
+/// Page break markers are removed and returned as offsets into the result.
+fn tidy(text: &str) -> (String, Vec<(usize, u32)>) {
+    let mut out = String::new();
+    let mut breaks = Vec::new();
+    let mut pending_page = None;
+    let mut blank = false;
+    for line in text.lines().map(str::trim) {
+        if let Some(page) = line.strip_prefix(PAGE_BREAK) {
+            pending_page = page.parse().ok();
+            continue;
+        }
+        if line.is_empty() {
+            blank = !out.is_empty();
+            continue;
+        }
+        if blank {
+            out.push_str("\n\n");
+        } else if !out.is_empty() {
+            out.push('\n');
+        }
+        if let Some(page) = pending_page.take() {
+            breaks.push((out.len(), page));
+        }
+        out.push_str(line);
+        blank = false;
+    }
+    (out, breaks)
+}
+
+impl Section {
+    /// The page the byte at `offset` of `text` is on.
+    pub fn page_at(&self, offset: usize) -> u32 {
+        self.page_breaks
+            .iter()
+            .take_while(|(start, _)| *start <= offset)
+            .last()
+            .map_or(self.page_start, |(_, page)| *page)
+    }
+}
+
+/// Parses `3`, `3-5`, `3-` or `-5` into an inclusive 1-based page range.
+pub fn parse_page_range(range: &str, page_count: u32) -> Result<(u32, u32), McpArxivError> {
+    let invalid = || {
+        McpArxivError::InvalidArgument(format!(
+            "invalid page range `{}` for a {} page document, expected e.g. `3` or `3-5`",
+            range, page_count
+        ))
+    };
+    let page = |p: &str, default: u32| match p.trim() {
+        "" => Ok(default),
+        p => p.parse::<u32>().map_err(|_| invalid()),
+    };
+
+    let (start, end) = match range.split_once(['-', '–']) {
+        Some((start, end)) => (page(start, 1)?, page(end, page_count)?),
+        None => {
+            let page = page(range, 0)?;
+            (page, page)
+        }
+    };
+    if start == 0 || start > end || end > page_count {
+        return Err(invalid());
+    }
+    Ok((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE_ONE: &str = "arXiv:2401.00001v1 [cs.LG] 1 Jan 2024
+Sparse Attention for
+Long Documents
+
+Ada Lovelace
+Analytical Engine Society
+
+Abstract
+We make attention sparse.
+It is fast.
+
+1 Introduction
+Transformers are popular. We cite 2 papers
+in this line of work.
+
+2 Method
+2.1 Sparse Kernels
+We keep the top k scores.
+Figure 1: Attention pattern of the
+sparse kernel.
+";
+
+    const PAGE_TWO: &str = "3 Experiments
+Table 1: Results on arXiv.
+As Figure 1 shows, it works.
+
+Acknowledgments
+Thanks.
+
+References
+[1] A. Turing. Computing machinery. 1950.
+
+A Proofs
+Proof of everything.
+";
+
+    fn paper() -> PaperText {
+        PaperText::parse(&[(1, PAGE_ONE.to_string()), (2, PAGE_TWO.to_string())], 2)
+    }
+
+    #[test]
+    fn test_parse_structure() {
+        let paper = paper();
+        assert_eq!(
+            paper.title.as_deref(),
+            Some("Sparse Attention for Long Documents")
+        );
+        assert_eq!(
+            paper.summary.as_deref(),
+            Some("We make attention sparse. It is fast.")
+        );
+
+        let outline: Vec<(Option<&str>, &str, SectionKind)> = paper
+            .sections
+            .iter()
+            .map(|s| (s.number.as_deref(), s.heading.as_str(), s.kind))
+            .collect();
+        assert_eq!(
+            outline,
+            [
+                (None, "Front matter", SectionKind::Front),
+                (None, "Abstract", SectionKind::Abstract),
+                (Some("1"), "Introduction", SectionKind::Body),
+                (Some("2"), "Method", SectionKind::Body),
+                (Some("2.1"), "Sparse Kernels", SectionKind::Body),
+                (Some("3"), "Experiments", SectionKind::Body),
+                (None, "Acknowledgments", SectionKind::Body),
+                (None, "References", SectionKind::References),
+                (Some("A"), "Proofs", SectionKind::Appendix),
+            ]
+        );
+        assert!(paper.sections[2].text.contains("We cite 2 papers"));
+        assert!(paper.sections.iter().all(|s| !s.text.contains(PAGE_BREAK)));
+
+        let captions: Vec<(&str, &str, u32)> = paper
+            .captions
+            .iter()
+            .map(|c| (c.label.as_str(), c.text.as_str(), c.page))
+            .collect();
+        assert_eq!(
+            captions,
+            [
+                ("Figure 1", "Attention pattern of the sparse kernel.", 1),
+                ("Table 1", "Results on arXiv.", 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_sections() {
+        let mut paper = paper();
+        paper.select(&["method".to_string()]).unwrap();
+        let headings: Vec<&str> = paper.sections.iter().map(|s| s.heading.as_str()).collect();
+        assert_eq!(headings, ["Method", "Sparse Kernels"]);
+        assert_eq!(paper.captions.len(), 1);
+
+        let mut paper = self::paper();
+        paper
+            .select(&["3".to_string(), "appendix".to_string()])
+            .unwrap();
+        let headings: Vec<&str> = paper.sections.iter().map(|s| s.heading.as_str()).collect();
+        assert_eq!(headings, ["Experiments", "Proofs"]);
+
+        assert!(self::paper().select(&["Results".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_inline_abstract_and_roman_headings() {
+        let text = "Abstract—We study things.\nI. INTRODUCTION\nText.\nII. RELATED WORK\nMore.";
+        let paper = PaperText::parse(&[(1, text.to_string())], 1);
+        assert_eq!(paper.summary.as_deref(), Some("We study things."));
+        assert_eq!(paper.sections[2].heading, "RELATED WORK");
+        assert_eq!(paper.sections[2].number.as_deref(), Some("II"));
+    }
+
+    #[test]
+    fn test_page_at() {
+        let paper = PaperText::parse(
+            &[
+                (3, "1 Introduction\nOn page three.\n".to_string()),
+                (4, "\nOn page four.\n".to_string()),
+            ],
+            4,
+        );
+        let section = &paper.sections[0];
+        assert_eq!(section.text, "On page three.\n\nOn page four.");
+        assert_eq!((section.page_start, section.page_end), (3, 4));
+        assert_eq!(section.page_at(0), 3);
+        assert_eq!(section.page_at(section.text.find("four").unwrap()), 4);
+    }
+
+    #[test]
+    fn test_parse_page_range() {
+        assert_eq!(parse_page_range("3-5", 10).unwrap(), (3, 5));
+        assert_eq!(parse_page_range("4", 10).unwrap(), (4, 4));
+        assert_eq!(parse_page_range("8-", 10).unwrap(), (8, 10));
+        assert_eq!(parse_page_range("-2", 10).unwrap(), (1, 2));
+        assert!(parse_page_range("0", 10).is_err());
+        assert!(parse_page_range("5-3", 10).is_err());
+        assert!(parse_page_range("9-11", 10).is_err());
+        assert!(parse_page_range("x", 10).is_err());
+    }
+}
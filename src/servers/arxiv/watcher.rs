@@ -0,0 +1,484 @@
+//! Polls arXiv for new submissions in watched categories.
+//!
+//! Settings are read before every poll, so they follow config reloads:
+//!
+//! - `ARXIV_WATCH_CATEGORIES`: categories to watch, e.g. `cs.CL,cs.LG`. The
+//!   watcher is idle while this is empty.
+//! - `ARXIV_WATCH_KEYWORDS` and `ARXIV_WATCH_AUTHORS`: a paper matches when its
+//!   title or abstract contains any keyword, or any author name contains one of
+//!   the authors. Without either, every new paper matches.
+//! - `ARXIV_WATCH_INTERVAL`: minutes between polls (default: 60).
+//! - `ARXIV_WATCH_STATE`: the checkpoint file (default: `arxiv-watch.json`).
+//!
+//! The checkpoint keeps the IDs already seen, so a restart does not report the
+//! same papers again, and the matches `GetNewSubmissions` has not had
+//! acknowledged yet.
+
+use lazy_static::lazy_static;
+use mcp_core::types::Resource;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+use super::atom::{Paper, query};
+use super::errors::McpArxivError;
+use super::taxonomy::{canonical_category, similar_categories};
+use crate::server::ResourceReader;
+
+pub const RESOURCE_URI: &str = "arxiv://watch/new-submissions";
+const DEFAULT_STATE_FILE: &str = "arxiv-watch.json";
+const DEFAULT_INTERVAL_MINUTES: u64 = 60;
+/// How often an idle watcher checks whether categories were configured
+const IDLE_INTERVAL: Duration = Duration::from_secs(60);
+/// Results per request, a busy day in cs.LG has a few hundred submissions
+const PAGE_SIZE: u32 = 200;
+const MAX_PAGES: u32 = 5;
+/// Seen IDs kept in the checkpoint, the oldest are dropped first
+const MAX_SEEN: usize = 20_000;
+/// Matches kept until they are acknowledged, the oldest are dropped first
+const MAX_PENDING: usize = 1_000;
+
+lazy_static! {
+    /// Serializes polls and reads of the checkpoint
+    static ref CHECKPOINT_LOCK: Mutex<()> = Mutex::new(());
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchConfig {
+    pub categories: Vec<String>,
+    pub keywords: Vec<String>,
+    pub authors: Vec<String>,
+    pub interval: Duration,
+}
+
+/// A new paper that passed the filters, with the filters it matched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Match {
+    #[serde(flatten)]
+    pub paper: Paper,
+    /// e.g. `keyword:retrieval` or `author:Lovelace`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matched: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Checkpoint {
+    /// Unix seconds
+    pub last_poll: Option<u64>,
+    pub seen: VecDeque<String>,
+    pub pending: Vec<Match>,
+}
+
+impl WatchConfig {
+    /// The current settings, `None` when no categories are watched.
+    pub fn from_env() -> Result<Option<WatchConfig>, McpArxivError> {
+        let list = |name: &str| -> Vec<String> {
+            crate::config::var(name)
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        };
+
+        let categories = list("ARXIV_WATCH_CATEGORIES")
+            .iter()
+            .map(|c| {
+                canonical_category(c)
+                    .map(str::to_string)
+                    .ok_or_else(|| unknown_category(c))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if categories.is_empty() {
+            return Ok(None);
+        }
+
+        let minutes = match crate::config::var("ARXIV_WATCH_INTERVAL") {
+            Some(v) if !v.trim().is_empty() => v
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .filter(|m| *m > 0)
+                .ok_or_else(|| {
+                    McpArxivError::InvalidArgument(format!(
+                        "ARXIV_WATCH_INTERVAL must be a positive number of minutes, got `{}`",
+                        v
+                    ))
+                })?,
+            _ => DEFAULT_INTERVAL_MINUTES,
+        };
+
+        Ok(Some(WatchConfig {
+            categories,
+            keywords: list("ARXIV_WATCH_KEYWORDS"),
+            authors: list("ARXIV_WATCH_AUTHORS"),
+            interval: Duration::from_secs(minutes * 60),
+        }))
+    }
+
+    /// The filters `paper` matched, `None` if it does not pass them.
+    pub fn matches(&self, paper: &Paper) -> Option<Vec<String>> {
+        if self.keywords.is_empty() && self.authors.is_empty() {
+            return Some(Vec::new());
+        }
+
+        let text = format!("{} {}", paper.title, paper.summary)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        let mut matched: Vec<String> = self
+            .keywords
+            .iter()
+            .filter(|k| text.contains(&k.to_lowercase()))
+            .map(|k| format!("keyword:{}", k))
+            .collect();
+        matched.extend(
+            self.authors
+                .iter()
+                .filter(|a| {
+                    let a = a.to_lowercase();
+                    paper
+                        .authors
+                        .iter()
+                        .any(|author| author.name.to_lowercase().contains(&a))
+                })
+                .map(|a| format!("author:{}", a)),
+        );
+        (!matched.is_empty()).then_some(matched)
+    }
+
+    fn search_query(&self) -> String {
+        self.categories
+            .iter()
+            .map(|c| format!("cat:{}", c))
+            .collect::<Vec<_>>()
+            .join(" OR ")
+    }
+}
+
+impl Checkpoint {
+    /// Marks `papers` as seen and keeps the unseen ones that match `config`.
+    /// Returns how many matches were added.
+    pub fn record(&mut self, papers: &[Paper], config: &WatchConfig) -> usize {
+        let mut seen: HashSet<String> = self.seen.iter().cloned().collect();
+        let mut added = 0;
+        // Oldest first, so pending matches stay in submission order
+        for paper in papers.iter().rev() {
+            if !seen.insert(paper.id.clone()) {
+                continue;
+            }
+            self.seen.push_back(paper.id.clone());
+            if let Some(matched) = config.matches(paper) {
+                self.pending.push(Match {
+                    paper: paper.clone(),
+                    matched,
+                });
+                added += 1;
+            }
+        }
+
+        while self.seen.len() > MAX_SEEN {
+            self.seen.pop_front();
+        }
+        let overflow = self.pending.len().saturating_sub(MAX_PENDING);
+        self.pending.drain(..overflow);
+        added
+    }
+
+    /// Removes the pending matches up to and including the paper `cursor`
+    /// names. Returns how many were removed, none if it is no longer pending.
+    pub fn acknowledge(&mut self, cursor: &str) -> usize {
+        match self.pending.iter().position(|m| m.paper.id == cursor) {
+            Some(i) => self.pending.drain(..=i).count(),
+            None => 0,
+        }
+    }
+
+    /// ID of the newest pending match, acknowledging it removes every match
+    /// returned with it.
+    pub fn cursor(&self) -> Option<&str> {
+        self.pending.last().map(|m| m.paper.id.as_str())
+    }
+
+    fn load(path: &Path) -> Result<Checkpoint, McpArxivError> {
+        match fs::read(path) {
+            Ok(data) => serde_json::from_slice(&data)
+                .map_err(|e| McpArxivError::WatcherError(format!("{}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Checkpoint::default()),
+            Err(e) => Err(McpArxivError::WatcherError(format!(
+                "{}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Writes through a temporary file so an interrupted save keeps the old one.
+    fn save(&self, path: &Path) -> Result<(), McpArxivError> {
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, serde_json::to_vec(self)?)
+            .and_then(|_| fs::rename(&temp, path))
+            .map_err(|e| McpArxivError::WatcherError(format!("{}: {}", path.display(), e)))
+    }
+}
+
+fn state_path() -> PathBuf {
+    PathBuf::from(
+        crate::config::var("ARXIV_WATCH_STATE")
+            .filter(|p| !p.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_STATE_FILE.to_string()),
+    )
+}
+
+fn unknown_category(code: &str) -> McpArxivError {
+    let similar = similar_categories(code);
+    McpArxivError::InvalidArgument(if similar.is_empty() {
+        format!("unknown category `{}` in ARXIV_WATCH_CATEGORIES", code)
+    } else {
+        format!(
+            "unknown category `{}` in ARXIV_WATCH_CATEGORIES, did you mean one of: {}",
+            code,
+            similar.join(", ")
+        )
+    })
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Fetches the newest submissions in the watched categories, page by page
+/// until reaching papers seen before, and records the matches. Notifies
+/// subscribers of the resource when there are new ones.
+pub async fn poll(config: &WatchConfig) -> Result<usize, McpArxivError> {
+    let _lock = CHECKPOINT_LOCK.lock().await;
+    let path = state_path();
+    let mut checkpoint = Checkpoint::load(&path)?;
+    let seen: HashSet<&str> = checkpoint.seen.iter().map(String::as_str).collect();
+    // The first poll only takes the newest page instead of the whole backlog
+    let max_pages = if seen.is_empty() { 1 } else { MAX_PAGES };
+
+    let mut papers: Vec<Paper> = Vec::new();
+    for page in 0..max_pages {
+        let feed = query(&[
+            ("search_query", config.search_query()),
+            ("sortBy", "submittedDate".to_string()),
+            ("sortOrder", "descending".to_string()),
+            ("start", (page * PAGE_SIZE).to_string()),
+            ("max_results", PAGE_SIZE.to_string()),
+        ])
+        .await?;
+        let done = feed.papers.len() < PAGE_SIZE as usize
+            || feed.papers.iter().any(|p| seen.contains(p.id.as_str()));
+        papers.extend(feed.papers);
+        if done {
+            break;
+        }
+    }
+
+    let added = checkpoint.record(&papers, config);
+    checkpoint.last_poll = Some(now());
+    checkpoint.save(&path)?;
+    if added > 0 {
+        tracing::info!("arXiv watcher found {} new matching paper(s)", added);
+        crate::server::resource_updated(RESOURCE_URI);
+    }
+    Ok(added)
+}
+
+/// Returns the checkpoint after removing the matches acknowledged by `ack`, a
+/// cursor from an earlier call. Matches stay pending until then, so a response
+/// lost on the way to the client does not lose them.
+pub async fn pending(ack: Option<&str>) -> Result<Checkpoint, McpArxivError> {
+    let _lock = CHECKPOINT_LOCK.lock().await;
+    let path = state_path();
+    let mut checkpoint = Checkpoint::load(&path)?;
+    if let Some(cursor) = ack
+        && checkpoint.acknowledge(cursor) > 0
+    {
+        checkpoint.save(&path)?;
+    }
+    Ok(checkpoint)
+}
+
+/// Polls on the configured interval for as long as the server runs.
+pub async fn run() {
+    loop {
+        let config = match WatchConfig::from_env() {
+            Ok(Some(config)) => config,
+            Ok(None) => {
+                tokio::time::sleep(IDLE_INTERVAL).await;
+                continue;
+            }
+            Err(e) => {
+                tracing::error!("arXiv watcher is not running: {}", e);
+                tokio::time::sleep(IDLE_INTERVAL).await;
+                continue;
+            }
+        };
+
+        // After a restart, wait out what is left of the interval
+        let last_poll = {
+            let _lock = CHECKPOINT_LOCK.lock().await;
+            Checkpoint::load(&state_path())
+                .ok()
+                .and_then(|c| c.last_poll)
+        };
+        let elapsed = Duration::from_secs(now().saturating_sub(last_poll.unwrap_or(0)));
+        if elapsed < config.interval {
+            // Capped so config changes are picked up
+            tokio::time::sleep((config.interval - elapsed).min(IDLE_INTERVAL)).await;
+            continue;
+        }
+
+        if let Err(e) = poll(&config).await {
+            tracing::error!("arXiv watcher poll failed: {}", e);
+            tokio::time::sleep(config.interval.min(IDLE_INTERVAL * 5)).await;
+        }
+    }
+}
+
+pub fn resource() -> Resource {
+    Resource {
+        uri: RESOURCE_URI.parse().expect("valid resource URI"),
+        name: "New arXiv submissions".to_string(),
+        description: Some(
+            "Papers in the watched categories that matched the watch filters and were not yet acknowledged with GetNewSubmissions"
+                .to_string(),
+        ),
+        mime_type: Some("application/json".to_string()),
+    }
+}
+
+/// Reads the pending matches without removing them.
+pub fn reader() -> ResourceReader {
+    Arc::new(|| {
+        Box::pin(async {
+            let checkpoint = pending(None).await?;
+            Ok(serde_json::to_string(&checkpoint.pending)?)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::servers::arxiv::atom::Author;
+
+    fn paper(id: &str, title: &str, author: &str) -> Paper {
+        Paper {
+            id: id.to_string(),
+            title: title.to_string(),
+            summary: "We study language models.".to_string(),
+            authors: vec![Author {
+                name: author.to_string(),
+                affiliations: vec![],
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn config() -> WatchConfig {
+        WatchConfig {
+            categories: vec!["cs.CL".to_string()],
+            keywords: vec!["Retrieval".to_string(), "speech".to_string()],
+            authors: vec!["lovelace".to_string()],
+            interval: Duration::from_secs(3600),
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let config = config();
+        assert_eq!(
+            config.matches(&paper("1", "Dense retrieval at scale", "Alan Turing")),
+            Some(vec!["keyword:Retrieval".to_string()])
+        );
+        assert_eq!(
+            config.matches(&paper("2", "Parsing", "Ada Lovelace")),
+            Some(vec!["author:lovelace".to_string()])
+        );
+        assert_eq!(config.matches(&paper("3", "Parsing", "Alan Turing")), None);
+
+        let everything = WatchConfig {
+            keywords: vec![],
+            authors: vec![],
+            ..config
+        };
+        assert_eq!(
+            everything.matches(&paper("3", "Parsing", "Alan Turing")),
+            Some(vec![])
+        );
+        assert_eq!(everything.search_query(), "cat:cs.CL");
+    }
+
+    #[test]
+    fn test_record_skips_seen_papers() {
+        let config = config();
+        let mut checkpoint = Checkpoint::default();
+        // Newest first, as returned by the API
+        let first = [
+            paper("2401.00003", "Speech models", "A"),
+            paper("2401.00002", "Parsing", "B"),
+            paper("2401.00001", "Retrieval", "C"),
+        ];
+        assert_eq!(checkpoint.record(&first, &config), 2);
+        let pending: Vec<&str> = checkpoint
+            .pending
+            .iter()
+            .map(|m| m.paper.id.as_str())
+            .collect();
+        assert_eq!(pending, ["2401.00001", "2401.00003"]);
+
+        let second = [
+            paper("2401.00004", "More retrieval", "D"),
+            paper("2401.00003", "Speech models", "A"),
+        ];
+        assert_eq!(checkpoint.record(&second, &config), 1);
+        assert_eq!(checkpoint.seen.len(), 4);
+        assert_eq!(checkpoint.pending.len(), 3);
+
+        let path =
+            std::env::temp_dir().join(format!("arxiv-watch-test-{}.json", std::process::id()));
+        checkpoint.save(&path).unwrap();
+        let loaded = Checkpoint::load(&path).unwrap();
+        fs::remove_file(&path).ok();
+        assert_eq!(loaded.seen, checkpoint.seen);
+        assert_eq!(loaded.pending[2].paper.id, "2401.00004");
+        assert_eq!(loaded.pending[2].matched, ["keyword:Retrieval"]);
+    }
+
+    #[test]
+    fn test_acknowledge_removes_returned_matches() {
+        let config = config();
+        let mut checkpoint = Checkpoint::default();
+        checkpoint.record(
+            &[
+                paper("2401.00002", "Speech models", "A"),
+                paper("2401.00001", "Retrieval", "B"),
+            ],
+            &config,
+        );
+        let cursor = checkpoint.cursor().unwrap().to_string();
+        assert_eq!(cursor, "2401.00002");
+
+        // Found after the response was sent, stays pending
+        checkpoint.record(&[paper("2401.00003", "More retrieval", "C")], &config);
+        assert_eq!(checkpoint.acknowledge(&cursor), 2);
+        assert_eq!(checkpoint.pending.len(), 1);
+        assert_eq!(checkpoint.cursor(), Some("2401.00003"));
+
+        // Acknowledging twice removes nothing more
+        assert_eq!(checkpoint.acknowledge(&cursor), 0);
+        assert_eq!(checkpoint.pending.len(), 1);
+    }
+}
@@ -0,0 +1,114 @@
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use super::errors::McpArxivError;
+
+/// Minimal XML tree shared by the Atom and OAI-PMH parsers. Element and attribute
+/// names are stored without namespace prefixes since arXiv responses mix several
+/// vocabularies (`atom`, `opensearch`, `arxiv`, `oai_dc`, ...).
+#[derive(Debug, Clone, Default)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    /// Raw text directly inside this element
+    pub text: String,
+}
+
+impl Element {
+    pub fn parse(xml: &str) -> Result<Element, McpArxivError> {
+        let mut reader = Reader::from_str(xml);
+        let mut stack: Vec<Element> = Vec::new();
+        let parse_error = |e: quick_xml::Error| McpArxivError::ParseError(e.to_string());
+
+        loop {
+            match reader.read_event().map_err(parse_error)? {
+                Event::Start(start) => stack.push(Element::from_start(&start)?),
+                Event::Empty(start) => {
+                    let element = Element::from_start(&start)?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                Event::Text(text) => {
+                    if let Some(current) = stack.last_mut() {
+                        current
+                            .text
+                            .push_str(&text.unescape().map_err(parse_error)?);
+                    }
+                }
+                Event::CData(data) => {
+                    if let Some(current) = stack.last_mut() {
+                        current.text.push_str(&String::from_utf8_lossy(&data));
+                    }
+                }
+                Event::End(_) => {
+                    let element = stack.pop().ok_or_else(|| {
+                        McpArxivError::ParseError("unexpected closing tag".to_string())
+                    })?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                Event::Eof => {
+                    return Err(McpArxivError::ParseError(
+                        "document ended before the root element was closed".to_string(),
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn from_start(start: &BytesStart) -> Result<Element, McpArxivError> {
+        let attributes = start
+            .attributes()
+            .map(|attr| {
+                let attr = attr.map_err(|e| McpArxivError::ParseError(e.to_string()))?;
+                let value = attr
+                    .unescape_value()
+                    .map_err(|e| McpArxivError::ParseError(e.to_string()))?;
+                Ok((
+                    String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string(),
+                    value.to_string(),
+                ))
+            })
+            .collect::<Result<_, McpArxivError>>()?;
+
+        Ok(Element {
+            name: String::from_utf8_lossy(start.local_name().as_ref()).to_string(),
+            attributes,
+            ..Default::default()
+        })
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Text with runs of whitespace, including the line breaks arXiv wraps
+    /// titles and abstracts with, collapsed to single spaces.
+    pub fn collapsed_text(&self) -> String {
+        self.text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Collapsed text of the first child called `name`, if present and not empty.
+    pub fn child_text(&self, name: &str) -> Option<String> {
+        self.child(name)
+            .map(Element::collapsed_text)
+            .filter(|t| !t.is_empty())
+    }
+}
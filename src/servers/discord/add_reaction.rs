@@ -4,6 +4,8 @@ use mcp_core_macros::tool;
 use serenity::all::{ChannelId, Http, MessageId, ReactionType};
 
 use super::errors::McpDiscordError;
+use super::ids::parse_id;
+use crate::secrets;
 
 #[tool(
     name = "AddReaction",
@@ -19,19 +21,18 @@ async fn add_reaction_tool(
     discord_message_id: String,
     reaction: String,
 ) -> Result<ToolResponseContent> {
-    let discord_token =
-        std::env::var("DISCORD_TOKEN").map_err(|_| McpDiscordError::MissingDiscordToken)?;
+    let discord_token = secrets::get("DISCORD_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpDiscordError::MissingDiscordToken))?;
 
     let channel_id = ChannelId::new(
-        discord_channel_id
-            .parse::<u64>()
-            .map_err(|_| McpDiscordError::InvalidChannelID(discord_channel_id.clone()))?,
+        parse_id(&discord_channel_id)
+            .ok_or_else(|| McpDiscordError::InvalidChannelID(discord_channel_id.clone()))?,
     );
 
     let message_id = MessageId::new(
-        discord_message_id
-            .parse::<u64>()
-            .map_err(|_| McpDiscordError::InvalidMessageID(discord_message_id.clone()))?,
+        parse_id(&discord_message_id)
+            .ok_or_else(|| McpDiscordError::InvalidMessageID(discord_message_id.clone()))?,
     );
 
     let http = Http::new(&discord_token);
@@ -83,7 +84,7 @@ mod tests {
         {
             Ok(content) => {
                 println!("Reaction result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -4,6 +4,8 @@ use mcp_core_macros::tool;
 use serenity::all::{GuildId, Http, RoleId, UserId};
 
 use super::errors::McpDiscordError;
+use super::ids::parse_id;
+use crate::secrets;
 
 #[tool(
     name = "AssignRole",
@@ -19,25 +21,20 @@ async fn assign_role_tool(
     user_id: String,
     role_id: String,
 ) -> Result<ToolResponseContent> {
-    let discord_token =
-        std::env::var("DISCORD_TOKEN").map_err(|_| McpDiscordError::MissingDiscordToken)?;
+    let discord_token = secrets::get("DISCORD_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpDiscordError::MissingDiscordToken))?;
 
     let guild_id = GuildId::new(
-        guild_id
-            .parse::<u64>()
-            .map_err(|_| McpDiscordError::InvalidGuildID(guild_id.clone()))?,
+        parse_id(&guild_id).ok_or_else(|| McpDiscordError::InvalidGuildID(guild_id.clone()))?,
     );
 
     let user_id = UserId::new(
-        user_id
-            .parse::<u64>()
-            .map_err(|_| McpDiscordError::InvalidUserID(user_id.clone()))?,
+        parse_id(&user_id).ok_or_else(|| McpDiscordError::InvalidUserID(user_id.clone()))?,
     );
 
     let role_id = RoleId::new(
-        role_id
-            .parse::<u64>()
-            .map_err(|_| McpDiscordError::InvalidRoleID(role_id.clone()))?,
+        parse_id(&role_id).ok_or_else(|| McpDiscordError::InvalidRoleID(role_id.clone()))?,
     );
 
     let http = Http::new(&discord_token);
@@ -100,7 +97,7 @@ mod tests {
         {
             Ok(content) => {
                 println!("Role assignment result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
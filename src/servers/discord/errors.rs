@@ -2,6 +2,8 @@ use serde_json::Error as JsonError;
 use serenity::all::Error as DiscordError;
 use thiserror::Error;
 
+use crate::secrets::McpSecretError;
+
 #[derive(Debug, Error)]
 pub enum McpDiscordError {
     #[error("Failed to serialize response: {0}")]
@@ -24,4 +26,6 @@ pub enum McpDiscordError {
     InvalidGuildID(String),
     #[error("Missing Discord Token")]
     MissingDiscordToken,
+    #[error(transparent)]
+    Secret(#[from] McpSecretError),
 }
@@ -4,6 +4,8 @@ use mcp_core_macros::tool;
 use serenity::all::{ChannelId, GetMessages, Http};
 
 use super::errors::McpDiscordError;
+use super::ids::parse_id;
+use crate::secrets;
 
 #[tool(
     name = "GetChannelMessages",
@@ -17,13 +19,13 @@ async fn get_channel_messages_tool(
     channel_id: String,
     limit: Option<f64>,
 ) -> Result<ToolResponseContent> {
-    let discord_token =
-        std::env::var("DISCORD_TOKEN").map_err(|_| McpDiscordError::MissingDiscordToken)?;
+    let discord_token = secrets::get("DISCORD_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpDiscordError::MissingDiscordToken))?;
 
     let channel_id = ChannelId::new(
-        channel_id
-            .parse::<u64>()
-            .map_err(|_| McpDiscordError::InvalidChannelID(channel_id.clone()))?,
+        parse_id(&channel_id)
+            .ok_or_else(|| McpDiscordError::InvalidChannelID(channel_id.clone()))?,
     );
 
     let http = Http::new(&discord_token);
@@ -64,7 +66,7 @@ mod tests {
         match get_channel_messages_tool(test_channel_id.to_string(), Some(5.0)).await {
             Ok(content) => {
                 println!("Messages result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -0,0 +1,48 @@
+/// Parses a Discord snowflake ID given as a decimal string.
+///
+/// Snowflakes are larger than 2^53, so they are taken and returned as
+/// strings rather than JSON numbers. `0` is rejected, serenity's ID types
+/// panic on it.
+pub fn parse_id(id: &str) -> Option<u64> {
+    let id = id.trim();
+    if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    id.parse::<u64>().ok().filter(|&id| id != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serenity::all::{ChannelId, Message, MessageId, UserId};
+
+    #[test]
+    fn test_parse_id() {
+        assert_eq!(
+            parse_id("1234567890123456789"),
+            Some(1_234_567_890_123_456_789)
+        );
+        assert_eq!(
+            parse_id(" 1099511627776000001 "),
+            Some(1_099_511_627_776_000_001)
+        );
+        assert_eq!(parse_id("0"), None);
+        assert_eq!(parse_id("+123"), None);
+        assert_eq!(parse_id("1.2e18"), None);
+        assert_eq!(parse_id("99999999999999999999"), None);
+        assert_eq!(parse_id(""), None);
+    }
+
+    #[test]
+    fn test_message_ids_serialize_as_strings() {
+        let mut message = Message::default();
+        message.id = MessageId::new(parse_id("1234567890123456789").unwrap());
+        message.channel_id = ChannelId::new(parse_id("1099511627776000001").unwrap());
+        message.author.id = UserId::new(parse_id("987654321098765432").unwrap());
+
+        let json: serde_json::Value = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["id"], "1234567890123456789");
+        assert_eq!(json["channel_id"], "1099511627776000001");
+        assert_eq!(json["author"]["id"], "987654321098765432");
+    }
+}
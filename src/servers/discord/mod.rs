@@ -2,6 +2,7 @@ pub mod add_reaction;
 pub mod assign_role;
 pub mod errors;
 pub mod get_channel_messages;
+pub mod ids;
 pub mod post_dm;
 pub mod post_message;
 pub mod post_webhook;
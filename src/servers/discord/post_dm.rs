@@ -4,6 +4,8 @@ use mcp_core_macros::tool;
 use serenity::all::{Http, UserId};
 
 use super::errors::McpDiscordError;
+use super::ids::parse_id;
+use crate::secrets;
 
 #[tool(
     name = "PostDM",
@@ -14,13 +16,13 @@ use super::errors::McpDiscordError;
     )
 )]
 async fn post_dm_tool(discord_user_id: String, content: String) -> Result<ToolResponseContent> {
-    let discord_token =
-        std::env::var("DISCORD_TOKEN").map_err(|_| McpDiscordError::MissingDiscordToken)?;
+    let discord_token = secrets::get("DISCORD_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpDiscordError::MissingDiscordToken))?;
 
     let discord_user_id = UserId::new(
-        discord_user_id
-            .parse::<u64>()
-            .map_err(|_| McpDiscordError::InvalidUserID(discord_user_id.clone()))?,
+        parse_id(&discord_user_id)
+            .ok_or_else(|| McpDiscordError::InvalidUserID(discord_user_id.clone()))?,
     );
 
     let http = Http::new(&discord_token);
@@ -70,7 +72,7 @@ mod tests {
         {
             Ok(content) => {
                 println!("DM result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -65,7 +65,7 @@ mod tests {
         {
             Ok(content) => {
                 println!("Webhook result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
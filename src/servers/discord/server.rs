@@ -1,9 +1,12 @@
 use super::*;
+use crate::server::Server;
 use mcp_core::protocol::Protocol;
-use mcp_core::server::Server;
 use mcp_core::types::ServerCapabilities;
 use serde_json::json;
 
+/// Credentials resolved at start-up so a broken secret reference fails fast
+pub const SECRETS: &[&str] = &["DISCORD_TOKEN"];
+
 pub fn protocol() -> Protocol {
     Server::builder("Discord".to_string(), "0.1.0".to_string())
         .capabilities(ServerCapabilities {
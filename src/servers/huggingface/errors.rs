@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::secrets::McpSecretError;
+
 #[derive(Debug, Error)]
 pub enum McpHuggingFaceError {
     #[error("HTTP request error: {0}")]
@@ -10,6 +12,8 @@ pub enum McpHuggingFaceError {
     JsonParseError(String),
     #[error("API error: {0}")]
     ApiError(String),
+    #[error(transparent)]
+    Secret(#[from] McpSecretError),
     // #[error("Failed to parse image: {0}")]
     // ImageParseError(String),
 }
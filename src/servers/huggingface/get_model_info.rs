@@ -43,7 +43,7 @@ mod tests {
         match get_model_info_tool("HiDream-ai/HiDream-I1-Full".to_string()).await {
             Ok(content) => {
                 println!("Model info: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
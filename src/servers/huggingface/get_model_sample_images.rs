@@ -31,22 +31,21 @@ async fn get_model_sample_images_tool(model_id: String) -> Result<ToolResponseCo
         .lines()
         .filter_map(|line| {
             // Look for markdown image syntax: ![alt text](image_path)
-            if let Some(start) = line.find("![") {
-                if let Some(mid) = line[start..].find("](") {
-                    if let Some(end) = line[start + mid + 2..].find(')') {
-                        let image_path = &line[start + mid + 2..start + mid + 2 + end];
-                        // Convert relative path to full HuggingFace URL
-                        let full_url = if image_path.starts_with("http") {
-                            image_path.to_string()
-                        } else {
-                            format!(
-                                "https://huggingface.co/{}/resolve/main/{}",
-                                model_id, image_path
-                            )
-                        };
-                        return Some(full_url);
-                    }
-                }
+            if let Some(start) = line.find("![")
+                && let Some(mid) = line[start..].find("](")
+                && let Some(end) = line[start + mid + 2..].find(')')
+            {
+                let image_path = &line[start + mid + 2..start + mid + 2 + end];
+                // Convert relative path to full HuggingFace URL
+                let full_url = if image_path.starts_with("http") {
+                    image_path.to_string()
+                } else {
+                    format!(
+                        "https://huggingface.co/{}/resolve/main/{}",
+                        model_id, image_path
+                    )
+                };
+                return Some(full_url);
             }
             None
         })
@@ -67,7 +66,7 @@ mod tests {
         match get_model_sample_images_tool("HiDream-ai/HiDream-I1-Full".to_string()).await {
             Ok(content) => {
                 println!("Sample images: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -41,7 +41,7 @@ mod tests {
         match search_models_tool("stable diffusion".to_string(), Some(5)).await {
             Ok(content) => {
                 println!("Search results: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -1,4 +1,5 @@
 use super::errors::McpHuggingFaceError;
+use crate::secrets;
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
@@ -9,7 +10,9 @@ use reqwest::Client;
     description = "Retrieve the username, email, and orgs for the current HF API token"
 )]
 async fn whoami_tool() -> Result<ToolResponseContent> {
-    let token = std::env::var("HF_API_TOKEN").map_err(|_| McpHuggingFaceError::MissingToken)?;
+    let token = secrets::get("HF_API_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpHuggingFaceError::MissingToken))?;
     let client = Client::new();
     let url = "https://huggingface.co/api/whoami-v2";
 
@@ -42,7 +45,7 @@ mod tests {
         match whoami_tool().await {
             Ok(content) => {
                 println!("User info: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -4,6 +4,8 @@ pub mod arxiv;
 pub mod discord;
 #[cfg(feature = "huggingface")]
 pub mod huggingface;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 #[cfg(feature = "replicate")]
 pub mod replicate;
 #[cfg(feature = "shopify")]
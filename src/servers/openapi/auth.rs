@@ -0,0 +1,92 @@
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+
+use super::errors::McpOpenApiError;
+use crate::secrets;
+
+/// Credentials applied to every request. Values are resolved from the named
+/// secrets at call time so the config file holds no secrets.
+///
+/// ```json
+/// { "type": "bearer", "token_env": "PETSTORE_TOKEN" }
+/// { "type": "api_key", "in": "header", "name": "X-API-Key", "value_env": "PETSTORE_KEY" }
+/// { "type": "basic", "username_env": "PETSTORE_USER", "password_env": "PETSTORE_PASSWORD" }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuthConfig {
+    #[default]
+    None,
+    Bearer {
+        token_env: String,
+    },
+    ApiKey {
+        #[serde(rename = "in")]
+        location: ApiKeyLocation,
+        name: String,
+        value_env: String,
+    },
+    Basic {
+        username_env: String,
+        password_env: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyLocation {
+    Header,
+    Query,
+}
+
+impl AuthConfig {
+    pub fn load(path: &str) -> Result<Self, McpOpenApiError> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| McpOpenApiError::ReadError(path.to_string(), e))?;
+        serde_json::from_str(&raw)
+            .map_err(|e| McpOpenApiError::ParseError(path.to_string(), e.to_string()))
+    }
+
+    /// Secrets this config reads, checked at start-up.
+    pub fn secret_names(&self) -> Vec<&str> {
+        match self {
+            AuthConfig::None => vec![],
+            AuthConfig::Bearer { token_env } => vec![token_env],
+            AuthConfig::ApiKey { value_env, .. } => vec![value_env],
+            AuthConfig::Basic {
+                username_env,
+                password_env,
+            } => vec![username_env, password_env],
+        }
+    }
+
+    pub async fn apply(&self, request: RequestBuilder) -> Result<RequestBuilder, McpOpenApiError> {
+        Ok(match self {
+            AuthConfig::None => request,
+            AuthConfig::Bearer { token_env } => request.bearer_auth(credential(token_env).await?),
+            AuthConfig::ApiKey {
+                location: ApiKeyLocation::Header,
+                name,
+                value_env,
+            } => request.header(name.as_str(), credential(value_env).await?),
+            AuthConfig::ApiKey {
+                location: ApiKeyLocation::Query,
+                name,
+                value_env,
+            } => request.query(&[(name.as_str(), credential(value_env).await?)]),
+            AuthConfig::Basic {
+                username_env,
+                password_env,
+            } => request.basic_auth(
+                credential(username_env).await?,
+                Some(credential(password_env).await?),
+            ),
+        })
+    }
+}
+
+async fn credential(env: &str) -> Result<String, McpOpenApiError> {
+    secrets::get(env)
+        .await
+        .map_err(|e| e.or_missing(McpOpenApiError::MissingCredential(env.to_string())))
+}
@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+use crate::secrets::McpSecretError;
+
+#[derive(Debug, Error)]
+pub enum McpOpenApiError {
+    #[error("Missing OpenAPI spec path, set OPENAPI_SPEC_PATH")]
+    MissingSpecPath,
+    #[error("Failed to read {0}: {1}")]
+    ReadError(String, std::io::Error),
+    #[error("Failed to parse {0}: {1}")]
+    ParseError(String, String),
+    #[error("Unsupported spec version: {0} (only OpenAPI 3.x is supported)")]
+    UnsupportedVersion(String),
+    #[error("Invalid spec: {0}")]
+    InvalidSpec(String),
+    #[error("No base URL, set OPENAPI_BASE_URL or add an absolute URL to `servers`")]
+    MissingBaseUrl,
+    #[error("No operations left after applying include/exclude filters")]
+    NoOperations,
+    #[error("Missing credential: {0}")]
+    MissingCredential(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("HTTP request error: {0}")]
+    HttpError(reqwest::Error),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error(transparent)]
+    Secret(#[from] McpSecretError),
+}
@@ -0,0 +1,10 @@
+pub mod auth;
+pub mod errors;
+pub mod operation;
+pub mod server;
+pub mod spec;
+
+pub use auth::*;
+pub use errors::*;
+pub use operation::*;
+pub use spec::*;
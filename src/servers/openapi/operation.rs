@@ -0,0 +1,202 @@
+use reqwest::{Client, Method};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+use super::auth::AuthConfig;
+use super::errors::McpOpenApiError;
+use mcp_core::types::Tool;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamLocation {
+    Path,
+    Query,
+    Header,
+}
+
+impl ParamLocation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParamLocation::Path => "path",
+            ParamLocation::Query => "query",
+            ParamLocation::Header => "header",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    /// Argument name in the tool schema, differs from `name` only on collisions
+    pub arg: String,
+    pub name: String,
+    pub location: ParamLocation,
+    pub required: bool,
+    pub schema: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyKind {
+    Json,
+    Form,
+    Raw,
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestBody {
+    pub content_type: String,
+    pub kind: BodyKind,
+    pub required: bool,
+    pub schema: Value,
+}
+
+/// One OpenAPI operation exposed as an MCP tool.
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub id: String,
+    pub name: String,
+    pub method: String,
+    pub path: String,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub parameters: Vec<Parameter>,
+    pub body: Option<RequestBody>,
+}
+
+impl Operation {
+    pub fn tool(&self) -> Tool {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+
+        for param in &self.parameters {
+            properties.insert(param.arg.clone(), param.schema.clone());
+            if param.required {
+                required.push(json!(param.arg));
+            }
+        }
+        if let Some(body) = &self.body {
+            properties.insert("body".to_string(), body.schema.clone());
+            if body.required {
+                required.push(json!("body"));
+            }
+        }
+
+        let description = match (&self.summary, &self.description) {
+            (Some(summary), Some(description)) => format!("{}\n\n{}", summary, description),
+            (Some(text), None) | (None, Some(text)) => text.clone(),
+            (None, None) => format!("{} {}", self.method, self.path),
+        };
+
+        Tool {
+            name: self.name.clone(),
+            description: Some(description),
+            input_schema: json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            }),
+            annotations: Some(json!({
+                "title": self.id,
+                "readOnlyHint": matches!(self.method.as_str(), "GET" | "HEAD" | "OPTIONS"),
+                "destructiveHint": self.method == "DELETE",
+                "idempotentHint": matches!(self.method.as_str(), "GET" | "HEAD" | "OPTIONS" | "PUT" | "DELETE"),
+                "openWorldHint": true
+            })),
+        }
+    }
+
+    /// Sends the request described by `args` and returns the response body.
+    pub async fn call(
+        &self,
+        client: &Client,
+        base_url: &str,
+        auth: &AuthConfig,
+        args: &HashMap<String, Value>,
+    ) -> Result<String, McpOpenApiError> {
+        let mut path = self.path.clone();
+        let mut query: Vec<(String, String)> = Vec::new();
+        let mut headers: Vec<(String, String)> = Vec::new();
+
+        for param in &self.parameters {
+            let Some(value) = args.get(&param.arg).filter(|v| !v.is_null()) else {
+                if param.required {
+                    return Err(McpOpenApiError::InvalidInput(format!(
+                        "missing required parameter `{}`",
+                        param.arg
+                    )));
+                }
+                continue;
+            };
+
+            match param.location {
+                ParamLocation::Path => {
+                    path = path.replace(
+                        &format!("{{{}}}", param.name),
+                        &urlencoding::encode(&value_to_string(value)),
+                    );
+                }
+                ParamLocation::Query => match value {
+                    // Default `form` style with `explode: true` repeats the key
+                    Value::Array(items) => query.extend(
+                        items
+                            .iter()
+                            .map(|item| (param.name.clone(), value_to_string(item))),
+                    ),
+                    _ => query.push((param.name.clone(), value_to_string(value))),
+                },
+                ParamLocation::Header => headers.push((param.name.clone(), value_to_string(value))),
+            }
+        }
+
+        let method = Method::from_bytes(self.method.as_bytes())
+            .map_err(|e| McpOpenApiError::InvalidInput(e.to_string()))?;
+        let url = format!("{}{}", base_url.trim_end_matches('/'), path);
+
+        let mut request = client.request(method, &url).query(&query);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
+        match (&self.body, args.get("body").filter(|v| !v.is_null())) {
+            (Some(body), Some(value)) => {
+                request = match body.kind {
+                    BodyKind::Json => request.json(value),
+                    BodyKind::Form => request.form(value),
+                    BodyKind::Raw => request
+                        .header("Content-Type", body.content_type.as_str())
+                        .body(value_to_string(value)),
+                };
+            }
+            (Some(body), None) if body.required => {
+                return Err(McpOpenApiError::InvalidInput(
+                    "missing required `body`".to_string(),
+                ));
+            }
+            _ => {}
+        }
+
+        let res = auth
+            .apply(request)
+            .await?
+            .send()
+            .await
+            .map_err(McpOpenApiError::HttpError)?;
+
+        let status = res.status();
+        let text = res.text().await.map_err(McpOpenApiError::HttpError)?;
+        if !status.is_success() {
+            return Err(McpOpenApiError::ApiError(format!("{}: {}", status, text)));
+        }
+
+        // e.g. `204 No Content`
+        if text.is_empty() {
+            return Ok(status.to_string());
+        }
+        Ok(text)
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,331 @@
+use super::*;
+use crate::output::{self, ContinueOutputTool};
+use crate::secrets;
+use crate::server::Server;
+use anyhow::Result;
+use mcp_core::protocol::Protocol;
+use mcp_core::types::{CallToolRequest, CallToolResponse, ServerCapabilities, ToolResponseContent};
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub async fn protocol() -> Result<Protocol> {
+    Ok(protocol_from_config(&OpenApiConfig::from_env()?).await?)
+}
+
+pub async fn protocol_from_config(config: &OpenApiConfig) -> Result<Protocol, McpOpenApiError> {
+    let spec = load_spec(&config.spec_path)?;
+    let auth = match &config.auth_path {
+        Some(path) => AuthConfig::load(path)?,
+        None => AuthConfig::None,
+    };
+    secrets::validate(&auth.secret_names()).await?;
+    let spec_base_url = base_url(&spec);
+    if config.base_url().is_none() && spec_base_url.is_none() {
+        return Err(McpOpenApiError::MissingBaseUrl);
+    }
+    // Every operation is registered, the include and exclude lists are applied
+    // on each call so they can change on reload
+    let operations = operations(&spec, &[], &[])?;
+    if !operations.iter().any(|o| config.selected(&o.id)) {
+        return Err(McpOpenApiError::NoOperations);
+    }
+
+    let info = |key: &str, default: &str| {
+        spec.pointer(&format!("/info/{}", key))
+            .and_then(Value::as_str)
+            .unwrap_or(default)
+            .to_string()
+    };
+    tracing::info!(
+        "Exposing {} operations from {} as tools",
+        operations.len(),
+        config.spec_path
+    );
+
+    let context = Arc::new(CallContext {
+        client: Client::new(),
+        config: config.clone(),
+        spec_base_url,
+        auth,
+    });
+    let ids: HashMap<String, String> = operations
+        .iter()
+        .map(|o| (o.name.clone(), o.id.clone()))
+        .collect();
+    let filter_config = config.clone();
+
+    let mut builder = Server::builder(info("title", "OpenAPI"), info("version", "0.1.0"))
+        .capabilities(ServerCapabilities {
+            tools: Some(json!({})),
+            ..Default::default()
+        })
+        .register_tool(ContinueOutputTool::tool(), ContinueOutputTool::call())
+        .tool_filter(Arc::new(move |tool: &str| {
+            ids.get(tool).is_none_or(|id| filter_config.selected(id))
+        }));
+
+    for operation in operations {
+        let operation = Arc::new(operation);
+        let context = context.clone();
+        builder = builder.register_tool_handler(
+            operation.tool(),
+            Arc::new(move |req: CallToolRequest| {
+                let operation = operation.clone();
+                let context = context.clone();
+                Box::pin(async move { context.respond(&operation, req).await })
+            }),
+        );
+    }
+
+    Ok(builder.build())
+}
+
+struct CallContext {
+    client: Client,
+    config: OpenApiConfig,
+    /// Used when `OPENAPI_BASE_URL` is not set
+    spec_base_url: Option<String>,
+    auth: AuthConfig,
+}
+
+impl CallContext {
+    async fn respond(&self, operation: &Operation, req: CallToolRequest) -> CallToolResponse {
+        let args = req.arguments.unwrap_or_default();
+        let result = match self
+            .config
+            .base_url()
+            .or_else(|| self.spec_base_url.clone())
+        {
+            Some(base_url) => {
+                operation
+                    .call(&self.client, &base_url, &self.auth, &args)
+                    .await
+            }
+            None => Err(McpOpenApiError::MissingBaseUrl),
+        };
+
+        match result {
+            Ok(body) => CallToolResponse {
+                content: vec![ToolResponseContent::Text {
+                    text: output::shape(&operation.name, body),
+                }],
+                is_error: None,
+                meta: None,
+            },
+            Err(e) => CallToolResponse {
+                content: vec![ToolResponseContent::Text {
+                    text: format!("Tool execution error: {}", e),
+                }],
+                is_error: Some(true),
+                meta: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::transport::{JsonRpcNotification, JsonRpcRequest};
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const SPEC: &str = r##"{
+        "openapi": "3.0.0",
+        "info": { "title": "Stub", "version": "1.2.3" },
+        "paths": {
+            "/items/{itemId}": {
+                "put": {
+                    "operationId": "updateItem",
+                    "parameters": [
+                        { "name": "itemId", "in": "path", "required": true, "schema": { "type": "integer" } },
+                        { "name": "tags", "in": "query", "schema": { "type": "array", "items": { "type": "string" } } },
+                        { "name": "X-Trace", "in": "header", "schema": { "type": "string" } }
+                    ],
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Item" } } }
+                    }
+                },
+                "delete": { "operationId": "deleteItem" }
+            }
+        },
+        "components": {
+            "schemas": { "Item": { "type": "object", "properties": { "name": { "type": "string" } } } }
+        }
+    }"##;
+
+    /// Answers every HTTP request with a JSON echo of what it received.
+    async fn stub_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    break;
+                };
+                tokio::spawn(async move {
+                    let mut raw = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    loop {
+                        let n = socket.read(&mut buf).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        raw.extend_from_slice(&buf[..n]);
+                        let text = String::from_utf8_lossy(&raw).to_string();
+                        if let Some(split) = text.find("\r\n\r\n") {
+                            let length = text[..split]
+                                .lines()
+                                .find_map(|l| {
+                                    l.to_lowercase()
+                                        .strip_prefix("content-length:")
+                                        .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                                })
+                                .unwrap_or(0);
+                            if raw.len() >= split + 4 + length {
+                                break;
+                            }
+                        }
+                    }
+
+                    let text = String::from_utf8_lossy(&raw).to_string();
+                    let (head, body) = text.split_once("\r\n\r\n").unwrap();
+                    let mut lines = head.lines();
+                    let request_line = lines.next().unwrap_or_default().to_string();
+                    let headers: Vec<String> = lines.map(|l| l.to_lowercase()).collect();
+                    let echo = json!({
+                        "request": request_line,
+                        "headers": headers,
+                        "body": body,
+                    })
+                    .to_string();
+
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        echo.len(),
+                        echo
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    fn request(id: u64, method: &str, params: Value) -> JsonRpcRequest {
+        JsonRpcRequest {
+            id,
+            method: method.to_string(),
+            params: Some(params),
+            jsonrpc: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_openapi_protocol_against_stub_server() {
+        let spec_path =
+            std::env::temp_dir().join(format!("openapi-stub-{}.json", std::process::id()));
+        std::fs::write(&spec_path, SPEC).unwrap();
+
+        let vars = Arc::new(Mutex::new(HashMap::from([
+            (
+                "OPENAPI_SPEC_PATH".to_string(),
+                spec_path.to_string_lossy().to_string(),
+            ),
+            ("OPENAPI_BASE_URL".to_string(), stub_server().await),
+            (
+                "OPENAPI_EXCLUDE_OPERATIONS".to_string(),
+                "deleteItem".to_string(),
+            ),
+        ])));
+        let lookup = vars.clone();
+        let config = OpenApiConfig::from_lookup(Arc::new(move |name: &str| {
+            lookup.lock().unwrap().get(name).cloned()
+        }))
+        .unwrap();
+        let protocol = protocol_from_config(&config).await.unwrap();
+        std::fs::remove_file(&spec_path).ok();
+
+        protocol
+            .handle_notification(JsonRpcNotification {
+                method: "notifications/initialized".to_string(),
+                ..Default::default()
+            })
+            .await;
+
+        let list = protocol
+            .handle_request(request(1, "tools/list", json!({})))
+            .await
+            .result
+            .unwrap();
+        let names: Vec<&str> = list["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|t| t["name"].as_str())
+            .collect();
+        assert_eq!(names, ["ContinueOutput", "updateItem"]);
+        let schema = &list["tools"][1]["inputSchema"];
+        assert_eq!(
+            schema["properties"]["body"]["properties"]["name"]["type"],
+            "string"
+        );
+        assert_eq!(schema["required"], json!(["itemId", "body"]));
+
+        let call = protocol
+            .handle_request(request(
+                2,
+                "tools/call",
+                json!({
+                    "name": "updateItem",
+                    "arguments": {
+                        "itemId": 42,
+                        "tags": ["a", "b"],
+                        "X-Trace": "abc",
+                        "body": { "name": "widget" }
+                    }
+                }),
+            ))
+            .await
+            .result
+            .unwrap();
+        assert!(call.get("isError").is_none());
+
+        let echo: Value =
+            serde_json::from_str(call["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(echo["request"], "PUT /items/42?tags=a&tags=b HTTP/1.1");
+        assert!(
+            echo["headers"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("x-trace: abc"))
+        );
+        assert_eq!(echo["body"], r#"{"name":"widget"}"#);
+
+        let missing = protocol
+            .handle_request(request(
+                3,
+                "tools/call",
+                json!({ "name": "updateItem", "arguments": { "itemId": 1 } }),
+            ))
+            .await
+            .result
+            .unwrap();
+        assert_eq!(missing["isError"], true);
+
+        // The operation lists follow reloads
+        vars.lock().unwrap().remove("OPENAPI_EXCLUDE_OPERATIONS");
+        let list = protocol
+            .handle_request(request(4, "tools/list", json!({})))
+            .await
+            .result
+            .unwrap();
+        assert_eq!(list["tools"].as_array().unwrap().len(), 3);
+    }
+}
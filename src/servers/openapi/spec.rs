@@ -0,0 +1,505 @@
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::errors::McpOpenApiError;
+use super::operation::{BodyKind, Operation, ParamLocation, Parameter, RequestBody};
+
+const HTTP_METHODS: [&str; 7] = ["get", "put", "post", "delete", "patch", "head", "options"];
+/// Tools the server registers next to the operations
+const RESERVED_TOOL_NAMES: &[&str] = &["ContinueOutput"];
+
+/// Looks up a setting by name, `crate::config::var` outside tests.
+pub type VarLookup = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// Settings for the OpenAPI server. The spec and auth paths are read once, the
+/// base URL and operation lists on every use so they follow config reloads.
+#[derive(Clone)]
+pub struct OpenApiConfig {
+    pub spec_path: String,
+    pub auth_path: Option<String>,
+    pub var: VarLookup,
+}
+
+impl OpenApiConfig {
+    pub fn from_env() -> Result<Self, McpOpenApiError> {
+        Self::from_lookup(Arc::new(crate::config::var))
+    }
+
+    pub fn from_lookup(var: VarLookup) -> Result<Self, McpOpenApiError> {
+        let config = Self {
+            spec_path: String::new(),
+            auth_path: None,
+            var,
+        };
+        Ok(Self {
+            spec_path: config
+                .non_empty("OPENAPI_SPEC_PATH")
+                .ok_or(McpOpenApiError::MissingSpecPath)?,
+            auth_path: config.non_empty("OPENAPI_AUTH_PATH"),
+            ..config
+        })
+    }
+
+    /// `OPENAPI_BASE_URL`, which overrides the spec's servers
+    pub fn base_url(&self) -> Option<String> {
+        self.non_empty("OPENAPI_BASE_URL")
+    }
+
+    pub fn include(&self) -> Vec<String> {
+        self.list("OPENAPI_INCLUDE_OPERATIONS")
+    }
+
+    pub fn exclude(&self) -> Vec<String> {
+        self.list("OPENAPI_EXCLUDE_OPERATIONS")
+    }
+
+    /// Whether the operation `id` passes the current include and exclude lists.
+    pub fn selected(&self, id: &str) -> bool {
+        selected(id, &self.include(), &self.exclude())
+    }
+
+    fn non_empty(&self, name: &str) -> Option<String> {
+        (self.var)(name).filter(|v| !v.is_empty())
+    }
+
+    fn list(&self, name: &str) -> Vec<String> {
+        (self.var)(name)
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+}
+
+/// Whether `id` is in `include`, or `include` is empty, and not in `exclude`.
+pub fn selected(id: &str, include: &[String], exclude: &[String]) -> bool {
+    (include.is_empty() || include.iter().any(|i| i == id)) && !exclude.iter().any(|e| e == id)
+}
+
+/// Reads a JSON or YAML OpenAPI 3 document.
+pub fn load_spec(path: &str) -> Result<Value, McpOpenApiError> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| McpOpenApiError::ReadError(path.to_string(), e))?;
+    parse_spec(path, &raw)
+}
+
+pub fn parse_spec(name: &str, raw: &str) -> Result<Value, McpOpenApiError> {
+    let spec: Value = if raw.trim_start().starts_with('{') {
+        serde_json::from_str(raw)
+            .map_err(|e| McpOpenApiError::ParseError(name.to_string(), e.to_string()))?
+    } else {
+        serde_yaml::from_str(raw)
+            .map_err(|e| McpOpenApiError::ParseError(name.to_string(), e.to_string()))?
+    };
+
+    match spec.get("openapi").and_then(Value::as_str) {
+        Some(version) if version.starts_with("3.") => Ok(spec),
+        Some(version) => Err(McpOpenApiError::UnsupportedVersion(version.to_string())),
+        None => Err(McpOpenApiError::UnsupportedVersion(
+            spec.get("swagger")
+                .and_then(Value::as_str)
+                .map(|v| format!("swagger {}", v))
+                .unwrap_or_else(|| "unknown".to_string()),
+        )),
+    }
+}
+
+/// The first absolute server URL with its variables set to their defaults.
+pub fn base_url(spec: &Value) -> Option<String> {
+    spec.get("servers")?
+        .as_array()?
+        .iter()
+        .filter_map(|server| {
+            let mut url = server.get("url")?.as_str()?.to_string();
+            if let Some(vars) = server.get("variables").and_then(Value::as_object) {
+                for (name, var) in vars {
+                    let default = var.get("default").and_then(Value::as_str).unwrap_or("");
+                    url = url.replace(&format!("{{{}}}", name), default);
+                }
+            }
+            Some(url)
+        })
+        .find(|url| url.starts_with("http://") || url.starts_with("https://"))
+}
+
+/// Builds one [`Operation`] per path/method pair that passes the operationId filters.
+pub fn operations(
+    spec: &Value,
+    include: &[String],
+    exclude: &[String],
+) -> Result<Vec<Operation>, McpOpenApiError> {
+    let paths = spec
+        .get("paths")
+        .and_then(Value::as_object)
+        .ok_or_else(|| McpOpenApiError::InvalidSpec("missing `paths`".to_string()))?;
+
+    let mut operations = Vec::new();
+    for (path, item) in paths {
+        let item = resolve(spec, item);
+        let shared_params = item
+            .get("parameters")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        for method in HTTP_METHODS {
+            let Some(op) = item.get(method) else {
+                continue;
+            };
+
+            let id = op
+                .get("operationId")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{}_{}", method, path));
+            if !selected(&id, include, exclude) {
+                continue;
+            }
+
+            let op_params = op
+                .get("parameters")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+
+            operations.push(Operation {
+                name: tool_name(&id),
+                id,
+                method: method.to_uppercase(),
+                path: path.clone(),
+                summary: op
+                    .get("summary")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                description: op
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                parameters: parameters(spec, &shared_params, &op_params)?,
+                body: op
+                    .get("requestBody")
+                    .and_then(|body| request_body(spec, body)),
+            });
+        }
+    }
+
+    if operations.is_empty() {
+        return Err(McpOpenApiError::NoOperations);
+    }
+    check_tool_names(&operations)?;
+    Ok(operations)
+}
+
+/// Rejects operations whose tool names are empty, taken by a built-in tool or
+/// shared with another operation, which would otherwise replace it.
+fn check_tool_names(operations: &[Operation]) -> Result<(), McpOpenApiError> {
+    let mut names: HashMap<&str, &str> = HashMap::new();
+    for op in operations {
+        if op.name.is_empty() {
+            return Err(McpOpenApiError::InvalidSpec(format!(
+                "operation `{}` has no letters or digits to name its tool, set an operationId",
+                op.id
+            )));
+        }
+        if RESERVED_TOOL_NAMES.contains(&op.name.as_str()) {
+            return Err(McpOpenApiError::InvalidSpec(format!(
+                "operation `{}` would replace the built-in {} tool, rename its operationId",
+                op.id, op.name
+            )));
+        }
+        if let Some(other) = names.insert(&op.name, &op.id) {
+            return Err(McpOpenApiError::InvalidSpec(format!(
+                "operations `{}` and `{}` both become the tool {}, rename one operationId",
+                other, op.id, op.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Merges path-level and operation-level parameters, the latter winning on (name, in).
+fn parameters(
+    spec: &Value,
+    shared: &[Value],
+    own: &[Value],
+) -> Result<Vec<Parameter>, McpOpenApiError> {
+    let mut merged: Vec<Parameter> = Vec::new();
+
+    for raw in shared.iter().chain(own) {
+        let raw = resolve(spec, raw);
+        let name = raw
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| McpOpenApiError::InvalidSpec("parameter without name".to_string()))?;
+        let location = match raw.get("in").and_then(Value::as_str) {
+            Some("path") => ParamLocation::Path,
+            Some("query") => ParamLocation::Query,
+            Some("header") => ParamLocation::Header,
+            // Cookies are not supported by the tool interface
+            _ => continue,
+        };
+
+        let mut schema = raw.get("schema").cloned().unwrap_or_else(|| json!({}));
+        if let (Some(description), Value::Object(map)) =
+            (raw.get("description").and_then(Value::as_str), &mut schema)
+        {
+            map.insert("description".to_string(), json!(description));
+        }
+
+        merged.retain(|p| !(p.name == name && p.location == location));
+        merged.push(Parameter {
+            arg: name.to_string(),
+            name: name.to_string(),
+            location,
+            required: location == ParamLocation::Path
+                || raw
+                    .get("required")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            schema,
+        });
+    }
+
+    // Same name in two locations, e.g. a path `id` and a query `id`
+    let names: Vec<String> = merged.iter().map(|p| p.name.clone()).collect();
+    for param in merged.iter_mut() {
+        if names.iter().filter(|n| **n == param.name).count() > 1 || param.name == "body" {
+            param.arg = format!("{}_{}", param.location.as_str(), param.name);
+        }
+    }
+
+    Ok(merged)
+}
+
+fn request_body(spec: &Value, body: &Value) -> Option<RequestBody> {
+    let body = resolve(spec, body);
+    let content = body.get("content")?.as_object()?;
+
+    let (content_type, media) = content
+        .iter()
+        .find(|(ct, _)| ct.contains("json"))
+        .or_else(|| {
+            content
+                .iter()
+                .find(|(ct, _)| *ct == "application/x-www-form-urlencoded")
+        })
+        .or_else(|| content.iter().next())?;
+
+    let kind = if content_type.contains("json") {
+        BodyKind::Json
+    } else if content_type == "application/x-www-form-urlencoded" {
+        BodyKind::Form
+    } else {
+        BodyKind::Raw
+    };
+    let schema = match kind {
+        BodyKind::Raw => json!({ "type": "string" }),
+        _ => media.get("schema").cloned().unwrap_or_else(|| json!({})),
+    };
+
+    let mut schema = resolve(spec, &schema);
+    if let (Some(description), Value::Object(map)) =
+        (body.get("description").and_then(Value::as_str), &mut schema)
+    {
+        map.entry("description")
+            .or_insert_with(|| json!(description));
+    }
+
+    Some(RequestBody {
+        content_type: content_type.clone(),
+        kind,
+        required: body
+            .get("required")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        schema,
+    })
+}
+
+/// Inlines every local `$ref` (`#/components/...`) inside `value`. A reference
+/// back into a schema that is already being inlined becomes an untyped `{}`.
+pub fn resolve(spec: &Value, value: &Value) -> Value {
+    resolve_inner(spec, value, &mut Vec::new())
+}
+
+fn resolve_inner(spec: &Value, value: &Value, stack: &mut Vec<String>) -> Value {
+    match value {
+        Value::Object(map) => {
+            if let Some(reference) = map.get("$ref").and_then(Value::as_str) {
+                if stack.iter().any(|r| r == reference) {
+                    return json!({});
+                }
+                let Some(target) = reference
+                    .strip_prefix('#')
+                    .and_then(|pointer| spec.pointer(pointer))
+                else {
+                    return json!({});
+                };
+
+                stack.push(reference.to_string());
+                let resolved = resolve_inner(spec, target, stack);
+                stack.pop();
+                return resolved;
+            }
+            Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), resolve_inner(spec, v, stack)))
+                    .collect::<Map<String, Value>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|v| resolve_inner(spec, v, stack))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// MCP tool names are limited to 64 characters of `[A-Za-z0-9_-]`.
+fn tool_name(id: &str) -> String {
+    let name: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_matches('_');
+    name.chars().take(64).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PETSTORE: &str = r#"
+openapi: 3.0.3
+info:
+  title: Petstore
+  version: 1.0.0
+servers:
+  - url: https://{region}.petstore.example/v1
+    variables:
+      region:
+        default: eu
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      summary: List all pets
+      parameters:
+        - name: limit
+          in: query
+          schema: { type: integer }
+    post:
+      operationId: createPet
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema: { $ref: '#/components/schemas/Pet' }
+  /pets/{petId}:
+    parameters:
+      - $ref: '#/components/parameters/PetId'
+    get:
+      operationId: showPetById
+    delete:
+      operationId: deletePet
+components:
+  parameters:
+    PetId:
+      name: petId
+      in: path
+      description: The id of the pet
+      schema: { type: string }
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        name: { type: string }
+        tag: { type: string }
+        parent: { $ref: '#/components/schemas/Pet' }
+"#;
+
+    #[test]
+    fn test_parse_operations() {
+        let spec = parse_spec("petstore.yaml", PETSTORE).unwrap();
+        let ops = operations(&spec, &[], &[]).unwrap();
+
+        let ids: Vec<&str> = ops.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(ids, ["listPets", "createPet", "showPetById", "deletePet"]);
+        assert_eq!(
+            base_url(&spec).as_deref(),
+            Some("https://eu.petstore.example/v1")
+        );
+
+        let show = &ops[2];
+        assert_eq!(show.parameters[0].name, "petId");
+        assert!(show.parameters[0].required);
+        assert_eq!(
+            show.parameters[0].schema["description"],
+            "The id of the pet"
+        );
+
+        let create = ops[1].body.as_ref().unwrap();
+        assert!(create.required);
+        assert_eq!(create.schema["properties"]["name"]["type"], "string");
+        // The recursive `parent` reference is cut off instead of expanded forever
+        assert_eq!(create.schema["properties"]["parent"], json!({}));
+    }
+
+    #[test]
+    fn test_operation_filters() {
+        let spec = parse_spec("petstore.yaml", PETSTORE).unwrap();
+
+        let included = operations(&spec, &["listPets".to_string()], &[]).unwrap();
+        assert_eq!(included.len(), 1);
+
+        let excluded = operations(&spec, &[], &["deletePet".to_string()]).unwrap();
+        assert!(excluded.iter().all(|o| o.id != "deletePet"));
+
+        assert!(matches!(
+            operations(&spec, &["missing".to_string()], &[]),
+            Err(McpOpenApiError::NoOperations)
+        ));
+    }
+
+    #[test]
+    fn test_rejects_clashing_tool_names() {
+        let spec_with = |ids: &[&str]| {
+            let paths: Map<String, Value> = ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| (format!("/p{}", i), json!({ "get": { "operationId": id } })))
+                .collect();
+            json!({ "openapi": "3.0.0", "paths": paths })
+        };
+        let invalid = |ids: &[&str]| {
+            matches!(
+                operations(&spec_with(ids), &[], &[]),
+                Err(McpOpenApiError::InvalidSpec(_))
+            )
+        };
+
+        assert!(invalid(&["get.pet", "get_pet"]));
+        assert!(invalid(&["ContinueOutput"]));
+        assert!(invalid(&["..."]));
+        assert!(!invalid(&["get.pet", "get.pets"]));
+    }
+
+    #[test]
+    fn test_rejects_swagger_2() {
+        assert!(matches!(
+            parse_spec("old.json", r#"{ "swagger": "2.0", "paths": {} }"#),
+            Err(McpOpenApiError::UnsupportedVersion(_))
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use super::errors::McpReplicateError;
+use crate::secrets;
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
@@ -24,8 +25,9 @@ async fn edit_image_tool(
     steps: Option<u32>,
     guidance: Option<u32>,
 ) -> Result<ToolResponseContent> {
-    let token =
-        std::env::var("REPLICATE_API_TOKEN").map_err(|_| McpReplicateError::MissingToken)?;
+    let token = secrets::get("REPLICATE_API_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpReplicateError::MissingToken))?;
     let client = Client::new();
 
     // Fixed model URL for flux-canny-pro
@@ -101,7 +103,7 @@ mod tests {
         {
             Ok(content) => {
                 println!("Prediction result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -1,4 +1,5 @@
 use super::errors::McpReplicateError;
+use crate::secrets;
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
@@ -22,8 +23,9 @@ async fn edit_image_with_mask_tool(
     mask: String,
     prompt: String,
 ) -> Result<ToolResponseContent> {
-    let token =
-        std::env::var("REPLICATE_API_TOKEN").map_err(|_| McpReplicateError::MissingToken)?;
+    let token = secrets::get("REPLICATE_API_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpReplicateError::MissingToken))?;
     let client = Client::new();
 
     // Create the prediction URL
@@ -98,7 +100,7 @@ mod tests {
         {
             Ok(content) => {
                 println!("Prediction result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::secrets::McpSecretError;
+
 #[derive(Debug, Error)]
 pub enum McpReplicateError {
     #[error("HTTP request error: {0}")]
@@ -10,6 +12,8 @@ pub enum McpReplicateError {
     ContentParseError(String),
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
+    #[error(transparent)]
+    Secret(#[from] McpSecretError),
     // #[error("Failed to parse JSON: {0}")]
     // JsonParseError(String),
     // #[error("Failed to parse image: {0}")]
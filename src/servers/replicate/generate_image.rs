@@ -1,4 +1,5 @@
 use super::errors::McpReplicateError;
+use crate::secrets;
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
@@ -22,8 +23,9 @@ async fn generate_image_tool(
     prompt: String,
     lora_weights: Option<String>,
 ) -> Result<ToolResponseContent> {
-    let token =
-        std::env::var("REPLICATE_API_TOKEN").map_err(|_| McpReplicateError::MissingToken)?;
+    let token = secrets::get("REPLICATE_API_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpReplicateError::MissingToken))?;
     let client = Client::new();
 
     // Create the prediction URL
@@ -38,13 +40,13 @@ async fn generate_image_tool(
     });
 
     // Add lora_weights if provided
-    if let Some(weights) = lora_weights {
-        if let serde_json::Value::Object(ref mut map) = input {
-            map.insert(
-                "lora_weights".to_string(),
-                serde_json::Value::String(weights),
-            );
-        }
+    if let Some(weights) = lora_weights
+        && let serde_json::Value::Object(ref mut map) = input
+    {
+        map.insert(
+            "lora_weights".to_string(),
+            serde_json::Value::String(weights),
+        );
     }
 
     // Create the full payload
@@ -108,7 +110,7 @@ mod tests {
         {
             Ok(content) => {
                 println!("Prediction result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -1,4 +1,5 @@
 use super::errors::McpReplicateError;
+use crate::secrets;
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
@@ -11,8 +12,9 @@ use serde_json::Value;
     params(model_id = "ID of the model, e.g. 'stability-ai/stable-diffusion'")
 )]
 async fn get_model_info_tool(model_id: String) -> Result<ToolResponseContent> {
-    let token =
-        std::env::var("REPLICATE_API_TOKEN").map_err(|_| McpReplicateError::MissingToken)?;
+    let token = secrets::get("REPLICATE_API_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpReplicateError::MissingToken))?;
     let client = Client::new();
     let url = format!("https://api.replicate.com/v1/models/{}", model_id);
 
@@ -45,7 +47,7 @@ mod tests {
         match get_model_info_tool("black-forest-labs/flux-dev-lora".to_string()).await {
             Ok(content) => {
                 println!("Model info: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -1,4 +1,5 @@
 use super::errors::McpReplicateError;
+use crate::secrets;
 use anyhow::Result;
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD;
@@ -14,8 +15,9 @@ use url::Url;
     params(prediction_id = "ID returned by GenerateImage")
 )]
 async fn get_prediction_tool(prediction_id: String) -> Result<Vec<ToolResponseContent>> {
-    let token =
-        std::env::var("REPLICATE_API_TOKEN").map_err(|_| McpReplicateError::MissingToken)?;
+    let token = secrets::get("REPLICATE_API_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpReplicateError::MissingToken))?;
     let client = Client::new();
     let url = format!("https://api.replicate.com/v1/predictions/{}", prediction_id);
 
@@ -45,7 +47,7 @@ async fn get_prediction_tool(prediction_id: String) -> Result<Vec<ToolResponseCo
     // Extract extension and determine mime type
     let mime_type = Url::parse(image_url)
         .ok()
-        .and_then(|url| url.path().split('.').last().map(|s| s.to_string()))
+        .and_then(|url| url.path().split('.').next_back().map(|s| s.to_string()))
         .map(|ext| match ext.as_str() {
             "webp" => "image/webp",
             "png" => "image/png",
@@ -94,7 +96,7 @@ mod tests {
         match get_prediction_tool(prediction_id.to_string()).await {
             Ok(content) => {
                 println!("Prediction status: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
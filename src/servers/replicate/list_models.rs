@@ -1,4 +1,5 @@
 use super::errors::McpReplicateError;
+use crate::secrets;
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
@@ -16,8 +17,9 @@ async fn list_models_tool(
     name_filter: Option<String>,
     limit: Option<usize>,
 ) -> Result<ToolResponseContent> {
-    let token =
-        std::env::var("REPLICATE_API_TOKEN").map_err(|_| McpReplicateError::MissingToken)?;
+    let token = secrets::get("REPLICATE_API_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpReplicateError::MissingToken))?;
     let client = Client::new();
     let mut url = format!(
         "https://api.replicate.com/v1/models?limit={}",
@@ -59,7 +61,7 @@ mod tests {
         match list_models_tool(Some("stable-diffusion".to_string()), Some(5)).await {
             Ok(content) => {
                 println!("Models list: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
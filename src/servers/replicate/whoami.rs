@@ -1,4 +1,5 @@
 use super::errors::McpReplicateError;
+use crate::secrets;
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
@@ -9,8 +10,9 @@ use reqwest::{Client, header::AUTHORIZATION};
     description = "Get details for the current Replicate API token."
 )]
 async fn whoami_tool() -> Result<ToolResponseContent> {
-    let token =
-        std::env::var("REPLICATE_API_TOKEN").map_err(|_| McpReplicateError::MissingToken)?;
+    let token = secrets::get("REPLICATE_API_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpReplicateError::MissingToken))?;
     let client = Client::new();
     let url = "https://api.replicate.com/v1/user";
 
@@ -43,7 +45,7 @@ mod tests {
         match whoami_tool().await {
             Ok(content) => {
                 println!("User info: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -5,6 +5,7 @@ use reqwest::Client;
 use serde_json::json;
 
 use super::errors::McpShopifyError;
+use crate::secrets;
 
 #[tool(
     name = "AddProductMedia",
@@ -20,10 +21,12 @@ async fn add_product_image_tool(
     image_url: String,
     image_alt: String,
 ) -> Result<ToolResponseContent> {
-    let shop_domain =
-        std::env::var("SHOPIFY_SHOP_DOMAIN").map_err(|_| McpShopifyError::MissingShopifyDomain)?;
-    let access_token = std::env::var("SHOPIFY_ACCESS_TOKEN")
-        .map_err(|_| McpShopifyError::MissingShopifyAccessToken)?;
+    let shop_domain = secrets::get("SHOPIFY_SHOP_DOMAIN")
+        .await
+        .map_err(|e| e.or_missing(McpShopifyError::MissingShopifyDomain))?;
+    let access_token = secrets::get("SHOPIFY_ACCESS_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpShopifyError::MissingShopifyAccessToken))?;
 
     let client = Client::new();
     let graphql_url = format!("https://{}/admin/api/2025-04/graphql.json", shop_domain);
@@ -110,7 +113,7 @@ mod tests {
         match test_result {
             Ok(content) => {
                 println!("Media addition result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -5,6 +5,7 @@ use reqwest::Client;
 use serde_json::json;
 
 use super::errors::McpShopifyError;
+use crate::secrets;
 
 #[tool(
     name = "CreateOrder",
@@ -18,10 +19,12 @@ async fn create_order_tool(
     line_items: Vec<serde_json::Value>,
     customer_id: Option<String>,
 ) -> Result<ToolResponseContent> {
-    let shop_domain =
-        std::env::var("SHOPIFY_SHOP_DOMAIN").map_err(|_| McpShopifyError::MissingShopifyDomain)?;
-    let access_token = std::env::var("SHOPIFY_ACCESS_TOKEN")
-        .map_err(|_| McpShopifyError::MissingShopifyAccessToken)?;
+    let shop_domain = secrets::get("SHOPIFY_SHOP_DOMAIN")
+        .await
+        .map_err(|e| e.or_missing(McpShopifyError::MissingShopifyDomain))?;
+    let access_token = secrets::get("SHOPIFY_ACCESS_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpShopifyError::MissingShopifyAccessToken))?;
 
     let client = Client::new();
     let url = format!("https://{}/admin/api/2022-04/orders.json", shop_domain);
@@ -73,7 +76,7 @@ mod tests {
         match test_order {
             Ok(content) => {
                 println!("Order creation result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
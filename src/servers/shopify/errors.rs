@@ -2,6 +2,9 @@ use reqwest::Error as HttpError;
 use serde_json::Error as JsonError;
 use thiserror::Error;
 
+use crate::secrets::McpSecretError;
+
+#[allow(dead_code)]
 #[derive(Debug, Error)]
 pub enum McpShopifyError {
     #[error("Failed to serialize response: {0}")]
@@ -20,4 +23,6 @@ pub enum McpShopifyError {
     InvalidOrderId(String),
     #[error("Invalid Customer ID: {0}")]
     InvalidCustomerId(String),
+    #[error(transparent)]
+    Secret(#[from] McpSecretError),
 }
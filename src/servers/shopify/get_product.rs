@@ -4,6 +4,7 @@ use mcp_core_macros::tool;
 use reqwest::Client;
 
 use super::errors::McpShopifyError;
+use crate::secrets;
 
 #[tool(
     name = "GetProduct",
@@ -11,10 +12,12 @@ use super::errors::McpShopifyError;
     params(product_id = "ID of the product to retrieve")
 )]
 async fn get_product_tool(product_id: String) -> Result<ToolResponseContent> {
-    let shop_domain =
-        std::env::var("SHOPIFY_SHOP_DOMAIN").map_err(|_| McpShopifyError::MissingShopifyDomain)?;
-    let access_token = std::env::var("SHOPIFY_ACCESS_TOKEN")
-        .map_err(|_| McpShopifyError::MissingShopifyAccessToken)?;
+    let shop_domain = secrets::get("SHOPIFY_SHOP_DOMAIN")
+        .await
+        .map_err(|e| e.or_missing(McpShopifyError::MissingShopifyDomain))?;
+    let access_token = secrets::get("SHOPIFY_ACCESS_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpShopifyError::MissingShopifyAccessToken))?;
 
     let client = Client::new();
     let url = format!(
@@ -56,7 +59,7 @@ mod tests {
         match get_product_tool(product_id.to_string()).await {
             Ok(content) => {
                 println!("Product result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
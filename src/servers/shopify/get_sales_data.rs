@@ -4,16 +4,19 @@ use mcp_core_macros::tool;
 use reqwest::Client;
 
 use super::errors::McpShopifyError;
+use crate::secrets;
 
 #[tool(
     name = "GetSalesData",
     description = "Retrieve sales data from the Shopify store."
 )]
 async fn get_sales_data_tool() -> Result<ToolResponseContent> {
-    let shop_domain =
-        std::env::var("SHOPIFY_SHOP_DOMAIN").map_err(|_| McpShopifyError::MissingShopifyDomain)?;
-    let access_token = std::env::var("SHOPIFY_ACCESS_TOKEN")
-        .map_err(|_| McpShopifyError::MissingShopifyAccessToken)?;
+    let shop_domain = secrets::get("SHOPIFY_SHOP_DOMAIN")
+        .await
+        .map_err(|e| e.or_missing(McpShopifyError::MissingShopifyDomain))?;
+    let access_token = secrets::get("SHOPIFY_ACCESS_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpShopifyError::MissingShopifyAccessToken))?;
 
     let client = Client::new();
     let url = format!("https://{}/admin/api/2022-04/reports.json", shop_domain);
@@ -50,7 +53,7 @@ mod tests {
         match get_sales_data_tool().await {
             Ok(content) => {
                 println!("Sales data result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -4,16 +4,20 @@ use mcp_core_macros::tool;
 use reqwest::Client;
 
 use super::errors::McpShopifyError;
+use crate::output;
+use crate::secrets;
 
 #[tool(
     name = "ListCustomers",
     description = "List all customers in the Shopify store."
 )]
 async fn list_customers_tool() -> Result<ToolResponseContent> {
-    let shop_domain =
-        std::env::var("SHOPIFY_SHOP_DOMAIN").map_err(|_| McpShopifyError::MissingShopifyDomain)?;
-    let access_token = std::env::var("SHOPIFY_ACCESS_TOKEN")
-        .map_err(|_| McpShopifyError::MissingShopifyAccessToken)?;
+    let shop_domain = secrets::get("SHOPIFY_SHOP_DOMAIN")
+        .await
+        .map_err(|e| e.or_missing(McpShopifyError::MissingShopifyDomain))?;
+    let access_token = secrets::get("SHOPIFY_ACCESS_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpShopifyError::MissingShopifyAccessToken))?;
 
     let client = Client::new();
     let url = format!("https://{}/admin/api/2022-04/customers.json", shop_domain);
@@ -27,7 +31,7 @@ async fn list_customers_tool() -> Result<ToolResponseContent> {
 
     let body = res.text().await.map_err(McpShopifyError::HttpError)?;
 
-    Ok(tool_text_content!(body))
+    Ok(tool_text_content!(output::shape("ListCustomers", body)))
 }
 
 #[cfg(test)]
@@ -50,7 +54,7 @@ mod tests {
         match list_customers_tool().await {
             Ok(content) => {
                 println!("Customers result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -1,9 +1,13 @@
 use super::*;
+use crate::output::ContinueOutputTool;
+use crate::server::Server;
 use mcp_core::protocol::Protocol;
-use mcp_core::server::Server;
 use mcp_core::types::ServerCapabilities;
 use serde_json::json;
 
+/// Credentials resolved at start-up so a broken secret reference fails fast
+pub const SECRETS: &[&str] = &["SHOPIFY_SHOP_DOMAIN", "SHOPIFY_ACCESS_TOKEN"];
+
 pub fn protocol() -> Protocol {
     Server::builder("Shopify".to_string(), "0.1.0".to_string())
         .capabilities(ServerCapabilities {
@@ -21,5 +25,6 @@ pub fn protocol() -> Protocol {
         .register_tool(ListProductsTool::tool(), ListProductsTool::call())
         .register_tool(UpdateProductTool::tool(), UpdateProductTool::call())
         .register_tool(AddProductImageTool::tool(), AddProductImageTool::call())
+        .register_tool(ContinueOutputTool::tool(), ContinueOutputTool::call())
         .build()
 }
@@ -5,6 +5,7 @@ use reqwest::Client;
 use serde_json::json;
 
 use super::errors::McpShopifyError;
+use crate::secrets;
 
 #[tool(
     name = "UpdateProduct",
@@ -26,10 +27,12 @@ async fn update_product_tool(
     product_type: Option<String>,
     price: Option<String>,
 ) -> Result<ToolResponseContent> {
-    let shop_domain =
-        std::env::var("SHOPIFY_SHOP_DOMAIN").map_err(|_| McpShopifyError::MissingShopifyDomain)?;
-    let access_token = std::env::var("SHOPIFY_ACCESS_TOKEN")
-        .map_err(|_| McpShopifyError::MissingShopifyAccessToken)?;
+    let shop_domain = secrets::get("SHOPIFY_SHOP_DOMAIN")
+        .await
+        .map_err(|e| e.or_missing(McpShopifyError::MissingShopifyDomain))?;
+    let access_token = secrets::get("SHOPIFY_ACCESS_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpShopifyError::MissingShopifyAccessToken))?;
 
     let client = Client::new();
     let url = format!(
@@ -95,7 +98,7 @@ mod tests {
         match test_update {
             Ok(content) => {
                 println!("Product update result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
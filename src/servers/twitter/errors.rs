@@ -2,6 +2,8 @@ use serde_json::Error as JsonError;
 use thiserror::Error;
 use twitter_v2::Error as TwitterError;
 
+use crate::secrets::McpSecretError;
+
 #[derive(Debug, Error)]
 pub enum McpTwitterError {
     #[error("Failed to serialize response: {0}")]
@@ -17,21 +19,27 @@ pub enum McpTwitterError {
     #[error("Twitter API error: {0}")]
     TwitterApiError(TwitterError),
     #[error("Invalid latest id: {0}")]
-    InvalidLatestID(f64),
+    InvalidLatestID(String),
     #[error("Invalid count: {0}")]
-    InvalidCount(f64),
+    InvalidCount(String),
     #[error("Invalid input: {0}")]
     InvalidInput(String),
     #[error("Tweet not found")]
     TweetNotFound,
-    #[error("Tweets not found")]
-    TweetsNotFound,
     #[error("Invalid reply id: {0}")]
-    InvalidReplyID(f64),
+    InvalidReplyID(String),
+    #[error("Invalid until id: {0}")]
+    InvalidUntilID(String),
+    #[error("Invalid time: {0}")]
+    InvalidTime(String),
+    #[error("Tweet too long: weighted length {0} is over {1}")]
+    TweetTooLong(usize, usize),
     #[error("Invalid query: {0}")]
     InvalidQuery(String),
     #[error("Invalid sort order: {0}")]
     InvalidSortOrder(String),
     #[error("User not found")]
     UserNotFound,
+    #[error(transparent)]
+    Secret(#[from] McpSecretError),
 }
@@ -0,0 +1,196 @@
+use serde::Serialize;
+use twitter_v2::data::{Expansions, Media, ReferencedTweetKind, Tweet, User};
+use twitter_v2::id::NumericId;
+use twitter_v2::query::{MediaField, TweetExpansion, TweetField, UserField};
+
+/// Tweet fields requested with every read, beyond the default `id` and `text`
+pub const TWEET_FIELDS: [TweetField; 8] = [
+    TweetField::AuthorId,
+    TweetField::CreatedAt,
+    TweetField::ConversationId,
+    TweetField::InReplyToUserId,
+    TweetField::Lang,
+    TweetField::PublicMetrics,
+    TweetField::ReferencedTweets,
+    TweetField::Attachments,
+];
+pub const USER_FIELDS: [UserField; 4] = [
+    UserField::Name,
+    UserField::Username,
+    UserField::Verified,
+    UserField::PublicMetrics,
+];
+pub const MEDIA_FIELDS: [MediaField; 7] = [
+    MediaField::Type,
+    MediaField::Url,
+    MediaField::PreviewImageUrl,
+    MediaField::AltText,
+    MediaField::Width,
+    MediaField::Height,
+    MediaField::Variants,
+];
+/// Authors, referenced tweets with their authors, and attached media
+pub const EXPANSIONS: [TweetExpansion; 4] = [
+    TweetExpansion::AuthorId,
+    TweetExpansion::ReferencedTweetsId,
+    TweetExpansion::ReferencedTweetsIdAuthorId,
+    TweetExpansion::AttachmentsMediaKeys,
+];
+
+/// A tweet with the users, media and tweets it points to joined in from the
+/// response's `includes`.
+#[derive(Debug, Serialize)]
+pub struct ExpandedTweet {
+    #[serde(flatten)]
+    pub tweet: Tweet,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<Author>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub media: Vec<Media>,
+    /// Quoted, replied-to and retweeted tweets, in `referenced_tweets` order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub referenced: Vec<Referenced>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Author {
+    pub id: NumericId,
+    pub username: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub followers_count: Option<usize>,
+}
+
+impl From<&User> for Author {
+    fn from(user: &User) -> Self {
+        Author {
+            id: user.id,
+            username: user.username.clone(),
+            name: user.name.clone(),
+            verified: user.verified,
+            followers_count: user.public_metrics.as_ref().map(|m| m.followers_count),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Referenced {
+    #[serde(rename = "type")]
+    pub kind: ReferencedTweetKind,
+    pub id: NumericId,
+    /// Absent when the tweet was deleted or is not visible
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<Author>,
+}
+
+/// Joins `includes` into each of `tweets`.
+pub fn join(tweets: Vec<Tweet>, includes: Option<&Expansions>) -> Vec<ExpandedTweet> {
+    let users = includes
+        .and_then(|i| i.users.as_deref())
+        .unwrap_or_default();
+    let media = includes
+        .and_then(|i| i.media.as_deref())
+        .unwrap_or_default();
+    let included = includes
+        .and_then(|i| i.tweets.as_deref())
+        .unwrap_or_default();
+    let author = |id: Option<NumericId>| {
+        let id = id?;
+        users.iter().find(|u| u.id == id).map(Author::from)
+    };
+
+    tweets
+        .into_iter()
+        .map(|tweet| {
+            let media_keys = tweet
+                .attachments
+                .as_ref()
+                .and_then(|a| a.media_keys.as_deref())
+                .unwrap_or_default();
+            let referenced = tweet
+                .referenced_tweets
+                .iter()
+                .flatten()
+                .map(|r| {
+                    let found = included.iter().find(|t| t.id == r.id);
+                    Referenced {
+                        kind: r.kind.clone(),
+                        id: r.id,
+                        text: found.map(|t| t.text.clone()),
+                        author: author(found.and_then(|t| t.author_id)),
+                    }
+                })
+                .collect();
+            ExpandedTweet {
+                author: author(tweet.author_id),
+                media: media_keys
+                    .iter()
+                    .filter_map(|key| media.iter().find(|m| &m.media_key == key).cloned())
+                    .collect(),
+                referenced,
+                tweet,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use twitter_v2::ApiPayload;
+    use twitter_v2::meta::TweetsMeta;
+
+    #[test]
+    fn test_join() {
+        let payload: ApiPayload<Vec<Tweet>, TweetsMeta> = serde_json::from_value(json!({
+            "data": [{
+                "id": "1893713254098280765",
+                "text": "Worth reading https://t.co/abc",
+                "author_id": "1460323737035677698",
+                "public_metrics": { "retweet_count": 4, "reply_count": 2, "like_count": 31, "quote_count": 1 },
+                "attachments": { "media_keys": ["3_1893713250000000000"] },
+                "referenced_tweets": [
+                    { "type": "quoted", "id": "1893700000000000001" },
+                    { "type": "replied_to", "id": "1893700000000000002" }
+                ]
+            }],
+            "includes": {
+                "users": [
+                    { "id": "1460323737035677698", "name": "Ada", "username": "ada", "verified": true },
+                    { "id": "783214", "name": "X", "username": "X", "verified": false }
+                ],
+                "media": [
+                    { "media_key": "3_1893713250000000000", "type": "photo", "url": "https://pbs.twimg.com/media/a.jpg" }
+                ],
+                "tweets": [
+                    { "id": "1893700000000000001", "text": "The quoted tweet", "author_id": "783214" }
+                ]
+            },
+            "meta": { "result_count": 1 }
+        }))
+        .unwrap();
+
+        let tweets = join(payload.data.unwrap(), payload.includes.as_ref());
+        let json = serde_json::to_value(&tweets).unwrap();
+        let tweet = &json[0];
+        assert_eq!(tweet["id"], "1893713254098280765");
+        assert_eq!(tweet["public_metrics"]["like_count"], 31);
+        assert_eq!(tweet["author"]["username"], "ada");
+        assert_eq!(tweet["author"]["verified"], true);
+        assert_eq!(
+            tweet["media"][0]["url"],
+            "https://pbs.twimg.com/media/a.jpg"
+        );
+        assert_eq!(tweet["referenced"][0]["type"], "quoted");
+        assert_eq!(tweet["referenced"][0]["text"], "The quoted tweet");
+        assert_eq!(tweet["referenced"][0]["author"]["username"], "X");
+        // Not in includes, e.g. deleted
+        assert_eq!(tweet["referenced"][1]["id"], "1893700000000000002");
+        assert!(tweet["referenced"][1].get("text").is_none());
+    }
+}
@@ -1,29 +1,50 @@
 use super::errors::McpTwitterError;
+use super::expansions::{EXPANSIONS, MEDIA_FIELDS, TWEET_FIELDS, USER_FIELDS};
+use super::paging::{self, Bounds, TIMELINE_RESULTS};
+use crate::secrets;
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
-use twitter_v2::{TwitterApi, authorization::Oauth1aToken, id::NumericId};
+use twitter_v2::{TwitterApi, authorization::Oauth1aToken};
 
 #[tool(
     name = "GetMentions",
-    description = "Fetches mentions to the user from Twitter. Required API and ACCESS values can be valued as 'null' if the info is not available.",
+    description = "Fetches mentions to the user from Twitter. Returns JSON with tweets, each with its author, public_metrics, media and referenced tweets, result_count and next_token (when more results exist). Required API and ACCESS values can be valued as 'null' if the info is not available.",
     params(
-        count = "The max count of tweets to be fetched",
-        latest_id = "The Tweet ID to fetch mentions after"
+        count = "The max count of tweets per page, from 5 to 100",
+        latest_id = "The Tweet ID, as a string, to fetch mentions after",
+        until_id = "The Tweet ID, as a string, to fetch mentions before",
+        start_time = "The oldest time to fetch mentions from, in RFC 3339 (e.g. '2025-01-31T12:00:00Z')",
+        end_time = "The newest time to fetch mentions until, in RFC 3339",
+        pagination_token = "The next_token of a previous call, to fetch the page after it",
+        max_pages = "Pages to fetch, following next_token (default: 1, at most 10)"
     )
 )]
 async fn get_mentions_tool(
     count: Option<f64>,
-    latest_id: Option<f64>,
+    latest_id: Option<String>,
+    until_id: Option<String>,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    pagination_token: Option<String>,
+    max_pages: Option<f64>,
 ) -> Result<ToolResponseContent> {
-    let twitter_api_key =
-        std::env::var("TWITTER_API_KEY").map_err(|_| McpTwitterError::MissingTwitterApiKey)?;
-    let twitter_api_secret = std::env::var("TWITTER_API_SECRET")
-        .map_err(|_| McpTwitterError::MissingTwitterApiSecret)?;
-    let twitter_access_token = std::env::var("TWITTER_ACCESS_TOKEN")
-        .map_err(|_| McpTwitterError::MissingTwitterAccessToken)?;
-    let twitter_access_token_secret = std::env::var("TWITTER_ACCESS_TOKEN_SECRET")
-        .map_err(|_| McpTwitterError::MissingTwitterAccessTokenSecret)?;
+    let max_results = paging::max_results(count, TIMELINE_RESULTS)?;
+    let max_pages = paging::max_pages(max_pages)?;
+    let bounds = Bounds::parse(latest_id, until_id, start_time, end_time)?;
+
+    let twitter_api_key = secrets::get("TWITTER_API_KEY")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterApiKey))?;
+    let twitter_api_secret = secrets::get("TWITTER_API_SECRET")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterApiSecret))?;
+    let twitter_access_token = secrets::get("TWITTER_ACCESS_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterAccessToken))?;
+    let twitter_access_token_secret = secrets::get("TWITTER_ACCESS_TOKEN_SECRET")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterAccessTokenSecret))?;
 
     let api = TwitterApi::new(Oauth1aToken::new(
         &twitter_api_key,
@@ -42,26 +63,35 @@ async fn get_mentions_tool(
 
     let mut builder = api.get_user_mentions(user.id);
 
-    if let Some(c) = count {
-        if c < 0.0 {
-            return Err(McpTwitterError::InvalidCount(c).into());
-        }
-        builder.max_results(c as usize);
+    builder
+        .tweet_fields(TWEET_FIELDS)
+        .user_fields(USER_FIELDS)
+        .media_fields(MEDIA_FIELDS)
+        .expansions(EXPANSIONS);
+    if let Some(max_results) = max_results {
+        builder.max_results(max_results);
     }
-
-    if let Some(id) = latest_id {
-        if (id as u64) == 0 {
-            return Err(McpTwitterError::InvalidLatestID(id).into());
-        }
-        builder.since_id(NumericId::new(id as u64));
+    if let Some(id) = bounds.since_id {
+        builder.since_id(id);
+    }
+    if let Some(id) = bounds.until_id {
+        builder.until_id(id);
+    }
+    if let Some(time) = bounds.start_time {
+        builder.start_time(time);
+    }
+    if let Some(time) = bounds.end_time {
+        builder.end_time(time);
+    }
+    if let Some(token) = pagination_token {
+        builder.pagination_token(&token);
     }
 
-    let mentions = builder
+    let first = builder
         .send()
         .await
-        .map_err(McpTwitterError::TwitterApiError)?
-        .into_data()
-        .ok_or_else(|| McpTwitterError::TweetsNotFound)?;
+        .map_err(McpTwitterError::TwitterApiError)?;
+    let mentions = paging::collect(first, max_pages).await?;
 
     Ok(tool_text_content!(
         serde_json::to_string(&mentions).map_err(McpTwitterError::ResponseSerializeError)?
@@ -87,10 +117,10 @@ mod tests {
             return;
         }
 
-        match get_mentions_tool(Some(5.0), None).await {
+        match get_mentions_tool(Some(5.0), None, None, None, None, None, None).await {
             Ok(content) => {
                 println!("Mentions result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
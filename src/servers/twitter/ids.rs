@@ -0,0 +1,51 @@
+use twitter_v2::id::NumericId;
+
+/// Parses a tweet ID given as a decimal string.
+///
+/// Tweet IDs are larger than 2^53 and lose precision as JSON numbers, so tools
+/// take them as strings. twitter-v2 serializes `NumericId` as a string too.
+pub fn parse_id(id: &str) -> Option<NumericId> {
+    let id = id.trim();
+    if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    id.parse::<u64>()
+        .ok()
+        .filter(|&id| id != 0)
+        .map(NumericId::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use twitter_v2::Tweet;
+
+    #[test]
+    fn test_parse_id() {
+        let id = parse_id("1893713254098280765").unwrap();
+        assert_eq!(id.as_u64(), 1_893_713_254_098_280_765);
+        // The same ID as a JSON number is rounded to the nearest f64
+        assert_ne!(1_893_713_254_098_280_765f64 as u64, id.as_u64());
+        assert_eq!(
+            parse_id(" 1460323737035677698 ").unwrap().as_u64(),
+            1_460_323_737_035_677_698
+        );
+        assert!(parse_id("0").is_none());
+        assert!(parse_id("-1").is_none());
+        assert!(parse_id("1.8937132540982808e18").is_none());
+        assert!(parse_id("").is_none());
+    }
+
+    #[test]
+    fn test_tweet_ids_serialize_as_strings() {
+        let tweet: Tweet = serde_json::from_value(serde_json::json!({
+            "id": "1893713254098280765",
+            "text": "hello",
+            "author_id": "1460323737035677698",
+        }))
+        .unwrap();
+        let json = serde_json::to_value(&tweet).unwrap();
+        assert_eq!(json["id"], "1893713254098280765");
+        assert_eq!(json["author_id"], "1460323737035677698");
+    }
+}
@@ -1,13 +1,22 @@
 pub mod errors;
+pub mod expansions;
 pub mod get_mentions;
 pub mod get_timeline;
+pub mod ids;
+pub mod paging;
+pub mod post_thread;
 pub mod post_tweet;
 pub mod reply_to_tweet;
 pub mod search_tweets;
 pub mod server;
+pub mod text;
+pub mod thread;
+pub mod validate_tweet;
 
 pub use get_mentions::*;
 pub use get_timeline::*;
+pub use post_thread::*;
 pub use post_tweet::*;
 pub use reply_to_tweet::*;
 pub use search_tweets::*;
+pub use validate_tweet::*;
@@ -0,0 +1,219 @@
+use std::ops::RangeInclusive;
+
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use twitter_v2::{
+    ApiResponse, Tweet, authorization::Oauth1aToken, id::NumericId, meta::TweetsMeta,
+    prelude::PaginableApiResponse,
+};
+
+use super::errors::McpTwitterError;
+use super::expansions::{self, ExpandedTweet};
+use super::ids::parse_id;
+
+/// `max_results` accepted by the user timeline and mentions endpoints
+pub const TIMELINE_RESULTS: RangeInclusive<usize> = 5..=100;
+/// `max_results` accepted by the recent search endpoint
+pub const SEARCH_RESULTS: RangeInclusive<usize> = 10..=100;
+/// Pages fetched by a single call with `max_pages`
+pub const MAX_PAGES: usize = 10;
+
+pub type TweetsResponse = ApiResponse<Oauth1aToken, Vec<Tweet>, TweetsMeta>;
+
+/// Tweets of one or more pages, with the token for the page after them.
+#[derive(Debug, Serialize)]
+pub struct Page {
+    pub tweets: Vec<ExpandedTweet>,
+    pub result_count: usize,
+    /// Pass as `pagination_token` to continue, absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+}
+
+/// Bounds on the tweets returned, all optional.
+#[derive(Debug, Default)]
+pub struct Bounds {
+    pub since_id: Option<NumericId>,
+    pub until_id: Option<NumericId>,
+    pub start_time: Option<OffsetDateTime>,
+    pub end_time: Option<OffsetDateTime>,
+}
+
+impl Bounds {
+    /// Parses decimal string IDs and RFC 3339 times, checking that each
+    /// range is not empty.
+    pub fn parse(
+        since_id: Option<String>,
+        until_id: Option<String>,
+        start_time: Option<String>,
+        end_time: Option<String>,
+    ) -> Result<Self, McpTwitterError> {
+        let bounds = Bounds {
+            since_id: since_id
+                .map(|id| parse_id(&id).ok_or(McpTwitterError::InvalidLatestID(id)))
+                .transpose()?,
+            until_id: until_id
+                .map(|id| parse_id(&id).ok_or(McpTwitterError::InvalidUntilID(id)))
+                .transpose()?,
+            start_time: start_time
+                .map(|t| parse_time("start_time", &t))
+                .transpose()?,
+            end_time: end_time.map(|t| parse_time("end_time", &t)).transpose()?,
+        };
+
+        if let (Some(since), Some(until)) = (bounds.since_id, bounds.until_id)
+            && since.as_u64() >= until.as_u64()
+        {
+            return Err(McpTwitterError::InvalidInput(format!(
+                "latest_id {} must be lower than until_id {}",
+                since, until
+            )));
+        }
+        if let (Some(start), Some(end)) = (bounds.start_time, bounds.end_time)
+            && start >= end
+        {
+            return Err(McpTwitterError::InvalidInput(
+                "start_time must be before end_time".to_string(),
+            ));
+        }
+        Ok(bounds)
+    }
+}
+
+fn parse_time(name: &str, value: &str) -> Result<OffsetDateTime, McpTwitterError> {
+    OffsetDateTime::parse(value.trim(), &Rfc3339).map_err(|_| {
+        McpTwitterError::InvalidTime(format!(
+            "{} `{}`, expected RFC 3339 (e.g. 2025-01-31T12:00:00Z)",
+            name, value
+        ))
+    })
+}
+
+/// Checks `count` against the `max_results` an endpoint accepts.
+pub fn max_results(
+    count: Option<f64>,
+    range: RangeInclusive<usize>,
+) -> Result<Option<usize>, McpTwitterError> {
+    let Some(count) = count else {
+        return Ok(None);
+    };
+    if count.fract() != 0.0 || !(*range.start() as f64..=*range.end() as f64).contains(&count) {
+        return Err(McpTwitterError::InvalidCount(format!(
+            "{}, must be a whole number from {} to {}",
+            count,
+            range.start(),
+            range.end()
+        )));
+    }
+    Ok(Some(count as usize))
+}
+
+/// Checks `max_pages`, one page when unset.
+pub fn max_pages(max_pages: Option<f64>) -> Result<usize, McpTwitterError> {
+    let Some(pages) = max_pages else {
+        return Ok(1);
+    };
+    if pages.fract() != 0.0 || !(1.0..=MAX_PAGES as f64).contains(&pages) {
+        return Err(McpTwitterError::InvalidInput(format!(
+            "max_pages {} must be a whole number from 1 to {}",
+            pages, MAX_PAGES
+        )));
+    }
+    Ok(pages as usize)
+}
+
+/// Collects the tweets of `first` and the pages after it, up to `max_pages`
+/// pages in all, each joined with the `includes` of its page.
+pub async fn collect(first: TweetsResponse, max_pages: usize) -> Result<Page, McpTwitterError> {
+    let mut response = first;
+    let mut tweets = Vec::new();
+    let mut pages = 1;
+    loop {
+        let next = if pages < max_pages {
+            response
+                .next_page()
+                .await
+                .map_err(McpTwitterError::TwitterApiError)?
+        } else {
+            None
+        };
+        let payload = response.into_payload();
+        let next_token = payload.meta.and_then(|meta| meta.next_token);
+        tweets.extend(expansions::join(
+            payload.data.unwrap_or_default(),
+            payload.includes.as_ref(),
+        ));
+
+        match next {
+            Some(next) => {
+                response = next;
+                pages += 1;
+            }
+            None => {
+                return Ok(Page {
+                    result_count: tweets.len(),
+                    tweets,
+                    next_token,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_results() {
+        assert_eq!(max_results(None, TIMELINE_RESULTS).unwrap(), None);
+        assert_eq!(max_results(Some(5.0), TIMELINE_RESULTS).unwrap(), Some(5));
+        assert_eq!(max_results(Some(100.0), SEARCH_RESULTS).unwrap(), Some(100));
+        assert!(max_results(Some(5.0), SEARCH_RESULTS).is_err());
+        assert!(max_results(Some(101.0), TIMELINE_RESULTS).is_err());
+        assert!(max_results(Some(12.5), TIMELINE_RESULTS).is_err());
+        assert!(max_results(Some(-10.0), SEARCH_RESULTS).is_err());
+
+        assert_eq!(max_pages(None).unwrap(), 1);
+        assert_eq!(max_pages(Some(3.0)).unwrap(), 3);
+        assert!(max_pages(Some(0.0)).is_err());
+        assert!(max_pages(Some(11.0)).is_err());
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds = Bounds::parse(
+            Some("1460323737035677698".to_string()),
+            Some("1893713254098280765".to_string()),
+            Some("2025-01-01T00:00:00Z".to_string()),
+            Some("2025-01-31T12:00:00+01:00".to_string()),
+        )
+        .unwrap();
+        assert_eq!(bounds.until_id.unwrap().as_u64(), 1_893_713_254_098_280_765);
+        assert_eq!(bounds.end_time.unwrap().unix_timestamp(), 1_738_321_200);
+
+        // Empty ranges
+        assert!(
+            Bounds::parse(
+                Some("1893713254098280765".to_string()),
+                Some("1460323737035677698".to_string()),
+                None,
+                None
+            )
+            .is_err()
+        );
+        assert!(
+            Bounds::parse(
+                None,
+                None,
+                Some("2025-02-01T00:00:00Z".to_string()),
+                Some("2025-01-01T00:00:00Z".to_string())
+            )
+            .is_err()
+        );
+        // Not RFC 3339
+        assert!(Bounds::parse(None, None, Some("2025-01-01".to_string()), None).is_err());
+        assert!(Bounds::parse(None, Some("abc".to_string()), None, None).is_err());
+    }
+}
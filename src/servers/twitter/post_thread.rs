@@ -0,0 +1,196 @@
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+use serde::Serialize;
+use twitter_v2::{TwitterApi, authorization::Oauth1aToken, id::NumericId};
+
+use super::errors::McpTwitterError;
+use super::ids::parse_id;
+use super::text::{MAX_WEIGHTED_LENGTH, normalize};
+use super::thread::split_thread;
+use crate::secrets;
+
+#[derive(Debug, Serialize)]
+struct PostedTweet {
+    index: usize,
+    id: NumericId,
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Thread {
+    total: usize,
+    complete: bool,
+    posted: Vec<PostedTweet>,
+    /// Why posting stopped, with `resume_from` and `reply_to_tweet_id` to
+    /// post the rest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resume_from: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_to_tweet_id: Option<NumericId>,
+}
+
+#[tool(
+    name = "PostThread",
+    description = "Post a thread to Twitter. Splits text or segments into tweets of at most 280 weighted characters, as counted by ValidateTweet, at paragraph, sentence and word boundaries, optionally numbered 1/n, and posts them as a reply chain. Returns JSON with total, complete and the posted tweets (index, id, text). If a tweet fails, returns what was posted with error, resume_from and reply_to_tweet_id: call again with the same text, segments and numbered plus those two to post the rest. Required API and ACCESS values can be valued as 'null' if the info is not available.",
+    params(
+        text = "Text of the thread, split into tweets where needed",
+        segments = "Tweets of the thread instead of text, each split further if too long",
+        numbered = "Whether to end each tweet with its number, e.g. '2/5' (default: false)",
+        resume_from = "Index of the first tweet to post, from a failed call (default: 0)",
+        reply_to_tweet_id = "Tweet ID, as a string, the first posted tweet replies to. Required with resume_from"
+    )
+)]
+async fn post_thread_tool(
+    text: Option<String>,
+    segments: Option<Vec<String>>,
+    numbered: Option<bool>,
+    resume_from: Option<f64>,
+    reply_to_tweet_id: Option<String>,
+) -> Result<ToolResponseContent> {
+    let segments = match (text, segments) {
+        (Some(text), None) => vec![normalize(&text)],
+        (None, Some(segments)) => segments.iter().map(|s| normalize(s)).collect(),
+        _ => {
+            return Err(
+                McpTwitterError::InvalidInput("Set either text or segments".to_string()).into(),
+            );
+        }
+    };
+    let parts = split_thread(&segments, MAX_WEIGHTED_LENGTH, numbered.unwrap_or(false));
+    if parts.is_empty() {
+        return Err(McpTwitterError::InvalidInput("Thread cannot be empty".to_string()).into());
+    }
+
+    let resume_from = match resume_from {
+        None => 0,
+        Some(i) if i.fract() == 0.0 && i >= 0.0 && (i as usize) < parts.len() => i as usize,
+        Some(i) => {
+            return Err(McpTwitterError::InvalidInput(format!(
+                "resume_from {} must be a tweet index from 0 to {}",
+                i,
+                parts.len() - 1
+            ))
+            .into());
+        }
+    };
+    let mut previous = reply_to_tweet_id
+        .map(|id| parse_id(&id).ok_or(McpTwitterError::InvalidReplyID(id)))
+        .transpose()?;
+    if resume_from > 0 && previous.is_none() {
+        return Err(McpTwitterError::InvalidInput(
+            "reply_to_tweet_id is required with resume_from".to_string(),
+        )
+        .into());
+    }
+
+    let twitter_api_key = secrets::get("TWITTER_API_KEY")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterApiKey))?;
+    let twitter_api_secret = secrets::get("TWITTER_API_SECRET")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterApiSecret))?;
+    let twitter_access_token = secrets::get("TWITTER_ACCESS_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterAccessToken))?;
+    let twitter_access_token_secret = secrets::get("TWITTER_ACCESS_TOKEN_SECRET")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterAccessTokenSecret))?;
+
+    let api = TwitterApi::new(Oauth1aToken::new(
+        &twitter_api_key,
+        &twitter_api_secret,
+        &twitter_access_token,
+        &twitter_access_token_secret,
+    ));
+
+    let mut thread = Thread {
+        total: parts.len(),
+        complete: false,
+        posted: Vec::new(),
+        error: None,
+        resume_from: None,
+        reply_to_tweet_id: None,
+    };
+    for (index, part) in parts.into_iter().enumerate().skip(resume_from) {
+        let mut builder = api.post_tweet();
+        builder.text(part.clone());
+        if let Some(id) = previous {
+            builder.in_reply_to_tweet_id(id);
+        }
+
+        let posted = builder
+            .send()
+            .await
+            .map_err(McpTwitterError::TwitterApiError)
+            .and_then(|res| res.into_data().ok_or(McpTwitterError::TweetNotFound));
+        match posted {
+            Ok(tweet) => {
+                previous = Some(tweet.id);
+                thread.posted.push(PostedTweet {
+                    index,
+                    id: tweet.id,
+                    text: part,
+                });
+            }
+            // Nothing was posted yet, so there is nothing to resume
+            Err(e) if thread.posted.is_empty() && resume_from == 0 => return Err(e.into()),
+            Err(e) => {
+                thread.error = Some(e.to_string());
+                thread.resume_from = Some(index);
+                thread.reply_to_tweet_id = previous;
+                break;
+            }
+        }
+    }
+    thread.complete = thread.error.is_none();
+
+    Ok(tool_text_content!(
+        serde_json::to_string(&thread).map_err(McpTwitterError::ResponseSerializeError)?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dotenv::dotenv;
+
+    #[tokio::test]
+    async fn test_post_thread_tool() {
+        dotenv().ok();
+
+        // Check if any required env var is missing
+        if std::env::var("TWITTER_API_KEY").is_err()
+            || std::env::var("TWITTER_API_SECRET").is_err()
+            || std::env::var("TWITTER_ACCESS_TOKEN").is_err()
+            || std::env::var("TWITTER_ACCESS_TOKEN_SECRET").is_err()
+        {
+            println!("Skipping test_post_thread_tool: Missing required Twitter credentials");
+            return;
+        }
+
+        match post_thread_tool(
+            None,
+            Some(vec![
+                "Test thread from automated testing.".to_string(),
+                "Second tweet of the test thread.".to_string(),
+            ]),
+            Some(true),
+            None,
+            None,
+        )
+        .await
+        {
+            Ok(content) => {
+                println!("Thread result: {:?}", content);
+                assert!(format!("{:?}", content).contains("complete"));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
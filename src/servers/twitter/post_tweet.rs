@@ -4,21 +4,27 @@ use mcp_core_macros::tool;
 use twitter_v2::{TwitterApi, authorization::Oauth1aToken};
 
 use super::errors::McpTwitterError;
+use super::text;
+use crate::secrets;
 
 #[tool(
     name = "PostTweet",
-    description = "Post a tweet to Twitter. Required API and ACCESS values can be valued as 'null' if the info is not available.",
+    description = "Post a tweet to Twitter. Text over 280 weighted characters, as counted by ValidateTweet, is rejected before posting. Required API and ACCESS values can be valued as 'null' if the info is not available.",
     params(tweet = "Text to post on Twitter")
 )]
 async fn post_tweet_tool(tweet: String) -> Result<ToolResponseContent> {
-    let twitter_api_key =
-        std::env::var("TWITTER_API_KEY").map_err(|_| McpTwitterError::MissingTwitterApiKey)?;
-    let twitter_api_secret = std::env::var("TWITTER_API_SECRET")
-        .map_err(|_| McpTwitterError::MissingTwitterApiSecret)?;
-    let twitter_access_token = std::env::var("TWITTER_ACCESS_TOKEN")
-        .map_err(|_| McpTwitterError::MissingTwitterAccessToken)?;
-    let twitter_access_token_secret = std::env::var("TWITTER_ACCESS_TOKEN_SECRET")
-        .map_err(|_| McpTwitterError::MissingTwitterAccessTokenSecret)?;
+    let twitter_api_key = secrets::get("TWITTER_API_KEY")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterApiKey))?;
+    let twitter_api_secret = secrets::get("TWITTER_API_SECRET")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterApiSecret))?;
+    let twitter_access_token = secrets::get("TWITTER_ACCESS_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterAccessToken))?;
+    let twitter_access_token_secret = secrets::get("TWITTER_ACCESS_TOKEN_SECRET")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterAccessTokenSecret))?;
 
     let api = TwitterApi::new(Oauth1aToken::new(
         &twitter_api_key,
@@ -27,13 +33,19 @@ async fn post_tweet_tool(tweet: String) -> Result<ToolResponseContent> {
         &twitter_access_token_secret,
     ));
 
-    if tweet.is_empty() {
+    if tweet.trim().is_empty() {
         return Err(McpTwitterError::InvalidInput("Tweet cannot be empty".to_string()).into());
     }
+    let checked = text::parse(&tweet);
+    if !checked.valid {
+        return Err(
+            McpTwitterError::TweetTooLong(checked.weighted_length, checked.max_length).into(),
+        );
+    }
 
     let tweet = api
         .post_tweet()
-        .text(tweet)
+        .text(text::normalize(&tweet))
         .send()
         .await
         .map_err(McpTwitterError::TwitterApiError)?
@@ -67,7 +79,7 @@ mod tests {
         match post_tweet_tool("Test tweet from automated testing".to_string()).await {
             Ok(content) => {
                 println!("Tweet result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -1,27 +1,37 @@
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
-use twitter_v2::{TwitterApi, authorization::Oauth1aToken, id::NumericId};
+use twitter_v2::{TwitterApi, authorization::Oauth1aToken};
 
 use super::errors::McpTwitterError;
+use super::ids::parse_id;
+use super::text;
+use crate::secrets;
 
 #[tool(
     name = "ReplyToTweet",
-    description = "Reply a tweet to Twitter. Required API and ACCESS values can be valued as 'null' if the info is not available.",
+    description = "Reply a tweet to Twitter. Text over 280 weighted characters, as counted by ValidateTweet, is rejected before posting. Required API and ACCESS values can be valued as 'null' if the info is not available.",
     params(
         reply = "Text for Twitter reply",
-        reply_to_tweet_id = "Tweet ID to reply to"
+        reply_to_tweet_id = "Tweet ID to reply to, as a string"
     )
 )]
-async fn reply_to_tweet_tool(reply: String, reply_to_tweet_id: f64) -> Result<ToolResponseContent> {
-    let twitter_api_key =
-        std::env::var("TWITTER_API_KEY").map_err(|_| McpTwitterError::MissingTwitterApiKey)?;
-    let twitter_api_secret = std::env::var("TWITTER_API_SECRET")
-        .map_err(|_| McpTwitterError::MissingTwitterApiSecret)?;
-    let twitter_access_token = std::env::var("TWITTER_ACCESS_TOKEN")
-        .map_err(|_| McpTwitterError::MissingTwitterAccessToken)?;
-    let twitter_access_token_secret = std::env::var("TWITTER_ACCESS_TOKEN_SECRET")
-        .map_err(|_| McpTwitterError::MissingTwitterAccessTokenSecret)?;
+async fn reply_to_tweet_tool(
+    reply: String,
+    reply_to_tweet_id: String,
+) -> Result<ToolResponseContent> {
+    let twitter_api_key = secrets::get("TWITTER_API_KEY")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterApiKey))?;
+    let twitter_api_secret = secrets::get("TWITTER_API_SECRET")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterApiSecret))?;
+    let twitter_access_token = secrets::get("TWITTER_ACCESS_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterAccessToken))?;
+    let twitter_access_token_secret = secrets::get("TWITTER_ACCESS_TOKEN_SECRET")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterAccessTokenSecret))?;
 
     let api = TwitterApi::new(Oauth1aToken::new(
         &twitter_api_key,
@@ -30,21 +40,23 @@ async fn reply_to_tweet_tool(reply: String, reply_to_tweet_id: f64) -> Result<To
         &twitter_access_token_secret,
     ));
 
-    if reply.is_empty() {
+    if reply.trim().is_empty() {
         return Err(McpTwitterError::InvalidInput("Reply cannot be empty".to_string()).into());
     }
-
-    let u64_reply_to_tweet_id = reply_to_tweet_id as u64;
-    if u64_reply_to_tweet_id == 0 {
-        return Err(McpTwitterError::InvalidReplyID(reply_to_tweet_id).into());
+    let checked = text::parse(&reply);
+    if !checked.valid {
+        return Err(
+            McpTwitterError::TweetTooLong(checked.weighted_length, checked.max_length).into(),
+        );
     }
 
-    let numeric_reply_to_tweet_id = NumericId::new(u64_reply_to_tweet_id);
+    let numeric_reply_to_tweet_id = parse_id(&reply_to_tweet_id)
+        .ok_or_else(|| McpTwitterError::InvalidReplyID(reply_to_tweet_id.clone()))?;
 
     let tweet = api
         .post_tweet()
         .in_reply_to_tweet_id(numeric_reply_to_tweet_id)
-        .text(reply)
+        .text(text::normalize(&reply))
         .send()
         .await
         .map_err(McpTwitterError::TwitterApiError)?
@@ -76,7 +88,7 @@ mod tests {
         }
 
         // Note: This test requires a valid tweet ID to reply to
-        let test_tweet_id = 1234567890.0; // Replace with a real tweet ID for actual testing
+        let test_tweet_id = "1893713254098280765".to_string(); // Replace with a real tweet ID for actual testing
         match reply_to_tweet_tool(
             "Test reply from automated testing".to_string(),
             test_tweet_id,
@@ -85,7 +97,7 @@ mod tests {
         {
             Ok(content) => {
                 println!("Reply result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
@@ -1,4 +1,7 @@
 use super::errors::McpTwitterError;
+use super::expansions::{EXPANSIONS, MEDIA_FIELDS, TWEET_FIELDS, USER_FIELDS};
+use super::paging::{self, Bounds, SEARCH_RESULTS};
+use crate::secrets;
 use anyhow::Result;
 use mcp_core::{tool_text_content, types::ToolResponseContent};
 use mcp_core_macros::tool;
@@ -6,26 +9,45 @@ use twitter_v2::{TwitterApi, authorization::Oauth1aToken, query};
 
 #[tool(
     name = "SearchTweets",
-    description = "Search tweets from Twitter. Required API and ACCESS values can be valued as 'null' if the info is not available.",
+    description = "Search tweets from Twitter. Returns JSON with tweets, each with its author, public_metrics, media and referenced tweets, result_count and next_token (when more results exist). Required API and ACCESS values can be valued as 'null' if the info is not available.",
     params(
         query = "Search query for Twitter search",
-        count = "The max count of tweets to be fetched",
-        sort_order = "The Twitter sort method used for the search"
+        count = "The max count of tweets per page, from 10 to 100",
+        sort_order = "The Twitter sort method used for the search",
+        until_id = "The Tweet ID, as a string, to fetch tweets before",
+        start_time = "The oldest time to fetch tweets from, in RFC 3339 (e.g. '2025-01-31T12:00:00Z'), within the last 7 days",
+        end_time = "The newest time to fetch tweets until, in RFC 3339",
+        pagination_token = "The next_token of a previous call, to fetch the page after it",
+        max_pages = "Pages to fetch, following next_token (default: 1, at most 10)"
     )
 )]
+#[allow(clippy::too_many_arguments)]
 async fn search_tweets_tool(
     query: String,
     count: Option<f64>,
     sort_order: Option<String>,
+    until_id: Option<String>,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    pagination_token: Option<String>,
+    max_pages: Option<f64>,
 ) -> Result<ToolResponseContent> {
-    let twitter_api_key =
-        std::env::var("TWITTER_API_KEY").map_err(|_| McpTwitterError::MissingTwitterApiKey)?;
-    let twitter_api_secret = std::env::var("TWITTER_API_SECRET")
-        .map_err(|_| McpTwitterError::MissingTwitterApiSecret)?;
-    let twitter_access_token = std::env::var("TWITTER_ACCESS_TOKEN")
-        .map_err(|_| McpTwitterError::MissingTwitterAccessToken)?;
-    let twitter_access_token_secret = std::env::var("TWITTER_ACCESS_TOKEN_SECRET")
-        .map_err(|_| McpTwitterError::MissingTwitterAccessTokenSecret)?;
+    let max_results = paging::max_results(count, SEARCH_RESULTS)?;
+    let max_pages = paging::max_pages(max_pages)?;
+    let bounds = Bounds::parse(None, until_id, start_time, end_time)?;
+
+    let twitter_api_key = secrets::get("TWITTER_API_KEY")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterApiKey))?;
+    let twitter_api_secret = secrets::get("TWITTER_API_SECRET")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterApiSecret))?;
+    let twitter_access_token = secrets::get("TWITTER_ACCESS_TOKEN")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterAccessToken))?;
+    let twitter_access_token_secret = secrets::get("TWITTER_ACCESS_TOKEN_SECRET")
+        .await
+        .map_err(|e| e.or_missing(McpTwitterError::MissingTwitterAccessTokenSecret))?;
 
     let api = TwitterApi::new(Oauth1aToken::new(
         &twitter_api_key,
@@ -40,11 +62,13 @@ async fn search_tweets_tool(
 
     let mut builder = api.get_tweets_search_recent(query);
 
-    if let Some(c) = count {
-        if c < 0.0 {
-            return Err(McpTwitterError::InvalidCount(c).into());
-        }
-        builder.max_results(c as usize);
+    builder
+        .tweet_fields(TWEET_FIELDS)
+        .user_fields(USER_FIELDS)
+        .media_fields(MEDIA_FIELDS)
+        .expansions(EXPANSIONS);
+    if let Some(max_results) = max_results {
+        builder.max_results(max_results);
     }
 
     if let Some(s) = sort_order {
@@ -55,12 +79,24 @@ async fn search_tweets_tool(
         };
     }
 
-    let tweets = builder
+    if let Some(id) = bounds.until_id {
+        builder.until_id(id);
+    }
+    if let Some(time) = bounds.start_time {
+        builder.start_time(time);
+    }
+    if let Some(time) = bounds.end_time {
+        builder.end_time(time);
+    }
+    if let Some(token) = pagination_token {
+        builder.pagination_token(&token);
+    }
+
+    let first = builder
         .send()
         .await
-        .map_err(McpTwitterError::TwitterApiError)?
-        .into_data()
-        .ok_or_else(|| McpTwitterError::TweetsNotFound)?;
+        .map_err(McpTwitterError::TwitterApiError)?;
+    let tweets = paging::collect(first, max_pages).await?;
 
     Ok(tool_text_content!(
         serde_json::to_string(&tweets).map_err(McpTwitterError::ResponseSerializeError)?
@@ -88,14 +124,19 @@ mod tests {
 
         match search_tweets_tool(
             "rust programming".to_string(),
-            Some(5.0),
+            Some(10.0),
             Some("recency".to_string()),
+            None,
+            None,
+            None,
+            None,
+            None,
         )
         .await
         {
             Ok(content) => {
                 println!("Search result: {:?}", content);
-                assert!(format!("{:?}", content).len() > 0);
+                assert!(!format!("{:?}", content).is_empty());
             }
             Err(e) => {
                 println!("Error: {:#?}", e);
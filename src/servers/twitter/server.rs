@@ -1,9 +1,17 @@
 use super::*;
+use crate::server::Server;
 use mcp_core::protocol::Protocol;
-use mcp_core::server::Server;
 use mcp_core::types::ServerCapabilities;
 use serde_json::json;
 
+/// Credentials resolved at start-up so a broken secret reference fails fast
+pub const SECRETS: &[&str] = &[
+    "TWITTER_API_KEY",
+    "TWITTER_API_SECRET",
+    "TWITTER_ACCESS_TOKEN",
+    "TWITTER_ACCESS_TOKEN_SECRET",
+];
+
 pub fn protocol() -> Protocol {
     Server::builder("Twitter".to_string(), "0.1.0".to_string())
         .capabilities(ServerCapabilities {
@@ -12,8 +20,10 @@ pub fn protocol() -> Protocol {
         })
         .register_tool(GetMentionsTool::tool(), GetMentionsTool::call())
         .register_tool(GetTimelineTool::tool(), GetTimelineTool::call())
+        .register_tool(PostThreadTool::tool(), PostThreadTool::call())
         .register_tool(PostTweetTool::tool(), PostTweetTool::call())
         .register_tool(ReplyToTweetTool::tool(), ReplyToTweetTool::call())
         .register_tool(SearchTweetsTool::tool(), SearchTweetsTool::call())
+        .register_tool(ValidateTweetTool::tool(), ValidateTweetTool::call())
         .build()
 }
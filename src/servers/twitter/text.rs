@@ -0,0 +1,257 @@
+//! Tweet length as X counts it.
+//!
+//! Follows the twitter-text v3 rules: text is NFC normalized, characters
+//! in the Latin and general punctuation ranges weigh 1 and all others 2, a URL
+//! weighs 23 however long it is, and an emoji sequence weighs 2 however many
+//! code points it has.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use unicode_normalization::UnicodeNormalization;
+
+/// Longest weighted length X accepts
+pub const MAX_WEIGHTED_LENGTH: usize = 280;
+/// Weight of any URL, the length of a t.co link
+pub const URL_WEIGHT: usize = 23;
+const ELLIPSIS: &str = "…";
+
+/// Code points weighing 1, everything else weighs 2
+const LIGHT_RANGES: [(u32, u32); 4] = [
+    (0x0000, 0x10FF),
+    (0x2000, 0x200D),
+    (0x2010, 0x201F),
+    (0x2032, 0x2037),
+];
+
+lazy_static! {
+    /// Links with a scheme, `www.` or a common top-level domain
+    static ref URL: Regex = Regex::new(
+        r#"(?i)\b(?:https?://[^\s<>"]+|www\.[^\s<>"]+|(?:[a-z0-9](?:[a-z0-9-]*[a-z0-9])?\.)+(?:com|org|net|edu|gov|io|co|ai|app|dev|me|ly|gg|tv|info|xyz|uk|de|fr|jp)\b(?:/[^\s<>"]*)?)"#
+    )
+    .unwrap();
+}
+
+/// The weighted length of a tweet and whether X accepts it.
+#[derive(Debug, Serialize)]
+pub struct TweetText {
+    pub weighted_length: usize,
+    pub max_length: usize,
+    /// Negative when the text is too long
+    pub remaining: i64,
+    pub valid: bool,
+    /// The text as X counts and stores it, when it differs from the input
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalized: Option<String>,
+    /// The longest start of the text that fits, cut between words and ending
+    /// with `…`, when the text is too long
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_truncation: Option<String>,
+}
+
+/// Normalizes `text` to NFC, as X does before counting.
+pub fn normalize(text: &str) -> String {
+    text.nfc().collect()
+}
+
+/// Weighted length of `text`, counted after normalization.
+pub fn weighted_length(text: &str) -> usize {
+    units(&normalize(text)).iter().map(|u| u.weight).sum()
+}
+
+/// Counts `text` and suggests a truncation when it is too long.
+pub fn parse(text: &str) -> TweetText {
+    let normalized = normalize(text);
+    let weighted_length = units(&normalized).iter().map(|u| u.weight).sum();
+    let valid = !normalized.trim().is_empty() && weighted_length <= MAX_WEIGHTED_LENGTH;
+    TweetText {
+        weighted_length,
+        max_length: MAX_WEIGHTED_LENGTH,
+        remaining: MAX_WEIGHTED_LENGTH as i64 - weighted_length as i64,
+        valid,
+        suggested_truncation: (weighted_length > MAX_WEIGHTED_LENGTH)
+            .then(|| truncate(&normalized, MAX_WEIGHTED_LENGTH)),
+        normalized: (normalized != text).then_some(normalized),
+    }
+}
+
+/// The longest start of `text` that fits `max` with `…` appended, cut after
+/// the last whitespace when there is one. URLs and emoji are never cut.
+pub fn truncate(text: &str, max: usize) -> String {
+    let text = normalize(text);
+    let budget = max.saturating_sub(weighted_length(ELLIPSIS));
+    let mut end = 0;
+    let mut last_space = None;
+    let mut total = 0;
+    for unit in units(&text) {
+        if total + unit.weight > budget {
+            break;
+        }
+        total += unit.weight;
+        if text[unit.start..unit.end].chars().all(char::is_whitespace) {
+            last_space = Some(unit.start);
+        }
+        end = unit.end;
+    }
+    if end == text.len() {
+        return text;
+    }
+    let end = last_space.filter(|&i| i > 0).unwrap_or(end);
+    format!("{}{}", text[..end].trim_end(), ELLIPSIS)
+}
+
+/// A span of text counted as a whole.
+#[derive(Debug, PartialEq)]
+struct Unit {
+    start: usize,
+    end: usize,
+    weight: usize,
+}
+
+fn units(text: &str) -> Vec<Unit> {
+    let urls: Vec<(usize, usize)> = URL
+        .find_iter(text)
+        .filter(|m| {
+            // Not the domain of an email address or part of a longer word
+            !text[..m.start()]
+                .chars()
+                .next_back()
+                .is_some_and(|c| matches!(c, '@' | '.' | '-' | '_'))
+        })
+        .map(|m| {
+            let url = m.as_str().trim_end_matches(|c| ".,;:!?)]'\"".contains(c));
+            (m.start(), m.start() + url.len())
+        })
+        .collect();
+
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let end_of = |i: usize| chars.get(i).map_or(text.len(), |&(at, _)| at);
+    let mut units = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = chars[i].0;
+        if let Some(&(_, url_end)) = urls.iter().find(|(s, _)| *s == start) {
+            units.push(Unit {
+                start,
+                end: url_end,
+                weight: URL_WEIGHT,
+            });
+            while i < chars.len() && chars[i].0 < url_end {
+                i += 1;
+            }
+            continue;
+        }
+
+        let emoji_end = emoji_sequence(&chars, i);
+        if emoji_end > i + 1 || is_pictographic(chars[i].1) {
+            units.push(Unit {
+                start,
+                end: end_of(emoji_end),
+                weight: 2,
+            });
+            i = emoji_end;
+            continue;
+        }
+
+        let code = chars[i].1 as u32;
+        let light = LIGHT_RANGES
+            .iter()
+            .any(|&(low, high)| (low..=high).contains(&code));
+        units.push(Unit {
+            start,
+            end: end_of(i + 1),
+            weight: if light { 1 } else { 2 },
+        });
+        i += 1;
+    }
+    units
+}
+
+/// End of the emoji sequence starting at `i`: a flag, or a base followed by
+/// variation selectors, keycaps, skin tones, tags and `ZWJ` joined emoji.
+/// Just past `i` when there is no sequence.
+fn emoji_sequence(chars: &[(usize, char)], i: usize) -> usize {
+    let is_regional = |c: char| ('\u{1F1E6}'..='\u{1F1FF}').contains(&c);
+    let base = chars[i].1;
+    if is_regional(base) {
+        return match chars.get(i + 1) {
+            Some(&(_, next)) if is_regional(next) => i + 2,
+            _ => i + 1,
+        };
+    }
+
+    let mut end = i + 1;
+    while let Some(&(_, c)) = chars.get(end) {
+        match c {
+            '\u{FE0F}' | '\u{20E3}' | '\u{1F3FB}'..='\u{1F3FF}' | '\u{E0020}'..='\u{E007F}' => {
+                end += 1;
+            }
+            '\u{200D}' if chars.get(end + 1).is_some_and(|&(_, c)| is_pictographic(c)) => {
+                end += 2;
+            }
+            _ => break,
+        }
+    }
+    end
+}
+
+fn is_pictographic(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2300..=0x23FF | 0x2B00..=0x2BFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weighted_length() {
+        assert_eq!(weighted_length("Hello, world!"), 13);
+        // CJK weighs 2
+        assert_eq!(weighted_length("こんにちは"), 10);
+        // Curly quotes and dashes are in the light ranges
+        assert_eq!(weighted_length("“quoted” — ok"), 13);
+        // URLs weigh 23, trailing punctuation is not part of them
+        assert_eq!(
+            weighted_length("See https://example.com/a/very/long/path/that/goes/on?q=1."),
+            4 + URL_WEIGHT + 1
+        );
+        assert_eq!(weighted_length("docs at example.com"), 8 + URL_WEIGHT);
+        assert_eq!(weighted_length("me@example.com"), 14);
+        // Emoji sequences weigh 2
+        assert_eq!(weighted_length("👍"), 2);
+        assert_eq!(weighted_length("👍🏽"), 2);
+        assert_eq!(weighted_length("❤️"), 2);
+        assert_eq!(weighted_length("👩‍👩‍👧‍👦"), 2);
+        assert_eq!(weighted_length("🇯🇵🇫🇷"), 4);
+        assert_eq!(weighted_length("1️⃣"), 2);
+        // Counted after NFC: e + combining acute is one character
+        assert_eq!(weighted_length("e\u{301}"), 1);
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = "word ".repeat(60);
+        let parsed = parse(&text);
+        assert_eq!(parsed.weighted_length, 300);
+        assert_eq!(parsed.remaining, -20);
+        assert!(!parsed.valid);
+        let truncated = parsed.suggested_truncation.unwrap();
+        assert!(truncated.ends_with("word…"));
+        assert!(weighted_length(&truncated) <= MAX_WEIGHTED_LENGTH);
+
+        let parsed = parse("Cafe\u{301} ☕");
+        assert!(parsed.valid);
+        assert_eq!(parsed.weighted_length, 7);
+        assert_eq!(parsed.normalized.as_deref(), Some("Café ☕"));
+        assert!(parsed.suggested_truncation.is_none());
+        assert!(!parse("   ").valid);
+
+        // URLs are kept whole or left out
+        let url = "https://example.com/".to_string() + &"x".repeat(300);
+        assert_eq!(
+            truncate(&format!("{} {}", "a".repeat(270), url), 280),
+            format!("{}…", "a".repeat(270))
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use super::text::weighted_length as length;
+
+/// Splits segments into tweets of at most `max` weighted length, breaking between
+/// paragraphs, then sentences, then words. Each segment starts a new tweet.
+/// When `numbered`, tweets end with ` i/n` and the room for it is kept free.
+pub fn split_thread(segments: &[String], max: usize, numbered: bool) -> Vec<String> {
+    let split_all =
+        |max: usize| -> Vec<String> { segments.iter().flat_map(|s| split(s, max)).collect() };
+    if !numbered {
+        return split_all(max);
+    }
+
+    // Numbering takes room, which can make more parts and longer numbers
+    let suffix = |total: usize| length(&format!(" {0}/{0}", total));
+    let mut total = 1;
+    let mut parts = split_all(max.saturating_sub(suffix(total)));
+    while suffix(parts.len()) > suffix(total) {
+        total = parts.len();
+        parts = split_all(max.saturating_sub(suffix(total)));
+    }
+    if parts.len() == 1 {
+        return split_all(max);
+    }
+    let total = parts.len();
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(i, part)| format!("{} {}/{}", part, i + 1, total))
+        .collect()
+}
+
+/// Splits `text` into parts of at most `max` weighted length.
+pub fn split(text: &str, max: usize) -> Vec<String> {
+    let max = max.max(1);
+    let mut parts = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        for (i, sentence) in sentences(paragraph).into_iter().enumerate() {
+            let separator = match (current.is_empty(), i) {
+                (true, _) => "",
+                (false, 0) => "\n\n",
+                (false, _) => " ",
+            };
+            if length(&format!("{}{}{}", current, separator, sentence)) <= max {
+                current.push_str(separator);
+                current.push_str(sentence);
+                continue;
+            }
+            if !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+            if length(sentence) <= max {
+                current.push_str(sentence);
+                continue;
+            }
+
+            // A sentence longer than a tweet is broken between words, and a
+            // word longer than a tweet wherever it has to be
+            for word in sentence.split_whitespace() {
+                for piece in pieces(word, max) {
+                    let separator = if current.is_empty() { "" } else { " " };
+                    if length(&format!("{}{}{}", current, separator, piece)) > max {
+                        parts.push(std::mem::take(&mut current));
+                    } else {
+                        current.push_str(separator);
+                    }
+                    current.push_str(&piece);
+                }
+            }
+        }
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+/// Sentences of a paragraph, ending after `.`, `!`, `?` or `…` and any
+/// closing quotes or brackets that are followed by whitespace.
+fn sentences(paragraph: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = paragraph.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?' | '…') {
+            continue;
+        }
+        while let Some(&(_, next)) = chars.peek() {
+            if matches!(
+                next,
+                '.' | '!' | '?' | '…' | '"' | '\'' | ')' | ']' | '”' | '’'
+            ) {
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        if let Some(&(end, next)) = chars.peek()
+            && next.is_whitespace()
+        {
+            sentences.push(paragraph[start..end].trim());
+            start = end;
+        }
+    }
+    sentences.push(paragraph[start..].trim());
+    sentences.retain(|s| !s.is_empty());
+    sentences
+}
+
+/// `word` whole when it fits, otherwise cut into pieces that do.
+fn pieces(word: &str, max: usize) -> Vec<String> {
+    if length(word) <= max {
+        return vec![word.to_string()];
+    }
+    let mut pieces = vec![String::new()];
+    for c in word.chars() {
+        let piece = pieces.last_mut().unwrap();
+        piece.push(c);
+        if length(piece) > max && piece.chars().count() > 1 {
+            piece.pop();
+            pieces.push(c.to_string());
+        }
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::servers::twitter::text::MAX_WEIGHTED_LENGTH;
+
+    #[test]
+    fn test_split() {
+        let text = "First sentence here. Second one is a bit longer! Third?\n\nNew paragraph.";
+        assert_eq!(
+            split(text, 40),
+            [
+                "First sentence here.",
+                "Second one is a bit longer! Third?",
+                "New paragraph."
+            ]
+        );
+        assert_eq!(split(text, 500), [text]);
+        // Long sentences break between words, long words anywhere
+        assert_eq!(
+            split("one two three four five", 9),
+            ["one two", "three", "four five"]
+        );
+        assert_eq!(split("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+        // Lengths are weighted: CJK counts 2, a URL 23
+        assert_eq!(split("日本語のテキスト", 6), ["日本語", "のテキ", "スト"]);
+        assert_eq!(
+            split("Read https://example.com/a/long/path/to/a/page now.", 33),
+            ["Read https://example.com/a/long/path/to/a/page now."]
+        );
+        assert_eq!(
+            split("Read https://example.com/a/long/path/to/a/page now.", 32),
+            ["Read https://example.com/a/long/path/to/a/page", "now."]
+        );
+        // Decimals and closing quotes stay in their sentence
+        assert_eq!(
+            sentences("Pi is 3.14. \"Really?\" Yes."),
+            ["Pi is 3.14.", "\"Really?\"", "Yes."]
+        );
+    }
+
+    #[test]
+    fn test_split_thread() {
+        let sentence = "This sentence is exactly 39 chars long.";
+        assert_eq!(length(sentence), 39);
+        // Seven sentences fit a tweet, six with room for numbering
+        let text = vec![sentence; 21].join(" ");
+
+        let parts = split_thread(std::slice::from_ref(&text), MAX_WEIGHTED_LENGTH, false);
+        assert!(parts.iter().all(|p| length(p) <= MAX_WEIGHTED_LENGTH));
+        assert_eq!(parts.len(), 3);
+
+        let numbered = split_thread(&[text], MAX_WEIGHTED_LENGTH, true);
+        assert!(numbered.iter().all(|p| length(p) <= MAX_WEIGHTED_LENGTH));
+        assert_eq!(numbered.len(), 4);
+        assert!(numbered[0].ends_with(" 1/4"));
+        assert!(numbered[3].ends_with(" 4/4"));
+
+        // Segments start new tweets, and a single tweet is not numbered
+        let segments = ["Intro.".to_string(), "Details.".to_string()];
+        assert_eq!(
+            split_thread(&segments, MAX_WEIGHTED_LENGTH, true),
+            ["Intro. 1/2", "Details. 2/2"]
+        );
+        assert_eq!(
+            split_thread(&segments[..1], MAX_WEIGHTED_LENGTH, true),
+            ["Intro."]
+        );
+    }
+}
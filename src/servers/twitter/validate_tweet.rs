@@ -0,0 +1,38 @@
+use anyhow::Result;
+use mcp_core::{tool_text_content, types::ToolResponseContent};
+use mcp_core_macros::tool;
+
+use super::errors::McpTwitterError;
+use super::text;
+
+#[tool(
+    name = "ValidateTweet",
+    description = "Check tweet text against X's length rules without posting it. URLs count as 23 characters, CJK characters and emoji as 2, and text is NFC normalized first. Returns JSON with weighted_length, max_length, remaining, valid, normalized (when normalization changed the text) and suggested_truncation (when too long).",
+    params(text = "Text of the tweet to check")
+)]
+async fn validate_tweet_tool(text: String) -> Result<ToolResponseContent> {
+    Ok(tool_text_content!(
+        serde_json::to_string(&text::parse(&text))
+            .map_err(McpTwitterError::ResponseSerializeError)?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_validate_tweet_tool() {
+        match validate_tweet_tool("Ship it 🚀 https://example.com/release/notes".to_string()).await
+        {
+            Ok(content) => {
+                println!("Validation result: {:?}", content);
+                assert!(format!("{:?}", content).contains("\\\"weighted_length\\\":34"));
+            }
+            Err(e) => {
+                println!("Error: {:#?}", e);
+                panic!("Test failed with error: {:?}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Graceful shutdown: stop accepting tool calls and let running ones finish
+//! before the process exits.
+
+use lazy_static::lazy_static;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::time::{Instant, sleep};
+
+/// Default time in seconds to wait for in-flight tool calls after a shutdown signal
+pub const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 30;
+
+lazy_static! {
+    static ref STATE: ShutdownState = ShutdownState::default();
+}
+
+#[derive(Default)]
+pub struct ShutdownState {
+    draining: AtomicBool,
+    in_flight: AtomicUsize,
+}
+
+/// Marks a tool call as running until dropped.
+pub struct CallGuard<'a> {
+    state: &'a ShutdownState,
+}
+
+impl Drop for CallGuard<'_> {
+    fn drop(&mut self) {
+        self.state.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ShutdownState {
+    pub fn begin_call(&self) -> Option<CallGuard<'_>> {
+        if self.is_draining() {
+            return None;
+        }
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = CallGuard { state: self };
+
+        // Shutdown may have started between the check and the increment
+        if self.is_draining() {
+            return None;
+        }
+        Some(guard)
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Stops new calls and waits for running ones. Returns the number of calls
+    /// still running when the timeout expired.
+    pub async fn drain(&self, timeout: Duration) -> usize {
+        self.draining.store(true, Ordering::SeqCst);
+
+        let deadline = Instant::now() + timeout;
+        while self.in_flight() > 0 && Instant::now() < deadline {
+            sleep(Duration::from_millis(50)).await;
+        }
+        self.in_flight()
+    }
+}
+
+/// Registers a running tool call, or returns `None` once shutdown has started.
+pub fn begin_call() -> Option<CallGuard<'static>> {
+    STATE.begin_call()
+}
+
+pub async fn drain(timeout: Duration) -> usize {
+    STATE.drain(timeout).await
+}
+
+/// Resolves with the name of the first SIGINT or SIGTERM received.
+pub async fn wait_for_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => tokio::select! {
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+                _ = sigterm.recv() => "SIGTERM",
+            },
+            Err(e) => {
+                tracing::warn!("Failed to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "SIGINT"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_rejects_new_calls_and_waits() {
+        let state: &'static ShutdownState = Box::leak(Box::default());
+        let running = state.begin_call().unwrap();
+        assert_eq!(state.in_flight(), 1);
+
+        let finisher = tokio::spawn(async move {
+            sleep(Duration::from_millis(100)).await;
+            drop(running);
+        });
+
+        let remaining = state.drain(Duration::from_secs(5)).await;
+        finisher.await.unwrap();
+
+        assert_eq!(remaining, 0);
+        assert!(state.begin_call().is_none());
+        assert_eq!(state.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_drain_timeout() {
+        let state = ShutdownState::default();
+        let _running = state.begin_call().unwrap();
+
+        assert_eq!(state.drain(Duration::from_millis(100)).await, 1);
+    }
+}